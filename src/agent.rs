@@ -0,0 +1,38 @@
+#[cfg(feature = "native")]
+use serde::de::IgnoredAny;
+use serde::Serialize;
+
+#[cfg(feature = "native")]
+use crate::client::Client;
+#[cfg(feature = "native")]
+use crate::Result;
+
+/// 应用设置, 未指定的字段保持不变
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AgentSettings {
+    pub agentid: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub report_location_flag: Option<u8>, // 是否打开地理位置上报 0: 不上报, 1: 进入会话上报
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logo_mediaid: Option<String>, // 通过上传临时素材获得的 media_id
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redirect_domain: Option<String>, // 可信域名, 不带 http 前缀
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub isreportenter: Option<u8>, // 是否上报用户进入应用事件 0: 不接收, 1: 接收
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub home_url: Option<String>, // 应用主页 url
+}
+
+/// 应用管理
+#[cfg(feature = "native")]
+impl Client {
+    /// 设置应用
+    pub async fn set_agent(&self, settings: &AgentSettings) -> Result<()> {
+        let _: IgnoredAny = self.post("/cgi-bin/agent/set", settings).await?;
+        Ok(())
+    }
+}
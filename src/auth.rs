@@ -0,0 +1,281 @@
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+
+use crate::client::Client;
+use crate::{Error, Result};
+
+static OAUTH2_URL: &str = "https://open.weixin.qq.com/connect/oauth2/authorize";
+static QR_CONNECT_URL: &str = "https://open.work.weixin.qq.com/wwopen/sso/qrConnect";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    Base,        // 静默授权, 可获取成员的基础信息
+    PrivateInfo, // 手动授权, 可获取成员的详细信息, 包含头像, 二维码等敏感信息, 需要指定 agent_id
+}
+
+impl Scope {
+    fn desc(&self) -> &'static str {
+        match self {
+            Scope::Base => "snsapi_base",
+            Scope::PrivateInfo => "snsapi_privateinfo",
+        }
+    }
+}
+
+/// 构造网页授权链接
+pub struct AuthorizeUrlBuilder {
+    corp_id: String,
+    redirect_uri: String,
+    scope: Scope,
+    state: Option<String>,
+    agent_id: Option<u64>,
+}
+
+impl AuthorizeUrlBuilder {
+    pub fn new(corp_id: impl ToString, redirect_uri: impl ToString) -> Self {
+        AuthorizeUrlBuilder {
+            corp_id: corp_id.to_string(),
+            redirect_uri: redirect_uri.to_string(),
+            scope: Scope::Base,
+            state: None,
+            agent_id: None,
+        }
+    }
+
+    pub fn scope(mut self, scope: Scope) -> Self {
+        self.scope = scope;
+        self
+    }
+
+    /// 重定向后会带上 state 参数, 长度不可超过128个字节
+    pub fn state(mut self, state: impl ToString) -> Self {
+        self.state = Some(state.to_string());
+        self
+    }
+
+    pub fn agent_id(mut self, agent_id: u64) -> Self {
+        self.agent_id = Some(agent_id);
+        self
+    }
+
+    pub fn build(self) -> String {
+        let mut url = Url::parse(OAUTH2_URL).unwrap();
+        {
+            let mut query = url.query_pairs_mut();
+            query
+                .append_pair("appid", &self.corp_id)
+                .append_pair("redirect_uri", &self.redirect_uri)
+                .append_pair("response_type", "code")
+                .append_pair("scope", self.scope.desc());
+            if let Some(state) = &self.state {
+                query.append_pair("state", state);
+            }
+            if let Some(agent_id) = self.agent_id {
+                query.append_pair("agentid", &agent_id.to_string());
+            }
+        }
+        url.set_fragment(Some("wechat_redirect"));
+        url.into()
+    }
+}
+
+/// 构造企业微信扫码登录链接
+pub struct QrConnectUrlBuilder {
+    corp_id: String,
+    agent_id: u64,
+    redirect_uri: String,
+    state: Option<String>,
+    lang: Option<String>,
+}
+
+impl QrConnectUrlBuilder {
+    pub fn new(corp_id: impl ToString, agent_id: u64, redirect_uri: impl ToString) -> Self {
+        QrConnectUrlBuilder {
+            corp_id: corp_id.to_string(),
+            agent_id,
+            redirect_uri: redirect_uri.to_string(),
+            state: None,
+            lang: None,
+        }
+    }
+
+    /// 用于防止重放攻击, 重定向后会原样带上
+    pub fn state(mut self, state: impl ToString) -> Self {
+        self.state = Some(state.to_string());
+        self
+    }
+
+    /// 自定义语言, 支持 zh, en, 默认为 zh
+    pub fn lang(mut self, lang: impl ToString) -> Self {
+        self.lang = Some(lang.to_string());
+        self
+    }
+
+    pub fn build(self) -> String {
+        let mut url = Url::parse(QR_CONNECT_URL).unwrap();
+        {
+            let mut query = url.query_pairs_mut();
+            query
+                .append_pair("appid", &self.corp_id)
+                .append_pair("agentid", &self.agent_id.to_string())
+                .append_pair("redirect_uri", &self.redirect_uri);
+            if let Some(state) = &self.state {
+                query.append_pair("state", state);
+            }
+            if let Some(lang) = &self.lang {
+                query.append_pair("lang", lang);
+            }
+        }
+        url.into()
+    }
+}
+
+/// 访问用户身份
+#[derive(Debug, Clone)]
+pub enum UserInfo {
+    Member {
+        userid: String,
+        user_ticket: Option<String>, // scope 为 snsapi_privateinfo 时返回, 用于获取成员详情
+    },
+    NonMember {
+        openid: String,
+        external_userid: Option<String>,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+struct UserInfoResponse {
+    #[serde(default)]
+    userid: Option<String>,
+    #[serde(default)]
+    user_ticket: Option<String>,
+    #[serde(default)]
+    openid: Option<String>,
+    #[serde(default)]
+    external_userid: Option<String>,
+}
+
+/// 成员的敏感信息, 需要成员在网页授权时同意
+#[derive(Debug, Clone, Deserialize)]
+pub struct UserDetail {
+    pub userid: String,
+    #[serde(default)]
+    pub gender: String, // 0表示未定义，1表示男性，2表示女性
+    #[serde(default)]
+    pub avatar: String,
+    #[serde(default)]
+    pub qr_code: String,
+    #[serde(default)]
+    pub mobile: String,
+    #[serde(default)]
+    pub email: String,
+    #[serde(default)]
+    pub biz_mail: String,
+    #[serde(default)]
+    pub address: String,
+}
+
+#[derive(Debug, Serialize)]
+struct UserDetailRequest<'a> {
+    user_ticket: &'a str,
+}
+
+/// 小程序登录的会话, `session_key` 不应该下发到小程序
+#[derive(Debug, Clone, Deserialize)]
+pub struct MiniprogramSession {
+    pub corpid: String,
+    pub userid: String,
+    pub session_key: String,
+}
+
+#[derive(Debug, Serialize)]
+struct Code2SessionQuery<'a> {
+    js_code: &'a str,
+    grant_type: &'a str,
+}
+
+/// 身份验证
+impl Client {
+    /// 根据网页授权回调中的 code 获取访问用户身份
+    pub async fn get_user_info(&self, code: &str) -> Result<UserInfo> {
+        let ret: UserInfoResponse = self
+            .get("/cgi-bin/auth/getuserinfo", &[("code", code)])
+            .await?;
+        match ret {
+            UserInfoResponse {
+                userid: Some(userid),
+                user_ticket,
+                ..
+            } => Ok(UserInfo::Member {
+                userid,
+                user_ticket,
+            }),
+            UserInfoResponse {
+                openid: Some(openid),
+                external_userid,
+                ..
+            } => Ok(UserInfo::NonMember {
+                openid,
+                external_userid,
+            }),
+            _ => Err(Error::InvalidResponse(
+                "neither userid nor openid returned".to_string(),
+            )),
+        }
+    }
+
+    /// 根据扫码登录回调中的 code 获取登录成员的 userid
+    pub async fn get_login_user_info(&self, code: &str) -> Result<String> {
+        match self.get_user_info(code).await? {
+            UserInfo::Member { userid, .. } => Ok(userid),
+            UserInfo::NonMember { .. } => Err(Error::InvalidResponse(
+                "login user is not a member of the corp".to_string(),
+            )),
+        }
+    }
+
+    /// 根据 `get_user_info` 返回的 user_ticket 获取成员敏感信息
+    pub async fn get_user_detail(&self, user_ticket: &str) -> Result<UserDetail> {
+        let req = UserDetailRequest { user_ticket };
+        self.post("/cgi-bin/auth/getuserdetail", &req).await
+    }
+
+    /// 根据小程序 `qy.login` 返回的 code 获取登录成员, Client 需要使用小程序的 secret 创建
+    pub async fn miniprogram_code2session(&self, js_code: &str) -> Result<MiniprogramSession> {
+        let query = Code2SessionQuery {
+            js_code,
+            grant_type: "authorization_code",
+        };
+        self.get("/cgi-bin/miniprogram/jscode2session", &query)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_authorize_url() {
+        let url = AuthorizeUrlBuilder::new("ww1234", "https://example.com/cb?a=1")
+            .scope(Scope::PrivateInfo)
+            .state("xyz")
+            .agent_id(1000002)
+            .build();
+        assert_eq!(
+            url,
+            "https://open.weixin.qq.com/connect/oauth2/authorize?appid=ww1234&redirect_uri=https%3A%2F%2Fexample.com%2Fcb%3Fa%3D1&response_type=code&scope=snsapi_privateinfo&state=xyz&agentid=1000002#wechat_redirect"
+        );
+    }
+
+    #[test]
+    fn test_qr_connect_url() {
+        let url = QrConnectUrlBuilder::new("ww1234", 1000002, "https://example.com/login")
+            .state("abc")
+            .build();
+        assert_eq!(
+            url,
+            "https://open.work.weixin.qq.com/wwopen/sso/qrConnect?appid=ww1234&agentid=1000002&redirect_uri=https%3A%2F%2Fexample.com%2Flogin&state=abc"
+        );
+    }
+}
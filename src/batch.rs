@@ -0,0 +1,143 @@
+#[cfg(feature = "native")]
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "native")]
+use crate::client::Client;
+#[cfg(feature = "native")]
+use crate::media::FileType;
+#[cfg(feature = "native")]
+use crate::poll::{wait, BatchJob};
+#[cfg(feature = "native")]
+use crate::Result;
+
+/// 任务完成后企业微信回调的地址
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchCallback {
+    pub url: String,
+    pub token: String,
+    pub encodingaeskey: String,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct BatchRequest<'a> {
+    media_id: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    to_invite: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    callback: Option<&'a BatchCallback>,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct BatchResponse {
+    jobid: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BatchJobResult {
+    pub status: u8, // 1表示任务开始，2表示任务进行中，3表示任务已完成
+    #[serde(rename = "type")]
+    pub ty: String, // sync_user, replace_user, invite_user, replace_party
+    #[serde(default)]
+    pub total: u64,
+    #[serde(default)]
+    pub percentage: u32,
+    #[serde(default)]
+    pub result: Vec<BatchJobItem>,
+}
+
+impl BatchJobResult {
+    pub fn is_finished(&self) -> bool {
+        self.status == 3
+    }
+}
+
+/// 每个成员或部门的处理结果, 成员任务包含 `userid`, 部门任务包含 `partyid` 和 `action`
+#[derive(Debug, Clone, Deserialize)]
+pub struct BatchJobItem {
+    #[serde(default)]
+    pub userid: Option<String>,
+    #[serde(default)]
+    pub partyid: Option<u64>,
+    #[serde(default)]
+    pub action: Option<u8>, // 1表示新建部门，2表示更改部门
+    pub errcode: u64,
+    #[serde(default)]
+    pub errmsg: String,
+}
+
+/// 异步批量接口
+#[cfg(feature = "native")]
+impl Client {
+    /// 上传批量任务所需的 csv 文件, 返回 media_id
+    pub async fn upload_batch_csv(&self, file_name: &str, data: Vec<u8>) -> Result<String> {
+        let ret = self
+            .upload_file_data(FileType::File, file_name, data)
+            .await?;
+        Ok(ret.media_id)
+    }
+
+    /// 增量更新成员, 返回 jobid
+    pub async fn sync_users(
+        &self,
+        media_id: &str,
+        to_invite: bool,
+        callback: Option<&BatchCallback>,
+    ) -> Result<String> {
+        let req = BatchRequest {
+            media_id,
+            to_invite: Some(to_invite),
+            callback,
+        };
+        let ret: BatchResponse = self.post("/cgi-bin/batch/syncuser", &req).await?;
+        Ok(ret.jobid)
+    }
+
+    /// 全量覆盖成员, 返回 jobid
+    pub async fn replace_users(
+        &self,
+        media_id: &str,
+        to_invite: bool,
+        callback: Option<&BatchCallback>,
+    ) -> Result<String> {
+        let req = BatchRequest {
+            media_id,
+            to_invite: Some(to_invite),
+            callback,
+        };
+        let ret: BatchResponse = self.post("/cgi-bin/batch/replaceuser", &req).await?;
+        Ok(ret.jobid)
+    }
+
+    /// 全量覆盖部门, 返回 jobid
+    pub async fn replace_parties(
+        &self,
+        media_id: &str,
+        callback: Option<&BatchCallback>,
+    ) -> Result<String> {
+        let req = BatchRequest {
+            media_id,
+            to_invite: None,
+            callback,
+        };
+        let ret: BatchResponse = self.post("/cgi-bin/batch/replaceparty", &req).await?;
+        Ok(ret.jobid)
+    }
+
+    pub async fn get_batch_result(&self, jobid: &str) -> Result<BatchJobResult> {
+        self.get("/cgi-bin/batch/getresult", &[("jobid", jobid)])
+            .await
+    }
+
+    /// 轮询任务结果直到任务完成, 轮询间隔逐渐增大, 超过 `timeout` 后返回 `Error::PollTimeout`
+    pub async fn wait_batch_job(
+        &self,
+        jobid: &str,
+        timeout: Option<Duration>,
+    ) -> Result<BatchJobResult> {
+        wait(self, &BatchJob(jobid.to_string()), timeout).await
+    }
+}
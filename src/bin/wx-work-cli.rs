@@ -0,0 +1,85 @@
+//! 常用操作的命令行工具, 企业 id 和应用 secret 通过环境变量 `WX_CORP_ID` 和 `WX_CORP_SECRET` 指定
+
+use std::env;
+use std::process;
+
+use wx_work::client::Client;
+use wx_work::media::FileType;
+use wx_work::message::MessageBuilder;
+use wx_work::webhook::{WebhookClient, WebhookMessage};
+
+const USAGE: &str = "usage:
+    wx-work-cli send-text <agent_id> <userid>[|<userid>...] <content>
+    wx-work-cli send-markdown <agent_id> <userid>[|<userid>...] <content>
+    wx-work-cli upload <image|voice|video|file> <path>
+    wx-work-cli recall <msgid>
+    wx-work-cli webhook-send <key> <content>";
+
+type CliResult = std::result::Result<(), Box<dyn std::error::Error>>;
+
+#[tokio::main]
+async fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    if let Err(e) = run(&args).await {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    }
+}
+
+async fn run(args: &[&str]) -> CliResult {
+    match args {
+        ["send-text", agent_id, users, content] => {
+            let builder = MessageBuilder::new_text(agent_id.parse()?, content.to_string());
+            send(builder, users).await
+        }
+        ["send-markdown", agent_id, users, content] => {
+            let builder = MessageBuilder::new_markdown(agent_id.parse()?, content.to_string());
+            send(builder, users).await
+        }
+        ["upload", ty, path] => {
+            let ty = match *ty {
+                "image" => FileType::Image,
+                "voice" => FileType::Voice,
+                "video" => FileType::Video,
+                "file" => FileType::File,
+                _ => return Err(format!("unknown media type: {}", ty).into()),
+            };
+            let resp = client()?.upload_file(ty, path).await?;
+            println!("{}", resp.media_id);
+            Ok(())
+        }
+        ["recall", msgid] => {
+            client()?.recall_msg(msgid).await?;
+            Ok(())
+        }
+        ["webhook-send", key, content] => {
+            WebhookClient::new(key)
+                .send(&WebhookMessage::text(content))
+                .await?;
+            Ok(())
+        }
+        _ => {
+            eprintln!("{}", USAGE);
+            process::exit(2);
+        }
+    }
+}
+
+async fn send(mut builder: MessageBuilder, users: &str) -> CliResult {
+    for user in users.split('|') {
+        builder = builder.with_user(user.to_string());
+    }
+    let resp = client()?.send_msg(&builder.build()?).await?;
+    if let Some(invalid) = resp.invaliduser.filter(|s| !s.is_empty()) {
+        eprintln!("invalid users: {}", invalid);
+    }
+    println!("{}", resp.msgid);
+    Ok(())
+}
+
+fn client() -> std::result::Result<Client, Box<dyn std::error::Error>> {
+    let corp_id = env::var("WX_CORP_ID").map_err(|_| "WX_CORP_ID is not set")?;
+    let secret = env::var("WX_CORP_SECRET").map_err(|_| "WX_CORP_SECRET is not set")?;
+    Ok(Client::new(&corp_id, &secret)?)
+}
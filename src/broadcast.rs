@@ -0,0 +1,108 @@
+use std::collections::{BTreeSet, HashSet};
+
+use crate::client::Client;
+use crate::message::{MessageBuilder, MessageResponse};
+use crate::poll::retry_rate_limited;
+use crate::tag::TagId;
+use crate::Result;
+
+// max number of users in the touser field of one message
+const MAX_USERS_PER_MESSAGE: usize = 1000;
+
+/// 按部门群发消息, 并排除指定的成员和标签, 比如 "除实习生以外的所有人"
+///
+/// 接口本身不支持排除, 这里会展开部门和标签的成员, 在本地计算差集后按成员发送
+#[derive(Debug, Clone, Default)]
+pub struct DepartmentBroadcast {
+    departments: Vec<u64>,
+    exclude_users: HashSet<String>,
+    exclude_tags: Vec<TagId>,
+}
+
+impl DepartmentBroadcast {
+    /// 部门会包含所有子部门的成员
+    pub fn new(departments: &[u64]) -> Self {
+        DepartmentBroadcast {
+            departments: departments.to_vec(),
+            ..Default::default()
+        }
+    }
+
+    pub fn exclude_user(mut self, userid: impl ToString) -> Self {
+        self.exclude_users.insert(userid.to_string());
+        self
+    }
+
+    /// 排除标签中的成员, 以及标签中部门的成员
+    pub fn exclude_tag(mut self, tagid: TagId) -> Self {
+        self.exclude_tags.push(tagid);
+        self
+    }
+
+    /// 计算最终的接收人, 展开部门和标签时遇到频率限制会等待后重试
+    pub async fn recipients(&self, client: &Client) -> Result<Vec<String>> {
+        let department_users =
+            |id| retry_rate_limited(move || client.list_department_users(id, true));
+
+        let mut members = vec![];
+        for id in &self.departments {
+            let users = department_users(*id).await?;
+            members.extend(users.into_iter().map(|u| u.userid));
+        }
+
+        let mut excluded = self.exclude_users.clone();
+        for tagid in &self.exclude_tags {
+            let tag = retry_rate_limited(|| client.get_tag_members(*tagid)).await?;
+            excluded.extend(tag.userlist.into_iter().map(|u| u.userid));
+            for party in tag.partylist {
+                let users = department_users(party).await?;
+                excluded.extend(users.into_iter().map(|u| u.userid));
+            }
+        }
+        Ok(subtract(members, &excluded))
+    }
+
+    /// 发送消息, `builder` 中已有的接收人会被忽略, 每 1000 人发送一条
+    pub async fn send(
+        &self,
+        client: &Client,
+        builder: MessageBuilder,
+    ) -> Result<Vec<MessageResponse>> {
+        let recipients = self.recipients(client).await?;
+        let mut ret = vec![];
+        for chunk in recipients.chunks(MAX_USERS_PER_MESSAGE) {
+            let msg = chunk
+                .iter()
+                .fold(builder.clone().clear_receivers(), |b, u| {
+                    b.with_user(u.clone())
+                })
+                .build()?;
+            ret.push(client.send_msg(&msg).await?);
+        }
+        Ok(ret)
+    }
+}
+
+// deduplicated `members` not in `excluded`, sorted to keep the chunks stable
+fn subtract(members: Vec<String>, excluded: &HashSet<String>) -> Vec<String> {
+    let members: BTreeSet<String> = members
+        .into_iter()
+        .filter(|m| !excluded.contains(m))
+        .collect();
+    members.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subtract() {
+        let members = vec!["c", "a", "b", "a", "intern1"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let excluded = ["intern1", "b"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(subtract(members, &excluded), vec!["a", "c"]);
+    }
+}
@@ -0,0 +1,545 @@
+#[cfg(feature = "native")]
+use serde::de::IgnoredAny;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "native")]
+use crate::client::Client;
+#[cfg(feature = "native")]
+use crate::Result;
+
+/// 一条打卡记录
+#[derive(Debug, Clone, Deserialize)]
+pub struct CheckinRecord {
+    pub userid: String,
+    #[serde(default)]
+    pub groupname: String,
+    pub checkin_type: String, // 上班打卡, 下班打卡, 外出打卡
+    #[serde(default)]
+    pub exception_type: String, // 为空表示正常, 否则为 时间异常, 地点异常, 未打卡, wifi异常, 非常用设备 等, 多个异常以 ; 分隔
+    pub checkin_time: u64,
+    #[serde(default)]
+    pub location_title: String,
+    #[serde(default)]
+    pub location_detail: String,
+    #[serde(default)]
+    pub wifiname: String,
+    #[serde(default)]
+    pub wifimac: String,
+    #[serde(default)]
+    pub notes: String,
+    #[serde(default)]
+    pub mediaids: Vec<String>,
+    #[serde(default)]
+    pub lat: i64, // 实际值的 1000000 倍
+    #[serde(default)]
+    pub lng: i64,
+    #[serde(default)]
+    pub deviceid: String,
+    #[serde(default)]
+    pub sch_checkin_time: u64, // 标准打卡时间
+    #[serde(default)]
+    pub groupid: u64,
+    #[serde(default)]
+    pub schedule_id: u64,
+    #[serde(default)]
+    pub timeline_id: u64,
+}
+
+impl CheckinRecord {
+    pub fn is_exception(&self) -> bool {
+        !self.exception_type.is_empty()
+    }
+}
+
+/// 打卡规则
+#[derive(Debug, Clone, Deserialize)]
+pub struct CheckinGroup {
+    pub grouptype: u8, // 1-固定时间上下班, 2-按班次上下班, 3-自由上下班
+    pub groupid: u64,
+    pub groupname: String,
+    #[serde(default)]
+    pub checkindate: Vec<CheckinDate>, // 固定时间上下班时的打卡时间
+    #[serde(default)]
+    pub spe_workdays: Vec<SpecialDay>, // 必须打卡的日期
+    #[serde(default)]
+    pub spe_offdays: Vec<SpecialDay>, // 不需要打卡的日期
+    #[serde(default)]
+    pub sync_holidays: bool, // 是否同步法定节假日
+    #[serde(default)]
+    pub need_photo: bool,
+    #[serde(default)]
+    pub note_can_use_local_pic: bool,
+    #[serde(default)]
+    pub allow_checkin_offworkday: bool,
+    #[serde(default)]
+    pub allow_apply_offworkday: bool,
+    #[serde(default)]
+    pub wifimac_infos: Vec<WifiSpec>,
+    #[serde(default)]
+    pub loc_infos: Vec<LocationSpec>,
+    #[serde(default)]
+    pub range: CheckinRange,
+    #[serde(default)]
+    pub white_users: Vec<String>, // 不需要打卡的成员
+    #[serde(default)]
+    pub create_time: u64,
+    #[serde(default)]
+    pub schedulelist: Vec<Schedule>, // 按班次上下班时的班次
+    #[serde(default)]
+    pub offwork_interval_time: u64, // 自由上下班时的最短打卡间隔, 单位秒
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CheckinDate {
+    #[serde(default)]
+    pub workdays: Vec<u8>, // 0 表示周日
+    #[serde(default)]
+    pub checkintime: Vec<CheckinTime>,
+    #[serde(default)]
+    pub flex_time: u64, // 弹性时间, 单位毫秒
+    #[serde(default)]
+    pub noneed_offwork: bool, // 下班是否不需要打卡
+    #[serde(default)]
+    pub limit_aheadtime: u64, // 打卡时间限制, 单位毫秒
+}
+
+/// 一天中的一段上下班时间, 均为距离当天 0 点的秒数
+#[derive(Debug, Clone, Deserialize)]
+pub struct CheckinTime {
+    #[serde(default)]
+    pub time_id: u64,
+    pub work_sec: u64,
+    pub off_work_sec: u64,
+    #[serde(default)]
+    pub remind_work_sec: u64,
+    #[serde(default)]
+    pub remind_off_work_sec: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SpecialDay {
+    pub timestamp: u64,
+    #[serde(default)]
+    pub notes: String,
+    #[serde(default)]
+    pub checkintime: Vec<CheckinTime>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WifiSpec {
+    #[serde(default)]
+    pub wifiname: String,
+    pub wifimac: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LocationSpec {
+    pub lat: i64, // 实际值的 1000000 倍
+    pub lng: i64,
+    #[serde(default)]
+    pub loc_title: String,
+    #[serde(default)]
+    pub loc_detail: String,
+    pub distance: u64, // 允许打卡的范围, 单位米
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CheckinRange {
+    #[serde(default)]
+    pub partyid: Vec<u64>,
+    #[serde(default)]
+    pub userid: Vec<String>,
+    #[serde(default)]
+    pub tagid: Vec<u64>,
+}
+
+/// 班次
+#[derive(Debug, Clone, Deserialize)]
+pub struct Schedule {
+    pub schedule_id: u64,
+    pub schedule_name: String,
+    #[serde(default)]
+    pub time_section: Vec<CheckinTime>,
+    #[serde(default)]
+    pub limit_aheadtime: u64,
+    #[serde(default)]
+    pub noneed_offwork: bool,
+    #[serde(default)]
+    pub limit_offtime: u64,
+    #[serde(default)]
+    pub allow_flex: bool,
+    #[serde(default)]
+    pub flex_on_duty_time: u64,
+    #[serde(default)]
+    pub flex_off_duty_time: u64,
+}
+
+/// 成员适用的打卡规则
+#[derive(Debug, Clone, Deserialize)]
+pub struct UserCheckinOption {
+    pub userid: String,
+    pub group: CheckinGroup,
+}
+
+/// 打卡日报
+#[derive(Debug, Clone, Deserialize)]
+pub struct CheckinDayData {
+    pub base_info: CheckinBaseInfo,
+    pub summary_info: DaySummary,
+    #[serde(default)]
+    pub exception_infos: Vec<CheckinException>,
+    #[serde(default)]
+    pub sp_items: Vec<SpItem>,
+}
+
+/// 打卡月报
+#[derive(Debug, Clone, Deserialize)]
+pub struct CheckinMonthData {
+    pub base_info: CheckinBaseInfo,
+    pub summary_info: MonthSummary,
+    #[serde(default)]
+    pub exception_infos: Vec<CheckinException>,
+    #[serde(default)]
+    pub sp_items: Vec<SpItem>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CheckinBaseInfo {
+    #[serde(default)]
+    pub date: u64, // 只在日报中返回
+    pub record_type: u8, // 1-固定上下班, 2-按班次上下班, 3-自由上下班
+    pub name: String,
+    #[serde(default)]
+    pub name_ex: String,
+    #[serde(default)]
+    pub departs_name: String,
+    pub acctid: String, // 成员的 userid
+    #[serde(default)]
+    pub rule_info: CheckinRuleInfo,
+    #[serde(default)]
+    pub day_type: u8, // 0-工作日, 1-休息日, 只在日报中返回
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CheckinRuleInfo {
+    #[serde(default)]
+    pub groupid: u64,
+    #[serde(default)]
+    pub groupname: String,
+    #[serde(default)]
+    pub scheduleid: u64,
+    #[serde(default)]
+    pub schedulename: String,
+    #[serde(default)]
+    pub checkintime: Vec<CheckinTime>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct DaySummary {
+    pub checkin_count: u32,
+    pub regular_work_sec: u64,  // 实际工作时长
+    pub standard_work_sec: u64, // 标准工作时长
+    pub earliest_time: u64,     // 最早打卡时间, 距离当天 0 点的秒数
+    pub lastest_time: u64,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct MonthSummary {
+    pub work_days: u32,
+    pub regular_days: u32,
+    pub except_days: u32,
+    pub regular_work_sec: u64,
+    pub standard_work_sec: u64,
+}
+
+/// 打卡异常的统计
+#[derive(Debug, Clone, Deserialize)]
+pub struct CheckinException {
+    pub exception: u8, // 1-迟到, 2-早退, 3-缺卡, 4-旷工, 5-地点异常, 6-设备异常
+    #[serde(default)]
+    pub count: u32,
+    #[serde(default)]
+    pub duration: u64, // 单位秒
+}
+
+/// 假勤统计, 比如请假和外出
+#[derive(Debug, Clone, Deserialize)]
+pub struct SpItem {
+    #[serde(rename = "type")]
+    pub ty: u8, // 1-请假, 2-补卡, 3-出差, 4-外出, 100-外勤
+    #[serde(default)]
+    pub vacation_id: u64,
+    #[serde(default)]
+    pub count: u32,
+    #[serde(default)]
+    pub duration: u64,
+    #[serde(default)]
+    pub time_type: u8, // 0-按天, 1-按小时
+    #[serde(default)]
+    pub name: String,
+}
+
+/// 由门禁, 考勤机等设备记录的打卡
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CheckinUserData {
+    pub userid: String,
+    pub checkin_time: u64,
+    pub device_type: u8, // 1-门禁, 2-考勤机, 3-其他
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location_title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location_detail: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub mediaids: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lat: Option<i64>, // 实际值的 1000000 倍
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lng: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device_detail: Option<String>, // 设备的名称或者编号
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wifiname: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wifimac: Option<String>,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct AddCheckinUserDataRequest<'a> {
+    records: &'a [CheckinUserData],
+}
+
+/// 成员一个月的排班
+#[derive(Debug, Clone, Deserialize)]
+pub struct UserScheduleList {
+    pub userid: String,
+    pub yearmonth: u32, // 比如 202011
+    pub groupid: u64,
+    #[serde(default)]
+    pub groupname: String,
+    #[serde(default)]
+    pub schedule: UserSchedules,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct UserSchedules {
+    #[serde(default)]
+    #[serde(rename = "scheduleList")]
+    pub schedule_list: Vec<DaySchedule>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DaySchedule {
+    pub day: u8,
+    pub schedule_info: Schedule,
+}
+
+/// 为成员 `userid` 在某一天安排班次 `schedule_id`, 为 0 时表示休息
+#[derive(Debug, Clone, Serialize)]
+pub struct ScheduleItem {
+    pub userid: String,
+    pub day: u8,
+    pub schedule_id: u64,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct ScheduleListResponse {
+    #[serde(default)]
+    schedule_list: Vec<UserScheduleList>,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct SetScheduleListRequest<'a> {
+    groupid: u64,
+    items: &'a [ScheduleItem],
+    yearmonth: u32,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct CheckinReportRequest<'a> {
+    starttime: u64,
+    endtime: u64,
+    useridlist: &'a [&'a str],
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct CheckinReportResponse<T> {
+    #[serde(default = "Vec::new")]
+    datas: Vec<T>,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct CheckinOptionRequest<'a> {
+    datetime: u64,
+    useridlist: &'a [&'a str],
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct CheckinOptionResponse {
+    #[serde(default)]
+    info: Vec<UserCheckinOption>,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct CorpCheckinOptionResponse {
+    #[serde(default)]
+    group: Vec<CheckinGroup>,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct CheckinDataRequest<'a> {
+    opencheckindatatype: u8,
+    starttime: u64,
+    endtime: u64,
+    useridlist: &'a [&'a str],
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct CheckinDataResponse {
+    #[serde(default)]
+    checkindata: Vec<CheckinRecord>,
+}
+
+/// 打卡
+#[cfg(feature = "native")]
+impl Client {
+    /// 获取打卡记录, `opencheckindatatype` 1-上下班打卡, 2-外出打卡, 3-全部打卡
+    ///
+    /// 时间跨度不超过 30 天, `userids` 每次最多 100 个
+    pub async fn get_checkin_data(
+        &self,
+        opencheckindatatype: u8,
+        start: u64,
+        end: u64,
+        userids: &[&str],
+    ) -> Result<Vec<CheckinRecord>> {
+        let req = CheckinDataRequest {
+            opencheckindatatype,
+            starttime: start,
+            endtime: end,
+            useridlist: userids,
+        };
+        let ret: CheckinDataResponse = self.post("/cgi-bin/checkin/getcheckindata", &req).await?;
+        Ok(ret.checkindata)
+    }
+
+    /// 获取打卡日报, `start` 和 `end` 为当天 0 点的时间戳, `userids` 每次最多 100 个
+    pub async fn get_checkin_day_data(
+        &self,
+        start: u64,
+        end: u64,
+        userids: &[&str],
+    ) -> Result<Vec<CheckinDayData>> {
+        let req = CheckinReportRequest {
+            starttime: start,
+            endtime: end,
+            useridlist: userids,
+        };
+        let ret: CheckinReportResponse<CheckinDayData> = self
+            .post("/cgi-bin/checkin/getcheckin_daydata", &req)
+            .await?;
+        Ok(ret.datas)
+    }
+
+    /// 获取打卡月报, 时间跨度不超过一个月, `userids` 每次最多 100 个
+    pub async fn get_checkin_month_data(
+        &self,
+        start: u64,
+        end: u64,
+        userids: &[&str],
+    ) -> Result<Vec<CheckinMonthData>> {
+        let req = CheckinReportRequest {
+            starttime: start,
+            endtime: end,
+            useridlist: userids,
+        };
+        let ret: CheckinReportResponse<CheckinMonthData> = self
+            .post("/cgi-bin/checkin/getcheckin_monthdata", &req)
+            .await?;
+        Ok(ret.datas)
+    }
+
+    /// 获取按班次上下班的成员的排班信息, 时间跨度不超过一个月
+    pub async fn get_checkin_schedule_list(
+        &self,
+        start: u64,
+        end: u64,
+        userids: &[&str],
+    ) -> Result<Vec<UserScheduleList>> {
+        let req = CheckinReportRequest {
+            starttime: start,
+            endtime: end,
+            useridlist: userids,
+        };
+        let ret: ScheduleListResponse = self
+            .post("/cgi-bin/checkin/getcheckinschedulist", &req)
+            .await?;
+        Ok(ret.schedule_list)
+    }
+
+    /// 为打卡规则 `groupid` 的成员排班, `yearmonth` 比如 202011
+    pub async fn set_checkin_schedule_list(
+        &self,
+        groupid: u64,
+        yearmonth: u32,
+        items: &[ScheduleItem],
+    ) -> Result<()> {
+        let req = SetScheduleListRequest {
+            groupid,
+            items,
+            yearmonth,
+        };
+        let _: IgnoredAny = self
+            .post("/cgi-bin/checkin/setcheckinschedulist", &req)
+            .await?;
+        Ok(())
+    }
+
+    /// 为成员添加打卡记录, 每次最多 200 条
+    pub async fn add_checkin_userdata(&self, records: &[CheckinUserData]) -> Result<()> {
+        let req = AddCheckinUserDataRequest { records };
+        let _: IgnoredAny = self
+            .post("/cgi-bin/checkin/add_checkin_userdata", &req)
+            .await?;
+        Ok(())
+    }
+
+    /// 获取成员在 `datetime` 当天适用的打卡规则, `userids` 每次最多 100 个
+    pub async fn get_checkin_option(
+        &self,
+        datetime: u64,
+        userids: &[&str],
+    ) -> Result<Vec<UserCheckinOption>> {
+        let req = CheckinOptionRequest {
+            datetime,
+            useridlist: userids,
+        };
+        let ret: CheckinOptionResponse =
+            self.post("/cgi-bin/checkin/getcheckinoption", &req).await?;
+        Ok(ret.info)
+    }
+
+    /// 获取企业所有的打卡规则
+    pub async fn get_corp_checkin_option(&self) -> Result<Vec<CheckinGroup>> {
+        let ret: CorpCheckinOptionResponse = self
+            .post(
+                "/cgi-bin/checkin/getcorpcheckinoption",
+                &serde_json::json!({}),
+            )
+            .await?;
+        Ok(ret.group)
+    }
+}
@@ -1,157 +1,630 @@
-use std::fs::File;
-use std::io::Read;
+use std::io;
 use std::path::Path;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::{self, Sender};
 use std::sync::{Arc, RwLock};
 use std::thread::{self, JoinHandle};
-use std::time::Duration;
+use std::time::{Duration, Instant, SystemTime};
 
-use log::{error, info};
+use futures::stream::{self, StreamExt};
+use log::{error, info, warn};
+use rand::Rng;
+use reqwest::header::{CONTENT_DISPOSITION, CONTENT_TYPE};
 use reqwest::multipart::{Form, Part};
-use serde::de::DeserializeOwned;
+use reqwest::{Body, RequestBuilder};
+use serde::de::{DeserializeOwned, IgnoredAny};
 use serde::{Deserialize, Serialize};
+use tokio::io::AsyncReadExt;
 
+use crate::correlation::{CorrelationStore, SentMessage};
+use crate::jsapi::JsapiTickets;
 use crate::media::*;
 use crate::message::*;
+use crate::poll::{wait, UploadByUrlJob};
+use crate::scan::{MediaScanner, ScanDecision};
 use crate::{Error, Result};
 
-static WX_URL: &str = "https://qyapi.weixin.qq.com";
+pub(crate) static WX_URL: &str = "https://qyapi.weixin.qq.com";
 
+// refresh the token this long before it expires
+const REFRESH_MARGIN: Duration = Duration::from_secs(300);
+const MIN_RETRY_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_RETRY_BACKOFF: Duration = Duration::from_secs(60);
+const UPLOAD_CHUNK_SIZE: usize = 64 * 1024;
+
+/// 可以廉价地 clone, 所有副本共享同一个 access token
+#[derive(Clone)]
 pub struct Client {
-    access_token: Arc<RwLock<String>>,
+    corp_id: String,
+    token: RefreshedToken,
     http_client: reqwest::Client,
-    refresh_token_thread: Option<JoinHandle<()>>,
+    timeout: Option<Duration>,
+    scanner: Option<Arc<dyn MediaScanner>>,
+    pub(crate) jsapi_tickets: Arc<JsapiTickets>,
+}
+
+// stops the refresh thread when the last Client is dropped
+pub(crate) struct RefreshThread {
+    handle: Option<JoinHandle<()>>,
     is_exit: Arc<AtomicBool>,
 }
 
+/// access token 刷新状态, 可用于服务的健康检查
+#[derive(Debug, Clone)]
+pub struct Health {
+    pub token_age: Duration,        // 距离上次成功刷新的时间
+    pub token_expires_in: Duration, // 上次刷新时 token 的有效期
+    pub last_refresh: std::result::Result<(), String>,
+    pub consecutive_failures: u32,
+}
+
+impl Health {
+    /// 当前 token 是否还在有效期内
+    pub fn is_token_valid(&self) -> bool {
+        self.token_age < self.token_expires_in
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct RefreshStatus {
+    refreshed_at: Instant,
+    expires_in: Duration,
+    last_error: Option<String>,
+    consecutive_failures: u32,
+}
+
+impl RefreshStatus {
+    fn health(&self) -> Health {
+        let last_refresh = match &self.last_error {
+            Some(e) => Err(e.clone()),
+            None => Ok(()),
+        };
+        Health {
+            token_age: self.refreshed_at.elapsed(),
+            token_expires_in: self.expires_in,
+            last_refresh,
+            consecutive_failures: self.consecutive_failures,
+        }
+    }
+
+    fn succeed(&mut self, expires_in: u64) {
+        self.refreshed_at = Instant::now();
+        self.expires_in = Duration::from_secs(expires_in);
+        self.last_error = None;
+        self.consecutive_failures = 0;
+    }
+
+    fn fail(&mut self, e: &Error) {
+        self.last_error = Some(format!("{}", e));
+        self.consecutive_failures += 1;
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Status {
+    errcode: u64,
+    errmsg: String,
+}
+
+#[derive(Debug, Serialize)]
+struct UploadByUrlRequest<'a> {
+    scene: u8, // 1-客户联系入群欢迎语素材
+    #[serde(rename = "type")]
+    ty: &'a str,
+    filename: &'a str,
+    url: &'a str,
+    md5: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+struct JobRequest<'a> {
+    jobid: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+struct JobResponse {
+    jobid: String,
+}
+
+#[derive(Debug, Serialize)]
+struct RecallRequest<'a> {
+    msgid: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+struct UpdateButtonRequest<'a> {
+    userids: &'a [&'a str],
+    agentid: u64,
+    response_code: &'a str,
+    button: ReplaceButton<'a>,
+}
+
+#[derive(Debug, Serialize)]
+struct ReplaceButton<'a> {
+    replace_name: &'a str,
+}
+
+// the service apis do not return errcode on success
 #[derive(Debug, Serialize, Deserialize)]
 struct AccessTokenResponse {
+    #[serde(default)]
     errcode: u64,
+    #[serde(default)]
     errmsg: String,
+    #[serde(default)]
+    #[serde(alias = "provider_access_token", alias = "suite_access_token")]
     access_token: String,
+    #[serde(default)]
     expires_in: u64,
 }
 
-fn get_access_token(client: &reqwest::blocking::Client, url: &str) -> Result<AccessTokenResponse> {
-    let resp = client.get(url).send()?.json::<AccessTokenResponse>()?;
+#[derive(Debug, Serialize)]
+struct ProviderTokenRequest<'a> {
+    corpid: &'a str,
+    provider_secret: &'a str,
+}
 
+#[derive(Debug, Serialize)]
+struct SuiteTokenRequest<'a> {
+    suite_id: &'a str,
+    suite_secret: &'a str,
+    suite_ticket: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct CorpGroupTokenRequest {
+    corpid: String,
+    business_type: u8,
+    agentid: u64,
+}
+
+// where the refresh thread gets its access token from
+pub(crate) enum TokenSource {
+    // the gettoken url, which carries the corp secret
+    Secret(String),
+    // a child corp's token, exchanged with the parent corp's token
+    CorpGroup {
+        parent: RefreshedToken, // also keeps the parent's token fresh
+        req: CorpGroupTokenRequest,
+    },
+    // the provider_access_token of a service provider
+    Provider {
+        corpid: String,
+        provider_secret: String,
+    },
+    // the suite_access_token of a third-party app, needs the latest pushed suite_ticket
+    Suite {
+        suite_id: String,
+        suite_secret: String,
+        suite_ticket: Arc<RwLock<String>>,
+    },
+}
+
+impl TokenSource {
+    fn fetch(&self, client: &reqwest::blocking::Client) -> Result<AccessTokenResponse> {
+        let resp = match self {
+            TokenSource::Secret(url) => client.get(url).send()?,
+            TokenSource::CorpGroup { parent, req } => {
+                let token = parent.get()?;
+                let url = format!(
+                    "{}/cgi-bin/corpgroup/corp/gettoken?access_token={}",
+                    WX_URL, token
+                );
+                client.post(&url).json(req).send()?
+            }
+            TokenSource::Provider {
+                corpid,
+                provider_secret,
+            } => {
+                let url = format!("{}/cgi-bin/service/get_provider_token", WX_URL);
+                let req = ProviderTokenRequest {
+                    corpid,
+                    provider_secret,
+                };
+                client.post(&url).json(&req).send()?
+            }
+            TokenSource::Suite {
+                suite_id,
+                suite_secret,
+                suite_ticket,
+            } => {
+                let suite_ticket = suite_ticket.read().unwrap().clone();
+                if suite_ticket.is_empty() {
+                    return Err(Error::TokenUnavailable);
+                }
+                let url = format!("{}/cgi-bin/service/get_suite_token", WX_URL);
+                let req = SuiteTokenRequest {
+                    suite_id,
+                    suite_secret,
+                    suite_ticket: &suite_ticket,
+                };
+                client.post(&url).json(&req).send()?
+            }
+        };
+        check_token_response(resp.json::<AccessTokenResponse>()?)
+    }
+}
+
+// a blank token or expires_in would make the refresh thread spin, retry with backoff instead
+fn check_token_response(resp: AccessTokenResponse) -> Result<AccessTokenResponse> {
     if resp.errcode != 0 {
         return Err(Error::GetAccessTokenFailed(resp.errcode, resp.errmsg));
     }
+    if resp.access_token.is_empty() || resp.expires_in == 0 {
+        let msg = "empty access token or expires_in".to_string();
+        return Err(Error::InvalidResponse(msg));
+    }
 
     Ok(resp)
 }
 
 fn start_refresh_token_thread(
-    url: String,
+    source: TokenSource,
     access_token: Arc<RwLock<String>>,
-    sender: Sender<Result<()>>,
+    refresh_status: Arc<RwLock<RefreshStatus>>,
+    mut sender: Option<Sender<Result<()>>>, // None in degraded mode, keep retrying instead
     is_exit: Arc<AtomicBool>,
 ) -> JoinHandle<()> {
     thread::Builder::new()
         .name("wx work client".to_string())
         .spawn(move || {
             let client = reqwest::blocking::Client::new();
-
-            let d = match get_access_token(&client, &url) {
-                Ok(d) => d,
-                Err(e) => {
-                    sender.send(Err(e)).unwrap();
-                    return;
-                }
-            };
-
-            let mut expires_in = d.expires_in;
-            {
-                let mut token = access_token.write().unwrap();
-                *token = d.access_token;
-            }
-            info!("init token success, expires_in {}", d.expires_in);
-            sender.send(Ok(())).unwrap();
+            let mut delay = Duration::from_secs(0);
+            let mut backoff = MIN_RETRY_BACKOFF;
 
             loop {
-                //let delay_time = expires_in / 2;
-                let delay_time = expires_in / 2;
-
-                thread::park_timeout(Duration::from_secs(delay_time));
+                if !delay.is_zero() {
+                    thread::park_timeout(delay);
+                }
                 if is_exit.load(Ordering::Acquire) {
                     info!("detect exit signal, exit thread");
                     break;
                 }
 
-                match get_access_token(&client, &url) {
+                match source.fetch(&client) {
                     Ok(d) => {
-                        expires_in = d.expires_in;
-                        let mut token = access_token.write().unwrap();
-                        *token = d.access_token;
+                        delay = refresh_delay(d.expires_in);
+                        backoff = MIN_RETRY_BACKOFF;
+                        *access_token.write().unwrap() = d.access_token;
+                        refresh_status.write().unwrap().succeed(d.expires_in);
                         info!("update token success, expires_in {}", d.expires_in);
+                        if let Some(sender) = sender.take() {
+                            sender.send(Ok(())).unwrap();
+                        }
+                    }
+                    Err(e) => {
+                        if let Some(sender) = sender.take() {
+                            sender.send(Err(e)).unwrap();
+                            return;
+                        }
+                        delay = with_jitter(backoff);
+                        backoff = (backoff * 2).min(MAX_RETRY_BACKOFF);
+                        refresh_status.write().unwrap().fail(&e);
+                        error!("refresh token failed, retry in {:?}, reason: {}", delay, e);
                     }
-                    Err(e) => error!("refresh token failed, reason: {}", e),
                 }
             }
         })
         .unwrap()
 }
 
-impl Client {
-    pub fn new(corp_id: &str, corp_secret: &str) -> Result<Self> {
-        let url = format!(
-            "{}/cgi-bin/gettoken?corpid={}&corpsecret={}",
-            WX_URL, corp_id, corp_secret
-        );
-
-        let http_client = reqwest::Client::new();
-        let (tx, rx) = mpsc::channel();
+/// 后台刷新的 token, 可以廉价地 clone
+#[derive(Clone)]
+pub(crate) struct RefreshedToken {
+    token: Arc<RwLock<String>>,
+    status: Arc<RwLock<RefreshStatus>>,
+    thread: Arc<RefreshThread>, // only held for its Drop
+}
 
-        let access_token = Arc::new(RwLock::new("".to_string()));
+impl RefreshedToken {
+    // start refreshing in the background, `sender` receives the result of the first refresh
+    pub(crate) fn start(source: TokenSource, sender: Option<Sender<Result<()>>>) -> Self {
+        let token = Arc::new(RwLock::new("".to_string()));
+        let status = Arc::new(RwLock::new(RefreshStatus {
+            refreshed_at: Instant::now(),
+            expires_in: Duration::from_secs(0),
+            last_error: None,
+            consecutive_failures: 0,
+        }));
         let is_exit = Arc::new(AtomicBool::new(false));
 
-        let refresh_token_thread = Some(start_refresh_token_thread(
-            url,
-            access_token.clone(),
-            tx,
+        let handle = Some(start_refresh_token_thread(
+            source,
+            token.clone(),
+            status.clone(),
+            sender,
             is_exit.clone(),
         ));
+        let thread = Arc::new(RefreshThread { handle, is_exit });
+        RefreshedToken {
+            token,
+            status,
+            thread,
+        }
+    }
+
+    pub(crate) fn health(&self) -> Health {
+        self.status.read().unwrap().health()
+    }
+
+    // the current token, fails if it has never been fetched or has expired
+    pub(crate) fn get(&self) -> Result<String> {
+        let token = self.token.read().unwrap();
+        if token.is_empty() || !self.health().is_token_valid() {
+            return Err(Error::TokenUnavailable);
+        }
+        Ok(token.clone())
+    }
+
+    // refresh now instead of waiting for the current retry backoff
+    pub(crate) fn wake(&self) {
+        if let Some(handle) = &self.thread.handle {
+            handle.thread().unpark();
+        }
+    }
+}
+
+// check the errcode and parse the body of an api response
+pub(crate) fn parse_response<T: DeserializeOwned>(bytes: &[u8]) -> Result<T> {
+    let status: Status = serde_json::from_slice(bytes)?;
+    if status.errcode != 0 {
+        return Err(Error::from_errcode(status.errcode, status.errmsg));
+    }
+    let ret = serde_json::from_slice(bytes)?;
+    Ok(ret)
+}
+
+// errors are returned as json instead of the file, but a text or json file
+// may be returned with the same content type, so only a parsed non-zero errcode counts
+fn check_media_error(content_type: Option<&str>, data: &[u8]) -> Result<()> {
+    let maybe_json = content_type
+        .is_some_and(|t| t.starts_with("application/json") || t.starts_with("text/plain"));
+    if !maybe_json {
+        return Ok(());
+    }
+    match serde_json::from_slice::<Status>(data) {
+        Ok(status) if status.errcode != 0 => {
+            Err(Error::from_errcode(status.errcode, status.errmsg))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn secret_source(corp_id: &str, corp_secret: &str) -> TokenSource {
+    let url = format!(
+        "{}/cgi-bin/gettoken?corpid={}&corpsecret={}",
+        WX_URL, corp_id, corp_secret
+    );
+    TokenSource::Secret(url)
+}
 
+// time to wait before the next refresh, leave a margin before expiring and
+// spread the replicas' refreshes apart
+pub(crate) fn refresh_delay(expires_in: u64) -> Duration {
+    let expires_in = Duration::from_secs(expires_in);
+    let delay = if expires_in > REFRESH_MARGIN * 2 {
+        expires_in - REFRESH_MARGIN
+    } else {
+        expires_in / 2
+    };
+    with_jitter(delay)
+}
+
+// randomly shorten `d` by up to 10%
+fn with_jitter(d: Duration) -> Duration {
+    let max_jitter = d.as_millis() as u64 / 10;
+    let jitter = rand::thread_rng().gen_range(0, max_jitter + 1);
+    d - Duration::from_millis(jitter)
+}
+
+impl Client {
+    /// 构造时会同步获取一次 access token, 获取失败时返回错误
+    pub fn new(corp_id: &str, corp_secret: &str) -> Result<Self> {
+        let (tx, rx) = mpsc::channel();
+        let client = Client::start(corp_id, secret_source(corp_id, corp_secret), Some(tx));
         rx.recv().unwrap()?;
 
         info!("construct Client success");
+        Ok(client)
+    }
 
-        let ret = Client {
-            access_token,
-            http_client,
-            refresh_token_thread,
-            is_exit,
+    /// 构造时不等待 access token, 获取失败时在后台持续重试
+    ///
+    /// 在拿到 token 之前所有调用都返回 `Error::TokenUnavailable`, 刷新成功后自动恢复,
+    /// 适合在企业微信故障期间也需要正常启动的服务
+    pub fn new_degraded(corp_id: &str, corp_secret: &str) -> Self {
+        info!("construct Client in degraded mode");
+        Client::start(corp_id, secret_source(corp_id, corp_secret), None)
+    }
+
+    /// 获取下级企业 `corpid` 的 Client, 使用上级企业共享给下级的应用 `agentid` 调用下级企业的接口
+    ///
+    /// 下级企业的 access token 通过本企业的 token 换取, 和 `new` 一样会同步获取一次并在后台自动刷新,
+    /// `business_type` 0-企业互联/局校互联, 1-上下游
+    pub fn corpgroup_client(&self, corpid: &str, agentid: u64, business_type: u8) -> Result<Self> {
+        let source = TokenSource::CorpGroup {
+            parent: self.token.clone(),
+            req: CorpGroupTokenRequest {
+                corpid: corpid.to_string(),
+                business_type,
+                agentid,
+            },
         };
+        let (tx, rx) = mpsc::channel();
+        let client = Client::start(corpid, source, Some(tx));
+        rx.recv().unwrap()?;
 
-        Ok(ret)
+        info!("construct corp group Client for {} success", corpid);
+        Ok(Client {
+            timeout: self.timeout,
+            scanner: self.scanner.clone(),
+            ..client
+        })
+    }
+
+    fn start(corp_id: &str, source: TokenSource, sender: Option<Sender<Result<()>>>) -> Self {
+        Client {
+            corp_id: corp_id.to_string(),
+            token: RefreshedToken::start(source, sender),
+            http_client: reqwest::Client::new(),
+            timeout: None,
+            scanner: None,
+            jsapi_tickets: Arc::new(JsapiTickets::default()),
+        }
+    }
+
+    /// 返回一个请求超时为 `timeout` 的副本, 默认不超时
+    ///
+    /// 可以先设置一个较短的默认值, 再为上传文件等耗时的调用单独放宽:
+    /// `client.with_timeout(Duration::from_secs(300)).upload_file(..)`
+    pub fn with_timeout(&self, timeout: Duration) -> Client {
+        Client {
+            timeout: Some(timeout),
+            ..self.clone()
+        }
+    }
+
+    /// 返回一个下载素材时会先经过 `scanner` 扫描的副本
+    pub fn with_media_scanner(&self, scanner: impl MediaScanner) -> Client {
+        Client {
+            scanner: Some(Arc::new(scanner)),
+            ..self.clone()
+        }
+    }
+
+    pub fn corp_id(&self) -> &str {
+        &self.corp_id
+    }
+
+    pub fn health(&self) -> Health {
+        self.token.health()
+    }
+}
+
+/// 通用请求
+impl Client {
+    pub(crate) async fn get<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &(impl Serialize + ?Sized),
+    ) -> Result<T> {
+        let req = self.http_client.get(self.api_url(path)?).query(query);
+        self.request(req).await
+    }
+
+    pub(crate) async fn post<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &(impl Serialize + ?Sized),
+    ) -> Result<T> {
+        let req = self.http_client.post(self.api_url(path)?).json(body);
+        self.request(req).await
+    }
+
+    // download a resource which does not need the access token
+    pub(crate) async fn download(&self, url: &str) -> Result<Vec<u8>> {
+        let req = self.with_deadline(self.http_client.get(url));
+        let bytes = req.send().await?.bytes().await?;
+        Ok(bytes.to_vec())
+    }
+
+    // download a resource which is protected by a cookie instead of the access token
+    pub(crate) async fn download_with_cookie(
+        &self,
+        url: &str,
+        cookie_name: &str,
+        cookie_value: &str,
+    ) -> Result<Vec<u8>> {
+        let cookie = format!("{}={}", cookie_name, cookie_value);
+        let req = self.http_client.get(url).header("Cookie", cookie);
+        let bytes = self.with_deadline(req).send().await?.bytes().await?;
+        Ok(bytes.to_vec())
+    }
+
+    fn api_url(&self, path: &str) -> Result<String> {
+        Ok(format!("{}{}?access_token={}", WX_URL, path, self.token()?))
+    }
+
+    fn token(&self) -> Result<String> {
+        self.token.get()
+    }
+
+    fn with_deadline(&self, req: RequestBuilder) -> RequestBuilder {
+        match self.timeout {
+            Some(t) => req.timeout(t),
+            None => req,
+        }
+    }
+
+    async fn request<T: DeserializeOwned>(&self, req: RequestBuilder) -> Result<T> {
+        let bytes = self.with_deadline(req).send().await?.bytes().await?;
+        parse_response(&bytes)
     }
 }
 
 /// 素材管理
 impl Client {
+    /// 上传文件, 文件内容以流的方式读取, 不会全部读入内存
     pub async fn upload_file(&self, ty: FileType, path: &str) -> Result<UploadFileResponse> {
+        let part = file_part(path, None).await?;
+        self.upload_part(ty, part).await
+    }
+
+    /// 同 `upload_file`, `progress` 的参数为已上传和总共的字节数
+    pub async fn upload_file_with_progress(
+        &self,
+        ty: FileType,
+        path: &str,
+        progress: impl Fn(u64, u64) + Send + Sync + 'static,
+    ) -> Result<UploadFileResponse> {
+        let part = file_part(path, Some(Box::new(progress))).await?;
+        self.upload_part(ty, part).await
+    }
+
+    /// 上传内存中的文件内容, `file_name` 为上传后的文件名
+    pub async fn upload_file_data(
+        &self,
+        ty: FileType,
+        file_name: impl ToString,
+        data: Vec<u8>,
+    ) -> Result<UploadFileResponse> {
+        let part = Part::bytes(data).file_name(file_name.to_string());
+        self.upload_part(ty, part).await
+    }
+
+    /// 上传附件资源, 用于创建客户朋友圈等, `media_type` 只支持图片, 视频和文件
+    pub async fn upload_moment_attachment(
+        &self,
+        media_type: FileType,
+        attachment_type: AttachmentType,
+        file_name: impl ToString,
+        data: Vec<u8>,
+    ) -> Result<UploadFileResponse> {
+        let url = format!(
+            "{}/cgi-bin/media/upload_attachment?access_token={}&media_type={}&attachment_type={}",
+            WX_URL,
+            self.token()?,
+            media_type.type_desc(),
+            attachment_type.value()
+        );
+        let part = Part::bytes(data).file_name(file_name.to_string());
+        let ret = self.upload_media::<UploadFileResponse>(&url, part).await?;
+        if ret.errcode != 0 {
+            Err(Error::UploadMediaFailed(ret.errcode, ret.errmsg))
+        } else {
+            Ok(ret)
+        }
+    }
+
+    async fn upload_part(&self, ty: FileType, part: Part) -> Result<UploadFileResponse> {
         let url = format!(
             "{}/cgi-bin/media/upload?access_token={}&type={}",
             WX_URL,
-            self.access_token.read().unwrap(),
+            self.token()?,
             ty.type_desc()
         );
 
-        let mut f = File::open(path)?;
-        let file_name = Path::new(path)
-            .file_name()
-            .unwrap()
-            .to_str()
-            .unwrap()
-            .to_string(); // TODO need handle unwrap
-        let mut buf = vec![];
-        f.read_to_end(&mut buf)?;
-
-        let ret = self
-            .upload_media::<UploadFileResponse>(&url, buf, file_name)
-            .await?;
+        let ret = self.upload_media::<UploadFileResponse>(&url, part).await?;
         if ret.errcode != 0 {
             Err(Error::UploadMediaFailed(ret.errcode, ret.errmsg))
         } else {
@@ -159,26 +632,35 @@ impl Client {
         }
     }
 
+    /// 上传图片, 返回永久有效的图片 url
     pub async fn upload_image(&self, path: &str) -> Result<UploadImageResponse> {
+        let file_name = file_name(path)?;
+        let data = tokio::fs::read(path).await?;
+        self.upload_image_data(file_name, data).await
+    }
+
+    /// 上传内存中的图片, 只支持 jpg 和 png 格式, 大小在 5B 到 2MB 之间
+    pub async fn upload_image_data(
+        &self,
+        file_name: impl ToString,
+        data: Vec<u8>,
+    ) -> Result<UploadImageResponse> {
+        let mime = sniff_image(&data)
+            .ok_or_else(|| Error::InvalidMedia("image must be jpg or png".to_string()))?;
+        if data.len() < MIN_IMAGE_SIZE || data.len() > MAX_IMAGE_SIZE {
+            let msg = format!("image size {} is out of range 5B - 2MB", data.len());
+            return Err(Error::InvalidMedia(msg));
+        }
+
         let url = format!(
             "{}/cgi-bin/media/uploadimg?access_token={}",
             WX_URL,
-            self.access_token.read().unwrap(),
+            self.token()?,
         );
-
-        let mut f = File::open(path)?;
-        let file_name = Path::new(path)
-            .file_name()
-            .unwrap()
-            .to_str()
-            .unwrap()
-            .to_string(); // TODO need handle unwrap
-        let mut buf = vec![];
-        f.read_to_end(&mut buf)?;
-
-        let ret = self
-            .upload_media::<UploadImageResponse>(&url, buf, file_name)
-            .await?;
+        let part = Part::bytes(data)
+            .file_name(file_name.to_string())
+            .mime_str(mime)?;
+        let ret = self.upload_media::<UploadImageResponse>(&url, part).await?;
         if ret.errcode != 0 {
             Err(Error::UploadMediaFailed(ret.errcode, ret.errmsg))
         } else {
@@ -186,54 +668,205 @@ impl Client {
         }
     }
 
-    async fn upload_media<T: DeserializeOwned>(
+    /// 通过 url 异步上传视频或者文件, 最大 200M, 返回 jobid
+    ///
+    /// `md5` 为文件的 md5, 企业微信下载后会校验
+    pub async fn upload_media_by_url(
         &self,
+        ty: FileType,
+        file_name: &str,
         url: &str,
-        data: Vec<u8>,
-        file_name: String,
-    ) -> Result<T> {
-        let part = Part::bytes(data).file_name(file_name);
-        let form = Form::new().part("media", part);
+        md5: &str,
+    ) -> Result<String> {
+        let req = UploadByUrlRequest {
+            scene: 1,
+            ty: ty.type_desc(),
+            filename: file_name,
+            url,
+            md5,
+        };
+        let ret: JobResponse = self.post("/cgi-bin/media/upload_by_url", &req).await?;
+        Ok(ret.jobid)
+    }
+
+    pub async fn get_upload_by_url_result(&self, jobid: &str) -> Result<UploadByUrlResult> {
+        let req = JobRequest { jobid };
+        self.post("/cgi-bin/media/get_upload_by_url_result", &req)
+            .await
+    }
+
+    /// 轮询上传结果直到任务完成或者失败, 超过 `timeout` 后返回 `Error::PollTimeout`
+    pub async fn wait_upload_by_url(
+        &self,
+        jobid: &str,
+        timeout: Option<Duration>,
+    ) -> Result<UploadByUrlResult> {
+        wait(self, &UploadByUrlJob(jobid.to_string()), timeout).await
+    }
 
-        let ret = self
+    /// 下载临时素材, 比如收到的图片和语音消息中的 media_id
+    ///
+    /// 设置了 `MediaScanner` 时, 被隔离的文件返回 `Error::Quarantined`
+    pub async fn download_media(&self, media_id: &str) -> Result<MediaFile> {
+        let file = self.download_media_unscanned(media_id).await?;
+        if let Some(scanner) = &self.scanner {
+            if let ScanDecision::Quarantine(reason) = scanner.scan(media_id, &file).await {
+                warn!("media {} is quarantined: {}", media_id, reason);
+                return Err(Error::Quarantined {
+                    media_id: media_id.to_string(),
+                    reason,
+                });
+            }
+        }
+        Ok(file)
+    }
+
+    async fn download_media_unscanned(&self, media_id: &str) -> Result<MediaFile> {
+        let req = self
             .http_client
-            .post(url)
-            .multipart(form)
-            .send()
-            .await?
-            .json()
-            .await?;
+            .get(self.api_url("/cgi-bin/media/get")?)
+            .query(&[("media_id", media_id)]);
+        let resp = self.with_deadline(req).send().await?;
+        let header = |name| {
+            resp.headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(|v| v.to_string())
+        };
+        let content_type = header(CONTENT_TYPE);
+        let file_name = header(CONTENT_DISPOSITION).and_then(|d| parse_file_name(&d));
+        let data = resp.bytes().await?.to_vec();
+
+        check_media_error(content_type.as_deref(), &data)?;
+        Ok(MediaFile {
+            file_name,
+            content_type,
+            data,
+        })
+    }
+
+    /// 下载临时素材并保存到 `path`
+    pub async fn download_media_to_file(
+        &self,
+        media_id: &str,
+        path: impl AsRef<Path>,
+    ) -> Result<MediaFile> {
+        let file = self.download_media(media_id).await?;
+        tokio::fs::write(path, &file.data).await?;
+        Ok(file)
+    }
+
+    async fn upload_media<T: DeserializeOwned>(&self, url: &str, part: Part) -> Result<T> {
+        let form = Form::new().part("media", part);
+
+        let req = self.http_client.post(url).multipart(form);
+        let ret = self.with_deadline(req).send().await?.json().await?;
 
         Ok(ret)
     }
 }
 
+type Progress = Box<dyn Fn(u64, u64) + Send + Sync>;
+
+fn file_name(path: &str) -> Result<String> {
+    let name = Path::new(path)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "invalid file name"))?;
+    Ok(name.to_string())
+}
+
+// a multipart part which streams the file instead of reading it into memory
+async fn file_part(path: &str, progress: Option<Progress>) -> Result<Part> {
+    let file_name = file_name(path)?;
+    let file = tokio::fs::File::open(path).await?;
+    let len = file.metadata().await?.len();
+
+    let chunks = stream::unfold(Some(file), |file| async move {
+        let mut file = file?;
+        let mut buf = vec![0; UPLOAD_CHUNK_SIZE];
+        match file.read(&mut buf).await {
+            Ok(0) => None,
+            Ok(n) => {
+                buf.truncate(n);
+                Some((Ok(buf), Some(file)))
+            }
+            Err(e) => Some((Err(e), None)),
+        }
+    });
+    let mut sent = 0;
+    let chunks = chunks.inspect(move |chunk: &io::Result<Vec<u8>>| {
+        if let (Ok(c), Some(p)) = (chunk, &progress) {
+            sent += c.len() as u64;
+            p(sent, len);
+        }
+    });
+    let body = Body::wrap_stream(chunks);
+    Ok(Part::stream_with_length(body, len).file_name(file_name))
+}
+
 /// 发送应用消息
 impl Client {
     pub async fn send_msg(&self, msg: &Message) -> Result<MessageResponse> {
-        let url = format!(
-            "{}/cgi-bin/message/send?access_token={}",
-            WX_URL,
-            self.access_token.read().unwrap(),
-        );
+        self.post("/cgi-bin/message/send", msg).await
+    }
 
-        let ret = self
-            .http_client
-            .post(&url)
-            .json(&msg)
-            .send()
-            .await?
-            .json()
+    /// 撤回 24 小时内发送的消息
+    pub async fn recall_msg(&self, msgid: &str) -> Result<()> {
+        let _: IgnoredAny = self
+            .post("/cgi-bin/message/recall", &RecallRequest { msgid })
             .await?;
+        Ok(())
+    }
 
+    /// 将按钮交互型模板卡片的按钮更新为不可点击状态, 并显示 `replace_name`
+    ///
+    /// `response_code` 来自 `template_card_event` 回调或者发送消息的返回, 只能使用一次
+    pub async fn update_template_card_button(
+        &self,
+        agent_id: u64,
+        response_code: &str,
+        userids: &[&str],
+        replace_name: &str,
+    ) -> Result<()> {
+        let req = UpdateButtonRequest {
+            userids,
+            agentid: agent_id,
+            response_code,
+            button: ReplaceButton { replace_name },
+        };
+        let _: IgnoredAny = self
+            .post("/cgi-bin/message/update_template_card", &req)
+            .await?;
+        Ok(())
+    }
+
+    /// 发送消息并在 `store` 中记录消息与业务实体 `entity` 的关联
+    pub async fn send_msg_correlated(
+        &self,
+        msg: &Message,
+        entity: impl ToString,
+        store: &dyn CorrelationStore,
+    ) -> Result<MessageResponse> {
+        let ret = self.send_msg(msg).await?;
+        // nothing to correlate when the message was not sent
+        if !ret.msgid.is_empty() {
+            store.insert(SentMessage {
+                msgid: ret.msgid.clone(),
+                response_code: ret.response_code.clone(),
+                task_id: msg.task_id().map(|s| s.to_string()),
+                entity: entity.to_string(),
+                sent_at: SystemTime::now(),
+            });
+        }
         Ok(ret)
     }
 }
 
-impl Drop for Client {
+impl Drop for RefreshThread {
     fn drop(&mut self) {
         self.is_exit.store(true, Ordering::Release);
-        let handle = self.refresh_token_thread.take().unwrap();
+        let handle = self.handle.take().unwrap();
         handle.thread().unpark();
         handle
             .join()
@@ -242,6 +875,70 @@ impl Drop for Client {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_refresh_delay() {
+        for _ in 0..100 {
+            let d = refresh_delay(7200);
+            assert!(d <= Duration::from_secs(6900));
+            assert!(d >= Duration::from_secs(6210));
+
+            let d = refresh_delay(100);
+            assert!(d <= Duration::from_secs(50));
+            assert!(d >= Duration::from_secs(45));
+        }
+    }
+
+    #[test]
+    fn test_check_media_error() {
+        let err = br#"{"errcode": 40007, "errmsg": "invalid media_id"}"#;
+        assert!(check_media_error(Some("application/json"), err).is_err());
+        assert!(check_media_error(Some("text/plain"), err).is_err());
+        assert!(check_media_error(Some("image/png"), err).is_ok());
+
+        assert!(check_media_error(Some("text/plain; charset=utf-8"), b"hello world").is_ok());
+        assert!(check_media_error(Some("application/json"), br#"{"a": 1}"#).is_ok());
+    }
+
+    #[test]
+    fn test_check_token_response() {
+        let resp = |access_token: &str, expires_in| AccessTokenResponse {
+            errcode: 0,
+            errmsg: "ok".to_string(),
+            access_token: access_token.to_string(),
+            expires_in,
+        };
+        assert!(check_token_response(resp("token", 7200)).is_ok());
+        assert!(check_token_response(resp("token", 0)).is_err());
+        assert!(check_token_response(resp("", 7200)).is_err());
+
+        let failed = AccessTokenResponse {
+            errcode: 40013,
+            errmsg: "invalid corpid".to_string(),
+            ..resp("", 0)
+        };
+        match check_token_response(failed) {
+            Err(Error::GetAccessTokenFailed(40013, _)) => {}
+            r => panic!("unexpected result: {:?}", r),
+        }
+    }
+
+    #[test]
+    fn test_service_token_response() {
+        let data = r#"{"provider_access_token": "enLSZ5xxxxx", "expires_in": 7200}"#;
+        let resp: AccessTokenResponse = serde_json::from_str(data).unwrap();
+        assert_eq!(resp.errcode, 0);
+        assert_eq!(resp.access_token, "enLSZ5xxxxx");
+
+        let data = r#"{"errcode": 0, "errmsg": "ok", "suite_access_token": "61W3mEpU", "expires_in": 7200}"#;
+        let resp: AccessTokenResponse = serde_json::from_str(data).unwrap();
+        assert_eq!(resp.access_token, "61W3mEpU");
+    }
+}
+
 // for mannual test
 //#[cfg(test)]
 //mod tests {
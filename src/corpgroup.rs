@@ -0,0 +1,64 @@
+use serde::Deserialize;
+#[cfg(feature = "native")]
+use serde::Serialize;
+
+#[cfg(feature = "native")]
+use crate::client::Client;
+#[cfg(feature = "native")]
+use crate::page::Page;
+#[cfg(feature = "native")]
+use crate::Result;
+
+/// 应用共享给的下级企业, 可以用 `Client::corpgroup_client` 获取其 Client
+#[derive(Debug, Clone, Deserialize)]
+pub struct SharedCorp {
+    pub corpid: String,
+    #[serde(default)]
+    pub corp_name: String,
+    pub agentid: u64, // 下级企业中的应用 id
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct AppShareInfoRequest<'a> {
+    agentid: u64,
+    business_type: u8,
+    cursor: &'a str,
+    limit: u32,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct AppShareInfoResponse {
+    #[serde(default)]
+    ending: u8, // 1-已拉取完
+    #[serde(default)]
+    next_cursor: String,
+    #[serde(default)]
+    corp_list: Vec<SharedCorp>,
+}
+
+/// 上下游和企业互联
+#[cfg(feature = "native")]
+impl Client {
+    /// 获取应用 `agentid` 共享给的下级企业, 每页最多 100 个
+    ///
+    /// `business_type` 0-企业互联/局校互联, 1-上下游
+    pub async fn list_app_share_info(
+        &self,
+        agentid: u64,
+        business_type: u8,
+        cursor: &str,
+    ) -> Result<Page<SharedCorp>> {
+        let req = AppShareInfoRequest {
+            agentid,
+            business_type,
+            cursor,
+            limit: 100,
+        };
+        let ret: AppShareInfoResponse = self
+            .post("/cgi-bin/corpgroup/corp/list_app_share_info", &req)
+            .await?;
+        Ok(Page::new(ret.corp_list, ret.next_cursor, ret.ending == 1))
+    }
+}
@@ -0,0 +1,163 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+use crate::server::TemplateCardEvent;
+
+// response_code is valid for 72 hours
+const DEFAULT_TTL: Duration = Duration::from_secs(72 * 3600);
+// expired records are swept once every this many inserts
+const SWEEP_INTERVAL: usize = 1024;
+
+/// 一条已发送的消息与业务实体的关联
+#[derive(Debug, Clone)]
+pub struct SentMessage {
+    pub msgid: String,
+    pub response_code: String,   // 最近一次可用于更新卡片的 response_code
+    pub task_id: Option<String>, // 模板卡片的 task_id
+    pub entity: String,          // 业务实体, 比如发布单号
+    pub sent_at: SystemTime,
+}
+
+/// 保存已发送消息的关联关系, 可以实现为数据库或者 redis 等共享存储
+pub trait CorrelationStore: Send + Sync {
+    fn insert(&self, sent: SentMessage);
+    fn by_msgid(&self, msgid: &str) -> Option<SentMessage>;
+    fn by_task_id(&self, task_id: &str) -> Option<SentMessage>;
+
+    /// 根据卡片回调事件找到原消息, 并记录事件带回的新 response_code
+    fn on_card_event(&self, event: &TemplateCardEvent) -> Option<SentMessage> {
+        let mut sent = self.by_task_id(&event.task_id)?;
+        if !event.response_code.is_empty() {
+            sent.response_code = event.response_code.clone();
+            self.insert(sent.clone());
+        }
+        Some(sent)
+    }
+}
+
+/// 保存在内存中的关联关系, 超过 `ttl` 的记录在查询时移除, 并且每插入 1024 条清理一次
+pub struct MemoryCorrelationStore {
+    ttl: Duration,
+    messages: Mutex<HashMap<String, SentMessage>>, // msgid -> message
+    tasks: Mutex<HashMap<String, String>>,         // task_id -> msgid
+    inserts: AtomicUsize,
+}
+
+impl MemoryCorrelationStore {
+    /// 默认保存 72 小时, 与 response_code 的有效期一致
+    pub fn new() -> Self {
+        Self::with_ttl(DEFAULT_TTL)
+    }
+
+    pub fn with_ttl(ttl: Duration) -> Self {
+        MemoryCorrelationStore {
+            ttl,
+            messages: Mutex::new(HashMap::new()),
+            tasks: Mutex::new(HashMap::new()),
+            inserts: AtomicUsize::new(0),
+        }
+    }
+
+    fn is_expired(&self, sent: &SentMessage) -> bool {
+        sent.sent_at.elapsed().is_ok_and(|d| d > self.ttl)
+    }
+}
+
+impl Default for MemoryCorrelationStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CorrelationStore for MemoryCorrelationStore {
+    fn insert(&self, sent: SentMessage) {
+        let mut messages = self.messages.lock().unwrap();
+        let mut tasks = self.tasks.lock().unwrap();
+        if self.inserts.fetch_add(1, Ordering::Relaxed) % SWEEP_INTERVAL == SWEEP_INTERVAL - 1 {
+            messages.retain(|_, m| !self.is_expired(m));
+            tasks.retain(|_, msgid| messages.contains_key(msgid));
+        }
+        if let Some(task_id) = &sent.task_id {
+            tasks.insert(task_id.clone(), sent.msgid.clone());
+        }
+        messages.insert(sent.msgid.clone(), sent);
+    }
+
+    fn by_msgid(&self, msgid: &str) -> Option<SentMessage> {
+        let mut messages = self.messages.lock().unwrap();
+        let sent = messages.get(msgid)?;
+        if self.is_expired(sent) {
+            // its task_id goes away with the next sweep
+            messages.remove(msgid);
+            return None;
+        }
+        Some(sent.clone())
+    }
+
+    fn by_task_id(&self, task_id: &str) -> Option<SentMessage> {
+        let msgid = self.tasks.lock().unwrap().get(task_id)?.clone();
+        self.by_msgid(&msgid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_store() {
+        let store = MemoryCorrelationStore::new();
+        store.insert(SentMessage {
+            msgid: "m1".into(),
+            response_code: "code1".into(),
+            task_id: Some("deploy-42".into()),
+            entity: "deploy/42".into(),
+            sent_at: SystemTime::now(),
+        });
+        assert_eq!(store.by_msgid("m1").unwrap().entity, "deploy/42");
+
+        let event = TemplateCardEvent {
+            card_type: "button_interaction".into(),
+            event_key: "approve".into(),
+            task_id: "deploy-42".into(),
+            response_code: "code2".into(),
+        };
+        assert_eq!(store.on_card_event(&event).unwrap().entity, "deploy/42");
+        assert_eq!(store.by_msgid("m1").unwrap().response_code, "code2");
+
+        let store = MemoryCorrelationStore::with_ttl(Duration::from_secs(1));
+        store.insert(SentMessage {
+            msgid: "m2".into(),
+            response_code: "".into(),
+            task_id: None,
+            entity: "".into(),
+            sent_at: SystemTime::now() - Duration::from_secs(10),
+        });
+        assert!(store.by_msgid("m2").is_none());
+        assert!(store.messages.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_sweep() {
+        let store = MemoryCorrelationStore::with_ttl(Duration::from_secs(1));
+        let sent = |i: usize, sent_at| SentMessage {
+            msgid: format!("m{}", i),
+            response_code: "".into(),
+            task_id: Some(format!("t{}", i)),
+            entity: "".into(),
+            sent_at,
+        };
+        let expired = SystemTime::now() - Duration::from_secs(10);
+        for i in 0..SWEEP_INTERVAL - 1 {
+            store.insert(sent(i, expired));
+        }
+        assert_eq!(store.messages.lock().unwrap().len(), SWEEP_INTERVAL - 1);
+
+        store.insert(sent(SWEEP_INTERVAL, SystemTime::now()));
+        assert_eq!(store.messages.lock().unwrap().len(), 1);
+        assert_eq!(store.tasks.lock().unwrap().len(), 1);
+        assert!(store.by_task_id(&format!("t{}", SWEEP_INTERVAL)).is_some());
+    }
+}
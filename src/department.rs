@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+
+#[cfg(feature = "native")]
+use serde::de::IgnoredAny;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "native")]
+use crate::client::Client;
+#[cfg(feature = "native")]
+use crate::Result;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Department {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<u64>, // 创建时不指定则自动生成, 更新时必须指定
+    pub name: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name_en: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parentid: Option<u64>, // 创建时必须指定, 更新时不指定则不移动部门
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order: Option<u32>, // 在父部门中的次序值, order 值大的排序靠前
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub department_leader: Vec<String>, // 部门负责人的 userid
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SimpleDepartment {
+    pub id: u64,
+    pub parentid: u64,
+    pub order: u32,
+}
+
+pub trait DepartmentNode {
+    fn id(&self) -> u64;
+    fn parentid(&self) -> u64;
+}
+
+impl DepartmentNode for Department {
+    fn id(&self) -> u64 {
+        self.id.unwrap_or_default()
+    }
+
+    fn parentid(&self) -> u64 {
+        self.parentid.unwrap_or_default()
+    }
+}
+
+impl DepartmentNode for SimpleDepartment {
+    fn id(&self) -> u64 {
+        self.id
+    }
+
+    fn parentid(&self) -> u64 {
+        self.parentid
+    }
+}
+
+/// 根据部门列表构建 父部门 id -> 子部门 的映射, 子部门保持列表中的顺序
+pub fn children_map<T: DepartmentNode>(departments: &[T]) -> HashMap<u64, Vec<&T>> {
+    let mut ret: HashMap<u64, Vec<&T>> = HashMap::new();
+    for d in departments {
+        ret.entry(d.parentid()).or_default().push(d);
+    }
+    ret
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct ListDepartmentQuery {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<u64>,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct ListDepartmentResponse {
+    department: Vec<Department>,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct SimpleListDepartmentResponse {
+    department_id: Vec<SimpleDepartment>,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct CreateDepartmentResponse {
+    id: u64,
+}
+
+/// 部门管理
+#[cfg(feature = "native")]
+impl Client {
+    /// 创建部门, 返回部门 id
+    pub async fn create_department(&self, department: &Department) -> Result<u64> {
+        let ret: CreateDepartmentResponse =
+            self.post("/cgi-bin/department/create", department).await?;
+        Ok(ret.id)
+    }
+
+    /// 更新部门, `department.id` 必须指定
+    pub async fn update_department(&self, department: &Department) -> Result<()> {
+        let _: IgnoredAny = self.post("/cgi-bin/department/update", department).await?;
+        Ok(())
+    }
+
+    /// 获取部门列表, 不指定 `id` 时获取全量组织架构
+    pub async fn list_departments(&self, id: Option<u64>) -> Result<Vec<Department>> {
+        let query = ListDepartmentQuery { id };
+        let ret: ListDepartmentResponse = self.get("/cgi-bin/department/list", &query).await?;
+        Ok(ret.department)
+    }
+
+    /// 获取子部门 id 列表, 不指定 `id` 时获取全量组织架构
+    pub async fn simple_list_departments(&self, id: Option<u64>) -> Result<Vec<SimpleDepartment>> {
+        let query = ListDepartmentQuery { id };
+        let ret: SimpleListDepartmentResponse =
+            self.get("/cgi-bin/department/simplelist", &query).await?;
+        Ok(ret.department_id)
+    }
+
+    pub async fn delete_department(&self, id: u64) -> Result<()> {
+        let _: IgnoredAny = self
+            .get("/cgi-bin/department/delete", &[("id", id)])
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "native"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_children_map() {
+        let data = r#"{"department_id": [
+            {"id": 2, "parentid": 1, "order": 10},
+            {"id": 3, "parentid": 2, "order": 40},
+            {"id": 4, "parentid": 1, "order": 20}
+        ]}"#;
+        let resp: SimpleListDepartmentResponse = serde_json::from_str(data).unwrap();
+        let map = children_map(&resp.department_id);
+
+        let ids = |p| map[&p].iter().map(|d| d.id).collect::<Vec<_>>();
+        assert_eq!(ids(1), vec![2, 4]);
+        assert_eq!(ids(2), vec![3]);
+        assert!(!map.contains_key(&3));
+    }
+
+    #[test]
+    fn test_serialize_update() {
+        let d = Department {
+            id: Some(2),
+            name: "研发部".to_string(),
+            ..Default::default()
+        };
+        let v = serde_json::to_value(&d).unwrap();
+        assert_eq!(v, serde_json::json!({"id": 2, "name": "研发部"}));
+
+        let d = Department {
+            parentid: Some(1),
+            ..d
+        };
+        assert_eq!(serde_json::to_value(&d).unwrap()["parentid"], 1);
+    }
+}
@@ -0,0 +1,71 @@
+use serde::Deserialize;
+#[cfg(feature = "native")]
+use serde::Serialize;
+
+#[cfg(feature = "native")]
+use crate::client::Client;
+#[cfg(feature = "native")]
+use crate::Result;
+
+/// 公费电话的一次通话记录
+#[derive(Debug, Clone, Deserialize)]
+pub struct DialRecord {
+    pub call_time: u64,
+    #[serde(default)]
+    pub total_duration: u64, // 单位秒, 多人通话时为所有参与者的通话时长之和
+    #[serde(default)]
+    pub call_type: u8, // 1-单人通话, 2-多人通话
+    #[serde(default)]
+    pub caller: DialParty,
+    #[serde(default)]
+    pub callee: Vec<DialParty>,
+}
+
+/// 通话的一方, 企业成员的 `userid` 不为空, 外部号码的 `phone` 不为空
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct DialParty {
+    #[serde(default)]
+    pub userid: String,
+    #[serde(default)]
+    pub phone: String,
+    #[serde(default)]
+    pub duration: u64,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct DialRecordRequest {
+    start_time: u64,
+    end_time: u64,
+    offset: u32,
+    limit: u32,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct DialRecordResponse {
+    #[serde(default)]
+    record: Vec<DialRecord>,
+}
+
+/// 公费电话
+#[cfg(feature = "native")]
+impl Client {
+    /// 获取一段时间内的拨打记录, 时间跨度不超过 30 天, `limit` 最大为 100
+    pub async fn get_dial_record(
+        &self,
+        start_time: u64,
+        end_time: u64,
+        offset: u32,
+        limit: u32,
+    ) -> Result<Vec<DialRecord>> {
+        let req = DialRecordRequest {
+            start_time,
+            end_time,
+            offset,
+            limit,
+        };
+        let ret: DialRecordResponse = self.post("/cgi-bin/dial/get_dial_record", &req).await?;
+        Ok(ret.record)
+    }
+}
@@ -1,15 +1,77 @@
+use std::time::Duration;
+
 use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum Error {
     #[error("io error: {0}")]
     IoError(#[from] std::io::Error),
+    #[cfg(feature = "webhook")]
     #[error("http error: {0}")]
     HttpError(#[from] reqwest::Error),
+    #[error("json error: {0}")]
+    JsonError(#[from] serde_json::Error),
     #[error("get access token failed, code:{0}, error message: {1}")]
     GetAccessTokenFailed(u64, String),
     #[error("upload file failed, code:{0}, error message: {1}")]
     UploadMediaFailed(u64, String),
+    #[error("decrypt failed, reason: {0}")]
+    DecryptFailed(String),
+    #[error("invalid response: {0}")]
+    InvalidResponse(String),
+    #[error("media {media_id} is quarantined: {reason}")]
+    Quarantined { media_id: String, reason: String },
+    #[error("invalid media: {0}")]
+    InvalidMedia(String),
+    #[error("access token is unavailable")]
+    TokenUnavailable, // 还没有成功获取过 token, 或者已经过期且刷新一直失败
+    #[error("polling job result timed out")]
+    PollTimeout,
+    #[error("request failed, code:{0}, error message: {1}")]
+    RequestFailed(u64, String),
+    #[error("rate limited, code:{errcode}, retry after {retry_after_hint:?}")]
+    RateLimited {
+        errcode: u64,
+        retry_after_hint: Duration, // 根据错误码估计的等待时间, 接口不会返回具体值
+    },
+    #[cfg(feature = "types")]
+    #[error("build message failed: {0}")]
+    BuildMessageFailed(#[from] crate::message::MessageBuildError),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    // convert a non-zero errcode of the api response
+    #[cfg(feature = "webhook")]
+    pub(crate) fn from_errcode(errcode: u64, errmsg: String) -> Error {
+        let retry_after_hint = match errcode {
+            45033 => Duration::from_secs(1),          // 接口并发调用超过限制
+            45009 | 45011 => Duration::from_secs(60), // 接口调用超过限制, 按分钟统计
+            _ => return Error::RequestFailed(errcode, errmsg),
+        };
+        Error::RateLimited {
+            errcode,
+            retry_after_hint,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "webhook"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_errcode() {
+        match Error::from_errcode(45009, "api freq out of limit".into()) {
+            Error::RateLimited {
+                retry_after_hint, ..
+            } => assert_eq!(retry_after_hint, Duration::from_secs(60)),
+            e => panic!("unexpected error: {}", e),
+        }
+        assert!(matches!(
+            Error::from_errcode(40014, "invalid access_token".into()),
+            Error::RequestFailed(40014, _)
+        ));
+    }
+}
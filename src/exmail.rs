@@ -0,0 +1,215 @@
+#[cfg(feature = "native")]
+use serde::de::IgnoredAny;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "native")]
+use crate::client::Client;
+#[cfg(feature = "native")]
+use crate::Result;
+
+/// 邮件群组, 创建和修改时使用, 修改时为 None 的字段不变
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MailGroup {
+    pub groupid: String, // 群组的邮箱地址
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub groupname: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email_list: Option<MailList<String>>, // 群组成员的邮箱地址
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag_list: Option<MailList<u64>>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub department_list: Option<MailList<u64>>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group_list: Option<MailList<String>>, // 子群组的邮箱地址
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_type: Option<u8>, // 群发权限, 0-企业成员, 1-任何人, 2-组内成员, 3-自定义
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_emaillist: Option<MailList<String>>, // allow_type 为 3 时允许群发的成员
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_departmentlist: Option<MailList<u64>>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_taglist: Option<MailList<u64>>,
+}
+
+impl MailGroup {
+    pub fn new(groupid: impl ToString, groupname: impl ToString) -> Self {
+        MailGroup {
+            groupid: groupid.to_string(),
+            groupname: Some(groupname.to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// 群组成员为部门 `departments` 的成员, 用于让邮件群组和部门保持一致
+    pub fn departments(mut self, departments: &[u64]) -> Self {
+        self.department_list = Some(MailList::new(departments.to_vec()));
+        self
+    }
+
+    pub fn emails(mut self, emails: &[&str]) -> Self {
+        let emails = emails.iter().map(|s| s.to_string()).collect();
+        self.email_list = Some(MailList::new(emails));
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MailList<T> {
+    #[serde(default = "Vec::new")]
+    pub list: Vec<T>,
+}
+
+impl<T> MailList<T> {
+    pub fn new(list: Vec<T>) -> Self {
+        MailList { list }
+    }
+}
+
+impl<T> Default for MailList<T> {
+    fn default() -> Self {
+        MailList::new(vec![])
+    }
+}
+
+/// 成员邮箱的功能设置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MailOption {
+    ForceSecureLogin, // 强制启用安全登录
+    ImapSmtp,         // IMAP/SMTP 服务
+    PopSmtp,          // POP/SMTP 服务
+    SecureLogin,      // 是否启用安全登录
+}
+
+impl MailOption {
+    #[cfg(feature = "native")]
+    fn code(self) -> u8 {
+        match self {
+            MailOption::ForceSecureLogin => 1,
+            MailOption::ImapSmtp => 2,
+            MailOption::PopSmtp => 3,
+            MailOption::SecureLogin => 4,
+        }
+    }
+
+    #[cfg(feature = "native")]
+    fn from_code(code: u8) -> Option<Self> {
+        match code {
+            1 => Some(MailOption::ForceSecureLogin),
+            2 => Some(MailOption::ImapSmtp),
+            3 => Some(MailOption::PopSmtp),
+            4 => Some(MailOption::SecureLogin),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct GroupIdRequest<'a> {
+    groupid: &'a str,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize, Deserialize)]
+struct OptionItem {
+    #[serde(rename = "type")]
+    ty: u8,
+    value: String, // "0" 或者 "1"
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct GetUserOptionRequest<'a> {
+    userid: &'a str,
+    #[serde(rename = "type")]
+    ty: Vec<u8>,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct GetUserOptionResponse {
+    #[serde(default)]
+    option: MailList<OptionItem>,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct UpdateUserOptionRequest<'a> {
+    userid: &'a str,
+    option: MailList<OptionItem>,
+}
+
+/// 企业邮箱
+#[cfg(feature = "native")]
+impl Client {
+    pub async fn create_mail_group(&self, group: &MailGroup) -> Result<()> {
+        let _: IgnoredAny = self.post("/cgi-bin/exmail/group/create", group).await?;
+        Ok(())
+    }
+
+    /// 修改邮件群组, 列表字段会整体覆盖
+    pub async fn update_mail_group(&self, group: &MailGroup) -> Result<()> {
+        let _: IgnoredAny = self.post("/cgi-bin/exmail/group/update", group).await?;
+        Ok(())
+    }
+
+    pub async fn delete_mail_group(&self, groupid: &str) -> Result<()> {
+        let req = GroupIdRequest { groupid };
+        let _: IgnoredAny = self.post("/cgi-bin/exmail/group/delete", &req).await?;
+        Ok(())
+    }
+
+    pub async fn get_mail_group(&self, groupid: &str) -> Result<MailGroup> {
+        self.get("/cgi-bin/exmail/group/get", &[("groupid", groupid)])
+            .await
+    }
+
+    /// 获取成员邮箱的功能设置, 返回的设置与 `options` 一一对应
+    pub async fn get_mail_user_option(
+        &self,
+        userid: &str,
+        options: &[MailOption],
+    ) -> Result<Vec<(MailOption, bool)>> {
+        let req = GetUserOptionRequest {
+            userid,
+            ty: options.iter().map(|o| o.code()).collect(),
+        };
+        let ret: GetUserOptionResponse = self.post("/cgi-bin/exmail/useroption/get", &req).await?;
+        let ret = ret
+            .option
+            .list
+            .into_iter()
+            .filter_map(|o| MailOption::from_code(o.ty).map(|ty| (ty, o.value == "1")))
+            .collect();
+        Ok(ret)
+    }
+
+    pub async fn update_mail_user_option(
+        &self,
+        userid: &str,
+        options: &[(MailOption, bool)],
+    ) -> Result<()> {
+        let list = options
+            .iter()
+            .map(|&(ty, enabled)| OptionItem {
+                ty: ty.code(),
+                value: if enabled { "1" } else { "0" }.to_string(),
+            })
+            .collect();
+        let req = UpdateUserOptionRequest {
+            userid,
+            option: MailList::new(list),
+        };
+        let _: IgnoredAny = self.post("/cgi-bin/exmail/useroption/update", &req).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,211 @@
+#[cfg(feature = "native")]
+use std::time::Duration;
+
+use serde::Deserialize;
+#[cfg(feature = "native")]
+use serde::Serialize;
+
+#[cfg(feature = "native")]
+use crate::client::Client;
+#[cfg(feature = "native")]
+use crate::poll::{wait, ExportJob};
+#[cfg(feature = "native")]
+use crate::server::crypto::{aes_decrypt, decode_aes_key};
+#[cfg(feature = "native")]
+use crate::tag::TagId;
+#[cfg(feature = "native")]
+use crate::{Error, Result};
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct ExportRequest<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tagid: Option<TagId>,
+    encoding_aeskey: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    block_size: Option<u64>,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct ExportResponse {
+    jobid: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExportResult {
+    pub status: u8, // 0-未处理，1-处理中，2-完成，3-异常失败
+    #[serde(default)]
+    pub data_list: Vec<ExportData>,
+}
+
+impl ExportResult {
+    pub fn is_finished(&self) -> bool {
+        self.status == 2 || self.status == 3
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.status == 2
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExportData {
+    pub url: String,
+    pub size: u64,
+    pub md5: String,
+}
+
+/// 异步导出接口
+///
+/// `encoding_aeskey` 为 43 位的 base64 字符串, 用于加密导出的文件,
+/// `block_size` 为每块数据的人员数和部门数之和, 默认且最大为 10^6
+#[cfg(feature = "native")]
+impl Client {
+    /// 导出成员, 返回 jobid
+    pub async fn export_simple_user(
+        &self,
+        encoding_aeskey: &str,
+        block_size: Option<u64>,
+    ) -> Result<String> {
+        self.export(
+            "/cgi-bin/export/simple_user",
+            None,
+            encoding_aeskey,
+            block_size,
+        )
+        .await
+    }
+
+    /// 导出成员详情, 返回 jobid
+    pub async fn export_user(
+        &self,
+        encoding_aeskey: &str,
+        block_size: Option<u64>,
+    ) -> Result<String> {
+        self.export("/cgi-bin/export/user", None, encoding_aeskey, block_size)
+            .await
+    }
+
+    /// 导出部门, 返回 jobid
+    pub async fn export_department(
+        &self,
+        encoding_aeskey: &str,
+        block_size: Option<u64>,
+    ) -> Result<String> {
+        self.export(
+            "/cgi-bin/export/department",
+            None,
+            encoding_aeskey,
+            block_size,
+        )
+        .await
+    }
+
+    /// 导出标签成员, 返回 jobid
+    pub async fn export_taguser(
+        &self,
+        tagid: TagId,
+        encoding_aeskey: &str,
+        block_size: Option<u64>,
+    ) -> Result<String> {
+        self.export(
+            "/cgi-bin/export/taguser",
+            Some(tagid),
+            encoding_aeskey,
+            block_size,
+        )
+        .await
+    }
+
+    pub async fn get_export_result(&self, jobid: &str) -> Result<ExportResult> {
+        self.get("/cgi-bin/export/get_result", &[("jobid", jobid)])
+            .await
+    }
+
+    /// 轮询导出结果直到任务完成或者失败, 超过 `timeout` 后返回 `Error::PollTimeout`
+    pub async fn wait_export_job(
+        &self,
+        jobid: &str,
+        timeout: Option<Duration>,
+    ) -> Result<ExportResult> {
+        wait(self, &ExportJob(jobid.to_string()), timeout).await
+    }
+
+    /// 下载并解密导出的文件, 返回每块数据解密后的 json 内容
+    pub async fn download_export(
+        &self,
+        result: &ExportResult,
+        encoding_aeskey: &str,
+    ) -> Result<Vec<Vec<u8>>> {
+        let aes_key =
+            decode_aes_key(encoding_aeskey).map_err(|e| Error::DecryptFailed(format!("{}", e)))?;
+        let mut ret = Vec::with_capacity(result.data_list.len());
+        for d in &result.data_list {
+            let encrypted = self.download(&d.url).await?;
+            let data = decrypt_export(&aes_key, &encrypted)?;
+            ret.push(data);
+        }
+        Ok(ret)
+    }
+
+    async fn export(
+        &self,
+        path: &str,
+        tagid: Option<TagId>,
+        encoding_aeskey: &str,
+        block_size: Option<u64>,
+    ) -> Result<String> {
+        let req = ExportRequest {
+            tagid,
+            encoding_aeskey,
+            block_size,
+        };
+        let ret: ExportResponse = self.post(path, &req).await?;
+        Ok(ret.jobid)
+    }
+}
+
+// the exported file is the aes-256-cbc ciphertext, either raw or base64 encoded
+#[cfg(feature = "native")]
+fn decrypt_export(aes_key: &[u8], data: &[u8]) -> Result<Vec<u8>> {
+    let is_base64 = data
+        .iter()
+        .all(|b| b.is_ascii_alphanumeric() || b"+/=\r\n".contains(b));
+    let encrypted = if is_base64 {
+        let trimmed: Vec<u8> = data
+            .iter()
+            .copied()
+            .filter(|b| !b.is_ascii_whitespace())
+            .collect();
+        base64::decode(&trimmed)
+            .map_err(|_| Error::DecryptFailed("invalid base64 string".to_string()))?
+    } else {
+        data.to_vec()
+    };
+    aes_decrypt(aes_key, &encrypted).map_err(|e| Error::DecryptFailed(format!("{}", e)))
+}
+
+#[cfg(all(test, feature = "native"))]
+mod tests {
+    use super::*;
+
+    use aes::Aes256;
+    use block_modes::block_padding::Pkcs7;
+    use block_modes::{BlockMode, Cbc};
+
+    #[test]
+    fn test_decrypt_export() {
+        let aes_key = decode_aes_key("4Ma3YBrSBbX2aez8MJpXGBne5LSDwgGqHbhM9WPYIws").unwrap();
+        let data = br#"{"userlist":[{"userid":"zhangsan","department":[1]}]}"#;
+        let cipher = Cbc::<Aes256, Pkcs7>::new_var(&aes_key, &aes_key[0..16]).unwrap();
+        let encrypted = cipher.encrypt_vec(data);
+
+        let raw = decrypt_export(&aes_key, &encrypted).unwrap();
+        assert_eq!(raw, data);
+
+        let encoded = base64::encode(&encrypted);
+        let decoded = decrypt_export(&aes_key, encoded.as_bytes()).unwrap();
+        assert_eq!(decoded, data);
+    }
+}
@@ -0,0 +1,233 @@
+#[cfg(feature = "native")]
+use serde::de::IgnoredAny;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "native")]
+use crate::client::Client;
+#[cfg(feature = "native")]
+use crate::page::Page;
+#[cfg(feature = "native")]
+use crate::Result;
+
+/// 获客链接
+#[derive(Debug, Clone, Deserialize)]
+pub struct AcquisitionLink {
+    #[serde(default)]
+    pub link_id: String,
+    pub link_name: String,
+    #[serde(default)]
+    #[serde(alias = "link_url")]
+    pub url: String,
+    #[serde(default)]
+    pub create_time: u64,
+    #[serde(default)]
+    pub skip_verify: bool,
+}
+
+/// 获客链接关联的成员和部门
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AcquisitionRange {
+    #[serde(default)]
+    pub user_list: Vec<String>,
+    #[serde(default)]
+    pub department_list: Vec<u64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct AcquisitionLinkDetail {
+    pub link: AcquisitionLink,
+    pub range: AcquisitionRange,
+}
+
+/// 通过获客链接添加的客户
+#[derive(Debug, Clone, Deserialize)]
+pub struct AcquisitionCustomer {
+    pub external_userid: String,
+    pub userid: String,
+    #[serde(default)]
+    pub chat_status: u8, // 0-未回复, 1-已回复, 2-已建群
+    #[serde(default)]
+    pub state: String,
+}
+
+/// 获客额度
+#[derive(Debug, Clone, Deserialize)]
+pub struct AcquisitionQuota {
+    pub total: u64,
+    pub balance: u64,
+    #[serde(default)]
+    pub quota_list: Vec<AcquisitionQuotaItem>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AcquisitionQuotaItem {
+    pub expire_date: u64,
+    pub balance: u64,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct CursorRequest<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    link_id: Option<&'a str>,
+    limit: u32,
+    cursor: &'a str,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct LinkIdRequest<'a> {
+    link_id: &'a str,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct LinkRequest<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    link_id: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    link_name: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    range: Option<&'a AcquisitionRange>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    skip_verify: Option<bool>,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct ListLinkResponse {
+    #[serde(default)]
+    link_id_list: Vec<String>,
+    #[serde(default)]
+    next_cursor: String,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct LinkResponse {
+    link: AcquisitionLink,
+    #[serde(default)]
+    range: AcquisitionRange,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct CustomerResponse {
+    #[serde(default)]
+    customer_list: Vec<AcquisitionCustomer>,
+    #[serde(default)]
+    next_cursor: String,
+}
+
+/// 获客助手
+#[cfg(feature = "native")]
+impl Client {
+    /// 获取获客链接的 id 列表, 每页最多 100 条
+    pub async fn list_acquisition_links(&self, cursor: &str) -> Result<Page<String>> {
+        let req = CursorRequest {
+            link_id: None,
+            limit: 100,
+            cursor,
+        };
+        let ret: ListLinkResponse = self
+            .post(
+                "/cgi-bin/externalcontact/customer_acquisition/list_link",
+                &req,
+            )
+            .await?;
+        Ok(Page::new(ret.link_id_list, ret.next_cursor, false))
+    }
+
+    pub async fn get_acquisition_link(&self, link_id: &str) -> Result<AcquisitionLinkDetail> {
+        let req = LinkIdRequest { link_id };
+        let ret: LinkResponse = self
+            .post("/cgi-bin/externalcontact/customer_acquisition/get", &req)
+            .await?;
+        Ok(AcquisitionLinkDetail {
+            link: ret.link,
+            range: ret.range,
+        })
+    }
+
+    /// 创建获客链接, `skip_verify` 为 true 时客户添加成员无需验证
+    pub async fn create_acquisition_link(
+        &self,
+        link_name: &str,
+        range: &AcquisitionRange,
+        skip_verify: bool,
+    ) -> Result<AcquisitionLink> {
+        let req = LinkRequest {
+            link_id: None,
+            link_name: Some(link_name),
+            range: Some(range),
+            skip_verify: Some(skip_verify),
+        };
+        let ret: LinkResponse = self
+            .post(
+                "/cgi-bin/externalcontact/customer_acquisition/create_link",
+                &req,
+            )
+            .await?;
+        Ok(ret.link)
+    }
+
+    /// 修改获客链接, 为 None 的字段不修改, 指定 `range` 时会覆盖原有的范围
+    pub async fn update_acquisition_link(
+        &self,
+        link_id: &str,
+        link_name: Option<&str>,
+        range: Option<&AcquisitionRange>,
+        skip_verify: Option<bool>,
+    ) -> Result<()> {
+        let req = LinkRequest {
+            link_id: Some(link_id),
+            link_name,
+            range,
+            skip_verify,
+        };
+        let _: IgnoredAny = self
+            .post(
+                "/cgi-bin/externalcontact/customer_acquisition/update_link",
+                &req,
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub async fn delete_acquisition_link(&self, link_id: &str) -> Result<()> {
+        let req = LinkIdRequest { link_id };
+        let _: IgnoredAny = self
+            .post(
+                "/cgi-bin/externalcontact/customer_acquisition/delete_link",
+                &req,
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// 获取通过获客链接添加的客户, 每页最多 1000 条
+    pub async fn get_acquisition_customers(
+        &self,
+        link_id: &str,
+        cursor: &str,
+    ) -> Result<Page<AcquisitionCustomer>> {
+        let req = CursorRequest {
+            link_id: Some(link_id),
+            limit: 1000,
+            cursor,
+        };
+        let ret: CustomerResponse = self
+            .post(
+                "/cgi-bin/externalcontact/customer_acquisition/customer",
+                &req,
+            )
+            .await?;
+        Ok(Page::new(ret.customer_list, ret.next_cursor, false))
+    }
+
+    /// 查询剩余的获客额度
+    pub async fn get_acquisition_quota(&self) -> Result<AcquisitionQuota> {
+        self.get("/cgi-bin/externalcontact/customer_acquisition_quota", &())
+            .await
+    }
+}
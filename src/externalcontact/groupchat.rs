@@ -0,0 +1,212 @@
+#[cfg(feature = "native")]
+use serde::de::IgnoredAny;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "native")]
+use super::ConfigIdRequest;
+#[cfg(feature = "native")]
+use crate::client::Client;
+#[cfg(feature = "native")]
+use crate::page::Page;
+#[cfg(feature = "native")]
+use crate::Result;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GroupChatStatus {
+    pub chat_id: String,
+    pub status: u8, // 0-跟进人正常, 1-跟进人离职, 2-离职继承中, 3-离职继承完成
+}
+
+/// 客户群详情
+#[derive(Debug, Clone, Deserialize)]
+pub struct GroupChat {
+    pub chat_id: String,
+    #[serde(default)]
+    pub name: String,
+    pub owner: String,
+    #[serde(default)]
+    pub create_time: u64,
+    #[serde(default)]
+    pub notice: String,
+    #[serde(default)]
+    pub member_list: Vec<GroupChatMember>,
+    #[serde(default)]
+    pub admin_list: Vec<GroupChatAdmin>,
+    #[serde(default)]
+    pub member_version: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GroupChatMember {
+    pub userid: String, // 成员为外部联系人时为 external_userid
+    #[serde(rename = "type")]
+    pub ty: u8, // 1-企业成员, 2-外部联系人
+    #[serde(default)]
+    pub unionid: String,
+    #[serde(default)]
+    pub join_time: u64,
+    #[serde(default)]
+    pub join_scene: u8, // 1-由群成员邀请, 2-由群成员通过链接入群, 3-通过扫描群二维码入群
+    #[serde(default)]
+    pub invitor: Option<GroupChatAdmin>, // 邀请者, 目前只有 join_scene 为 1 时返回
+    #[serde(default)]
+    pub group_nickname: String,
+    #[serde(default)]
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GroupChatAdmin {
+    pub userid: String,
+}
+
+/// 加入群聊 的配置, 用于生成进群二维码或者小程序按钮
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct JoinWay {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config_id: Option<String>, // 创建时不指定, 更新时必须指定
+    pub scene: u8, // 1-群的小程序插件, 2-群的二维码插件
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remark: Option<String>, // 最多 30 个字符
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_create_room: Option<u8>, // 群满了之后是否自动新建群, 0-否, 1-是, 默认为 1
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub room_base_name: Option<String>, // 自动建群的群名前缀
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub room_base_id: Option<u32>, // 自动建群的群起始序号
+    pub chat_id_list: Vec<String>, // 最多 5 个
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>, // 渠道参数, 最长 30 个字符
+    #[serde(default)]
+    #[serde(skip_serializing)]
+    pub qr_code: String, // 只在读取时返回
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct AddJoinWayResponse {
+    config_id: String,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct GetJoinWayResponse {
+    join_way: JoinWay,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct GroupChatListRequest<'a> {
+    status_filter: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    owner_filter: Option<OwnerFilter<'a>>,
+    cursor: &'a str,
+    limit: u32,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct OwnerFilter<'a> {
+    userid_list: &'a [&'a str],
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct GroupChatListResponse {
+    #[serde(default)]
+    group_chat_list: Vec<GroupChatStatus>,
+    #[serde(default)]
+    next_cursor: String,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct GroupChatRequest<'a> {
+    chat_id: &'a str,
+    need_name: u8,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct GroupChatResponse {
+    group_chat: GroupChat,
+}
+
+/// 客户群管理
+#[cfg(feature = "native")]
+impl Client {
+    /// 获取客户群列表, `owner` 为空时不按群主过滤, `cursor` 第一页传空字符串, `limit` 最大为 1000
+    pub async fn list_group_chats(
+        &self,
+        owner: &[&str],
+        cursor: &str,
+        limit: u32,
+    ) -> Result<Page<GroupChatStatus>> {
+        let owner_filter = if owner.is_empty() {
+            None
+        } else {
+            Some(OwnerFilter { userid_list: owner })
+        };
+        let req = GroupChatListRequest {
+            status_filter: 0,
+            owner_filter,
+            cursor,
+            limit,
+        };
+        let resp: GroupChatListResponse = self
+            .post("/cgi-bin/externalcontact/groupchat/list", &req)
+            .await?;
+        Ok(Page::new(resp.group_chat_list, resp.next_cursor, false))
+    }
+
+    /// 获取客户群详情, 包括群成员的名字
+    pub async fn get_group_chat(&self, chat_id: &str) -> Result<GroupChat> {
+        let req = GroupChatRequest {
+            chat_id,
+            need_name: 1,
+        };
+        let resp: GroupChatResponse = self
+            .post("/cgi-bin/externalcontact/groupchat/get", &req)
+            .await?;
+        Ok(resp.group_chat)
+    }
+
+    /// 配置客户群进群方式, 返回 config_id
+    pub async fn add_join_way(&self, way: &JoinWay) -> Result<String> {
+        let resp: AddJoinWayResponse = self
+            .post("/cgi-bin/externalcontact/groupchat/add_join_way", way)
+            .await?;
+        Ok(resp.config_id)
+    }
+
+    /// 获取进群方式的配置, 包括二维码链接
+    pub async fn get_join_way(&self, config_id: &str) -> Result<JoinWay> {
+        let req = ConfigIdRequest { config_id };
+        let resp: GetJoinWayResponse = self
+            .post("/cgi-bin/externalcontact/groupchat/get_join_way", &req)
+            .await?;
+        Ok(resp.join_way)
+    }
+
+    /// 更新进群方式, `way.config_id` 必须指定, 会覆盖原有的配置
+    pub async fn update_join_way(&self, way: &JoinWay) -> Result<()> {
+        let _: IgnoredAny = self
+            .post("/cgi-bin/externalcontact/groupchat/update_join_way", way)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn del_join_way(&self, config_id: &str) -> Result<()> {
+        let req = ConfigIdRequest { config_id };
+        let _: IgnoredAny = self
+            .post("/cgi-bin/externalcontact/groupchat/del_join_way", &req)
+            .await?;
+        Ok(())
+    }
+}
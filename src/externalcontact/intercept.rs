@@ -0,0 +1,154 @@
+#[cfg(feature = "native")]
+use serde::de::IgnoredAny;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "native")]
+use crate::client::Client;
+#[cfg(feature = "native")]
+use crate::Result;
+
+/// 新建的敏感词规则
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct InterceptRule {
+    pub rule_name: String,      // 最多 20 个字符
+    pub word_list: Vec<String>, // 最多 300 个, 每个最多 32 个字符
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub semantics_list: Vec<u8>, // 额外的拦截语义, 1-手机号, 2-邮箱地址, 3-红包
+    pub intercept_type: u8,     // 1-警告并拦截发送, 2-仅发警告
+    pub applicable_range: InterceptRange,
+}
+
+/// 规则适用的成员和部门
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InterceptRange {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub user_list: Vec<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub department_list: Vec<u64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct InterceptRuleSummary {
+    pub rule_id: String,
+    pub rule_name: String,
+    #[serde(default)]
+    pub create_time: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct InterceptRuleDetail {
+    pub rule_id: String,
+    pub rule_name: String,
+    #[serde(default)]
+    pub word_list: Vec<String>,
+    #[serde(default)]
+    pub extra_rule: InterceptExtraRule,
+    pub intercept_type: u8,
+    #[serde(default)]
+    pub applicable_range: InterceptRange,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InterceptExtraRule {
+    #[serde(default)]
+    pub semantics_list: Vec<u8>,
+}
+
+/// 修改敏感词规则, 为 None 的字段不修改
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct InterceptRuleUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rule_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub word_list: Option<Vec<String>>, // 会覆盖原有的敏感词
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extra_rule: Option<InterceptExtraRule>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub intercept_type: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub add_applicable_range: Option<InterceptRange>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remove_applicable_range: Option<InterceptRange>,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct RuleIdRequest<'a> {
+    rule_id: &'a str,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct UpdateRuleRequest<'a> {
+    rule_id: &'a str,
+    #[serde(flatten)]
+    update: &'a InterceptRuleUpdate,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct AddRuleResponse {
+    rule_id: String,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct RuleListResponse {
+    #[serde(default)]
+    rule_list: Vec<InterceptRuleSummary>,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct RuleResponse {
+    rule: InterceptRuleDetail,
+}
+
+/// 敏感词拦截规则
+#[cfg(feature = "native")]
+impl Client {
+    /// 新建敏感词规则, 返回 rule_id
+    pub async fn add_intercept_rule(&self, rule: &InterceptRule) -> Result<String> {
+        let ret: AddRuleResponse = self
+            .post("/cgi-bin/externalcontact/add_intercept_rule", rule)
+            .await?;
+        Ok(ret.rule_id)
+    }
+
+    pub async fn get_intercept_rule_list(&self) -> Result<Vec<InterceptRuleSummary>> {
+        let ret: RuleListResponse = self
+            .get("/cgi-bin/externalcontact/get_intercept_rule_list", &())
+            .await?;
+        Ok(ret.rule_list)
+    }
+
+    pub async fn get_intercept_rule(&self, rule_id: &str) -> Result<InterceptRuleDetail> {
+        let req = RuleIdRequest { rule_id };
+        let ret: RuleResponse = self
+            .post("/cgi-bin/externalcontact/get_intercept_rule", &req)
+            .await?;
+        Ok(ret.rule)
+    }
+
+    pub async fn update_intercept_rule(
+        &self,
+        rule_id: &str,
+        update: &InterceptRuleUpdate,
+    ) -> Result<()> {
+        let req = UpdateRuleRequest { rule_id, update };
+        let _: IgnoredAny = self
+            .post("/cgi-bin/externalcontact/update_intercept_rule", &req)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn del_intercept_rule(&self, rule_id: &str) -> Result<()> {
+        let req = RuleIdRequest { rule_id };
+        let _: IgnoredAny = self
+            .post("/cgi-bin/externalcontact/del_intercept_rule", &req)
+            .await?;
+        Ok(())
+    }
+}
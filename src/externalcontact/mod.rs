@@ -0,0 +1,337 @@
+//! 客户联系
+
+mod acquisition;
+mod groupchat;
+mod intercept;
+mod moment;
+mod msg_template;
+mod statistic;
+mod tag;
+mod transfer;
+
+pub use acquisition::*;
+pub use groupchat::*;
+pub use intercept::*;
+pub use moment::*;
+pub use msg_template::*;
+pub use statistic::*;
+pub use tag::*;
+pub use transfer::*;
+
+#[cfg(feature = "native")]
+use serde::de::IgnoredAny;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "native")]
+use crate::client::Client;
+#[cfg(feature = "native")]
+use crate::page::Page;
+#[cfg(feature = "native")]
+use crate::Result;
+
+/// 客户的基础信息
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExternalContact {
+    pub external_userid: String,
+    pub name: String,
+    #[serde(default)]
+    pub avatar: String,
+    #[serde(rename = "type")]
+    pub ty: u8, // 1-微信用户, 2-企业微信用户
+    #[serde(default)]
+    pub gender: u8, // 0-未知, 1-男性, 2-女性
+    #[serde(default)]
+    pub unionid: String,
+    #[serde(default)]
+    pub position: String,
+    #[serde(default)]
+    pub corp_name: String,
+    #[serde(default)]
+    pub corp_full_name: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FollowUserTag {
+    #[serde(default)]
+    pub group_name: String,
+    #[serde(default)]
+    pub tag_name: String,
+    #[serde(default)]
+    pub tag_id: String, // 个人标签没有 tag_id
+    #[serde(rename = "type")]
+    pub ty: u8, // 1-企业标签, 2-用户自定义标签, 3-规则组标签
+}
+
+/// 添加了客户的成员, 以及成员对客户的备注和标签
+#[derive(Debug, Clone, Deserialize)]
+pub struct FollowUser {
+    pub userid: String,
+    #[serde(default)]
+    pub remark: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub createtime: u64,
+    #[serde(default)]
+    pub tags: Vec<FollowUserTag>, // 只在 get_external_contact 中返回
+    #[serde(default)]
+    pub tag_id: Vec<String>, // 只在 batch_get_by_user 中返回
+    #[serde(default)]
+    pub remark_corp_name: String,
+    #[serde(default)]
+    pub remark_mobiles: Vec<String>,
+    #[serde(default)]
+    pub oper_userid: String,
+    #[serde(default)]
+    pub add_way: u32, // 添加客户的来源, 比如 1-扫描二维码, 2-搜索手机号
+    #[serde(default)]
+    pub state: String, // 联系我 方式中配置的 state
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExternalContactDetail {
+    pub external_contact: ExternalContact,
+    pub follow_user: Vec<FollowUser>,
+}
+
+/// `batch_get_by_user` 返回的客户, 只包含指定成员的跟进信息
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExternalContactByUser {
+    pub external_contact: ExternalContact,
+    pub follow_info: FollowUser,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct ExternalContactResponse {
+    external_contact: ExternalContact,
+    #[serde(default)]
+    follow_user: Vec<FollowUser>,
+    #[serde(default)]
+    next_cursor: String,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct BatchGetByUserRequest<'a> {
+    userid_list: &'a [&'a str],
+    cursor: &'a str,
+    limit: u32,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct BatchGetByUserResponse {
+    #[serde(default)]
+    external_contact_list: Vec<ExternalContactByUser>,
+    #[serde(default)]
+    next_cursor: String,
+}
+
+/// 成员对客户的备注, 为 None 的字段不修改, 传空值会清空对应字段
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ExternalContactRemark {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remark: Option<String>, // 最多 20 个字符
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>, // 最多 150 个字符
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remark_company: Option<String>, // 只在客户不是企业微信用户时有效
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remark_mobiles: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remark_pic_mediaid: Option<String>, // 名片图片的临时素材 id
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct RemarkRequest<'a> {
+    userid: &'a str,
+    external_userid: &'a str,
+    #[serde(flatten)]
+    remark: &'a ExternalContactRemark,
+}
+
+/// 联系我 的配置, 用于生成带 state 参数的二维码或者小程序按钮
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ContactWay {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config_id: Option<String>, // 创建时不指定, 更新时必须指定
+    #[serde(rename = "type")]
+    pub ty: u8, // 1-单人, 2-多人
+    pub scene: u8, // 1-在小程序中联系, 2-通过二维码联系
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<u8>, // 小程序中联系按钮的样式
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remark: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skip_verify: Option<bool>, // 外部客户添加时是否无需验证, 默认为 true
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>, // 渠道参数, 最长 30 个字符, 会在添加客户的回调事件中带回
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub user: Vec<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub party: Vec<u64>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_temp: Option<bool>, // 是否临时会话模式
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_in: Option<u64>, // 临时会话二维码的有效期, 单位秒
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chat_expires_in: Option<u64>, // 临时会话的有效期, 单位秒
+    #[serde(default)]
+    #[serde(skip_serializing)]
+    pub qr_code: String, // 只在读取时返回
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AddContactWayResponse {
+    pub config_id: String,
+    #[serde(default)]
+    pub qr_code: String, // scene 为 2 时返回
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct ConfigIdRequest<'a> {
+    config_id: &'a str,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct GetContactWayResponse {
+    contact_way: ContactWay,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct FollowUserListResponse {
+    #[serde(default)]
+    follow_user: Vec<String>,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct ExternalContactListResponse {
+    #[serde(default)]
+    external_userid: Vec<String>,
+}
+
+/// 客户联系
+#[cfg(feature = "native")]
+impl Client {
+    /// 获取配置了客户联系功能的成员列表
+    pub async fn get_follow_user_list(&self) -> Result<Vec<String>> {
+        let ret: FollowUserListResponse = self
+            .get("/cgi-bin/externalcontact/get_follow_user_list", &())
+            .await?;
+        Ok(ret.follow_user)
+    }
+
+    /// 获取成员添加的客户的 external_userid 列表
+    pub async fn list_external_contacts(&self, userid: &str) -> Result<Vec<String>> {
+        let ret: ExternalContactListResponse = self
+            .get("/cgi-bin/externalcontact/list", &[("userid", userid)])
+            .await?;
+        Ok(ret.external_userid)
+    }
+
+    /// 获取客户详情, 添加了该客户的成员超过 500 人时会自动翻页
+    pub async fn get_external_contact(
+        &self,
+        external_userid: &str,
+    ) -> Result<ExternalContactDetail> {
+        let mut cursor = String::new();
+        let mut follow_user = vec![];
+        loop {
+            let query = [("external_userid", external_userid), ("cursor", &cursor)];
+            let resp: ExternalContactResponse =
+                self.get("/cgi-bin/externalcontact/get", &query).await?;
+            follow_user.extend(resp.follow_user);
+            if resp.next_cursor.is_empty() {
+                return Ok(ExternalContactDetail {
+                    external_contact: resp.external_contact,
+                    follow_user,
+                });
+            }
+            cursor = resp.next_cursor;
+        }
+    }
+
+    /// 批量获取成员添加的客户详情, 每页最多 100 个, `cursor` 第一页传空字符串,
+    /// 可以配合 `page::paginate` 获取全部客户
+    pub async fn batch_get_by_user(
+        &self,
+        userid_list: &[&str],
+        cursor: &str,
+    ) -> Result<Page<ExternalContactByUser>> {
+        let req = BatchGetByUserRequest {
+            userid_list,
+            cursor,
+            limit: 100,
+        };
+        let resp: BatchGetByUserResponse = self
+            .post("/cgi-bin/externalcontact/batch/get_by_user", &req)
+            .await?;
+        Ok(Page::new(
+            resp.external_contact_list,
+            resp.next_cursor,
+            false,
+        ))
+    }
+
+    /// 修改成员 `userid` 对客户的备注信息
+    pub async fn remark_external_contact(
+        &self,
+        userid: &str,
+        external_userid: &str,
+        remark: &ExternalContactRemark,
+    ) -> Result<()> {
+        let req = RemarkRequest {
+            userid,
+            external_userid,
+            remark,
+        };
+        let _: IgnoredAny = self.post("/cgi-bin/externalcontact/remark", &req).await?;
+        Ok(())
+    }
+
+    /// 配置客户联系 联系我 方式
+    pub async fn add_contact_way(&self, way: &ContactWay) -> Result<AddContactWayResponse> {
+        self.post("/cgi-bin/externalcontact/add_contact_way", way)
+            .await
+    }
+
+    pub async fn get_contact_way(&self, config_id: &str) -> Result<ContactWay> {
+        let req = ConfigIdRequest { config_id };
+        let ret: GetContactWayResponse = self
+            .post("/cgi-bin/externalcontact/get_contact_way", &req)
+            .await?;
+        Ok(ret.contact_way)
+    }
+
+    /// 更新 联系我 方式, `way.config_id` 必须指定, `type` 和 `scene` 不能修改
+    pub async fn update_contact_way(&self, way: &ContactWay) -> Result<()> {
+        let _: IgnoredAny = self
+            .post("/cgi-bin/externalcontact/update_contact_way", way)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn del_contact_way(&self, config_id: &str) -> Result<()> {
+        let req = ConfigIdRequest { config_id };
+        let _: IgnoredAny = self
+            .post("/cgi-bin/externalcontact/del_contact_way", &req)
+            .await?;
+        Ok(())
+    }
+}
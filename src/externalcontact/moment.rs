@@ -0,0 +1,375 @@
+#[cfg(feature = "native")]
+use std::time::Duration;
+
+use serde::ser::{SerializeMap, Serializer};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "native")]
+use crate::client::Client;
+#[cfg(feature = "native")]
+use crate::page::Page;
+#[cfg(feature = "native")]
+use crate::poll::{wait, MomentJob};
+#[cfg(feature = "native")]
+use crate::Result;
+
+/// 客户朋友圈的附件, 图片最多 9 个, 视频和链接只能有一个
+#[derive(Debug, Clone)]
+pub enum MomentAttachment {
+    Image {
+        media_id: String,
+    },
+    Video {
+        media_id: String,
+    },
+    Link {
+        title: String,
+        url: String,
+        media_id: String,
+    }, // media_id 为链接的封面图片
+}
+
+impl Serialize for MomentAttachment {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(2))?;
+        match self {
+            MomentAttachment::Image { media_id } => {
+                map.serialize_entry("msgtype", "image")?;
+                map.serialize_entry("image", &MediaId { media_id })?;
+            }
+            MomentAttachment::Video { media_id } => {
+                map.serialize_entry("msgtype", "video")?;
+                map.serialize_entry("video", &MediaId { media_id })?;
+            }
+            MomentAttachment::Link {
+                title,
+                url,
+                media_id,
+            } => {
+                map.serialize_entry("msgtype", "link")?;
+                map.serialize_entry(
+                    "link",
+                    &MomentLinkAttachment {
+                        title,
+                        url,
+                        media_id,
+                    },
+                )?;
+            }
+        }
+        map.end()
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct MediaId<'a> {
+    media_id: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+struct MomentLinkAttachment<'a> {
+    title: &'a str,
+    url: &'a str,
+    media_id: &'a str,
+}
+
+/// 企业发表的客户朋友圈, 附件的 media_id 需要通过 `Client::upload_moment_attachment` 上传
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MomentTask {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<MomentText>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<MomentAttachment>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub visible_range: Option<MomentVisibleRange>, // 为 None 时所有成员都可以发表
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MomentText {
+    #[serde(default)]
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MomentVisibleRange {
+    pub sender_list: MomentSenders,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub external_contact_list: Option<MomentTags>, // 只对带有这些标签的客户可见
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MomentSenders {
+    #[serde(default)]
+    pub user_list: Vec<String>,
+    #[serde(default)]
+    pub department_list: Vec<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MomentTags {
+    #[serde(default)]
+    pub tag_list: Vec<String>,
+}
+
+/// 创建朋友圈任务的结果
+#[derive(Debug, Clone, Deserialize)]
+pub struct MomentTaskResult {
+    pub status: u8, // 1-开始创建, 2-正在创建, 3-创建完成
+    #[serde(default)]
+    pub result: Option<MomentTaskDetail>, // 创建完成后返回
+}
+
+impl MomentTaskResult {
+    pub fn is_finished(&self) -> bool {
+        self.status == 3
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MomentTaskDetail {
+    #[serde(default)]
+    pub errcode: u64,
+    #[serde(default)]
+    pub errmsg: String,
+    #[serde(default)]
+    pub moment_id: String,
+    #[serde(default)]
+    pub invalid_sender_list: MomentSenders,
+    #[serde(default)]
+    pub invalid_external_contact_list: MomentTags,
+}
+
+/// 客户朋友圈
+#[derive(Debug, Clone, Deserialize)]
+pub struct Moment {
+    pub moment_id: String,
+    #[serde(default)]
+    pub creator: String,
+    #[serde(default)]
+    pub create_time: u64,
+    #[serde(default)]
+    pub create_type: u8, // 0-企业发表, 1-个人发表
+    #[serde(default)]
+    pub visible_type: u8, // 0-部分可见, 1-公开
+    #[serde(default)]
+    pub text: MomentText,
+    #[serde(default)]
+    pub image: Vec<MomentMedia>,
+    #[serde(default)]
+    pub video: Option<MomentVideo>,
+    #[serde(default)]
+    pub link: Option<MomentLink>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MomentMedia {
+    pub media_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MomentVideo {
+    pub media_id: String,
+    #[serde(default)]
+    pub thumb_media_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MomentLink {
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub url: String,
+}
+
+/// 成员发表朋友圈的状态
+#[derive(Debug, Clone, Deserialize)]
+pub struct MomentSendStatus {
+    pub userid: String,
+    pub publish_status: u8, // 0-未发表, 1-已发表
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct AddMomentTaskResponse {
+    jobid: String,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct MomentListRequest<'a> {
+    start_time: u64,
+    end_time: u64,
+    filter_type: u8,
+    cursor: &'a str,
+    limit: u32,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct MomentListResponse {
+    #[serde(default)]
+    moment_list: Vec<Moment>,
+    #[serde(default)]
+    next_cursor: String,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct MomentTaskRequest<'a> {
+    moment_id: &'a str,
+    cursor: &'a str,
+    limit: u32,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct MomentTaskResponse {
+    #[serde(default)]
+    task_list: Vec<MomentSendStatus>,
+    #[serde(default)]
+    next_cursor: String,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct MomentSendResultRequest<'a> {
+    moment_id: &'a str,
+    userid: &'a str,
+    cursor: &'a str,
+    limit: u32,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct MomentSendResultResponse {
+    #[serde(default)]
+    customer_list: Vec<MomentCustomer>,
+    #[serde(default)]
+    next_cursor: String,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct MomentCustomer {
+    external_userid: String,
+}
+
+/// 客户朋友圈
+#[cfg(feature = "native")]
+impl Client {
+    /// 创建企业发表朋友圈的任务, 返回 jobid, 成员需要在客户端确认后才会发表
+    pub async fn add_moment_task(&self, task: &MomentTask) -> Result<String> {
+        let ret: AddMomentTaskResponse = self
+            .post("/cgi-bin/externalcontact/add_moment_task", task)
+            .await?;
+        Ok(ret.jobid)
+    }
+
+    /// 查询创建朋友圈任务的结果, 结果只保存 24 小时
+    pub async fn get_moment_task_result(&self, jobid: &str) -> Result<MomentTaskResult> {
+        self.get(
+            "/cgi-bin/externalcontact/get_moment_task_result",
+            &[("jobid", jobid)],
+        )
+        .await
+    }
+
+    /// 轮询创建朋友圈任务的结果直到任务完成, 超过 `timeout` 后返回 `Error::PollTimeout`
+    pub async fn wait_moment_task(
+        &self,
+        jobid: &str,
+        timeout: Option<Duration>,
+    ) -> Result<MomentTaskResult> {
+        wait(self, &MomentJob(jobid.to_string()), timeout).await
+    }
+
+    /// 获取一段时间内企业和成员发表的朋友圈, 时间跨度不超过一个月, 每页最多 100 条
+    pub async fn get_moment_list(
+        &self,
+        start_time: u64,
+        end_time: u64,
+        cursor: &str,
+    ) -> Result<Page<Moment>> {
+        let req = MomentListRequest {
+            start_time,
+            end_time,
+            filter_type: 2, // 企业和个人发表的都返回
+            cursor,
+            limit: 100,
+        };
+        let ret: MomentListResponse = self
+            .post("/cgi-bin/externalcontact/get_moment_list", &req)
+            .await?;
+        Ok(Page::new(ret.moment_list, ret.next_cursor, false))
+    }
+
+    /// 获取企业发表的朋友圈在各个成员处的发表状态, 每页最多 1000 条
+    pub async fn get_moment_sends(
+        &self,
+        moment_id: &str,
+        cursor: &str,
+    ) -> Result<Page<MomentSendStatus>> {
+        let req = MomentTaskRequest {
+            moment_id,
+            cursor,
+            limit: 1000,
+        };
+        let ret: MomentTaskResponse = self
+            .post("/cgi-bin/externalcontact/get_moment_task", &req)
+            .await?;
+        Ok(Page::new(ret.task_list, ret.next_cursor, false))
+    }
+
+    /// 获取成员 `userid` 发表的朋友圈实际可见的客户, 返回 external_userid, 每页最多 5000 条
+    pub async fn get_moment_send_result(
+        &self,
+        moment_id: &str,
+        userid: &str,
+        cursor: &str,
+    ) -> Result<Page<String>> {
+        let req = MomentSendResultRequest {
+            moment_id,
+            userid,
+            cursor,
+            limit: 5000,
+        };
+        let ret: MomentSendResultResponse = self
+            .post("/cgi-bin/externalcontact/get_moment_send_result", &req)
+            .await?;
+        let items = ret
+            .customer_list
+            .into_iter()
+            .map(|c| c.external_userid)
+            .collect();
+        Ok(Page::new(items, ret.next_cursor, false))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize_attachment() {
+        let a = MomentAttachment::Link {
+            title: "t".to_string(),
+            url: "https://example.com".to_string(),
+            media_id: "m".to_string(),
+        };
+        let v = serde_json::to_value(&a).unwrap();
+        assert_eq!(v["msgtype"], "link");
+        assert_eq!(v["link"]["media_id"], "m");
+
+        let a = MomentAttachment::Image {
+            media_id: "i".to_string(),
+        };
+        let v = serde_json::to_value(&a).unwrap();
+        assert_eq!(
+            v,
+            serde_json::json!({"msgtype": "image", "image": {"media_id": "i"}})
+        );
+    }
+}
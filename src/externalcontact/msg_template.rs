@@ -0,0 +1,391 @@
+use std::convert::TryFrom;
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "native")]
+use crate::client::Client;
+#[cfg(feature = "native")]
+use crate::page::Page;
+#[cfg(feature = "native")]
+use crate::Result;
+
+/// 群发消息的附件, 最多 9 个
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(into = "RawAttachment", try_from = "RawAttachment")]
+pub enum MsgAttachment {
+    Image(ImageAttachment),
+    Link(LinkAttachment),
+    Miniprogram(MiniprogramAttachment),
+    Video(MediaAttachment),
+    File(MediaAttachment),
+}
+
+/// `media_id` 和 `pic_url` 二选一, 同时指定时使用 `media_id`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImageAttachment {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub media_id: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pic_url: Option<String>, // 通过 `Client::upload_image` 上传得到的图片链接
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LinkAttachment {
+    pub title: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub picurl: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub desc: Option<String>,
+    pub url: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MiniprogramAttachment {
+    pub title: String,
+    pub pic_media_id: String, // 封面图片, 建议 520*416
+    pub appid: String,        // 需要关联到企业的小程序
+    pub page: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MediaAttachment {
+    pub media_id: String,
+}
+
+// the wire format: {"msgtype": "image", "image": {...}}
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct RawAttachment {
+    msgtype: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    image: Option<ImageAttachment>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    link: Option<LinkAttachment>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    miniprogram: Option<MiniprogramAttachment>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    video: Option<MediaAttachment>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file: Option<MediaAttachment>,
+}
+
+impl From<MsgAttachment> for RawAttachment {
+    fn from(a: MsgAttachment) -> Self {
+        let (msgtype, raw) = match a {
+            MsgAttachment::Image(d) => (
+                "image",
+                RawAttachment {
+                    image: Some(d),
+                    ..Default::default()
+                },
+            ),
+            MsgAttachment::Link(d) => (
+                "link",
+                RawAttachment {
+                    link: Some(d),
+                    ..Default::default()
+                },
+            ),
+            MsgAttachment::Miniprogram(d) => (
+                "miniprogram",
+                RawAttachment {
+                    miniprogram: Some(d),
+                    ..Default::default()
+                },
+            ),
+            MsgAttachment::Video(d) => (
+                "video",
+                RawAttachment {
+                    video: Some(d),
+                    ..Default::default()
+                },
+            ),
+            MsgAttachment::File(d) => (
+                "file",
+                RawAttachment {
+                    file: Some(d),
+                    ..Default::default()
+                },
+            ),
+        };
+        RawAttachment {
+            msgtype: msgtype.to_string(),
+            ..raw
+        }
+    }
+}
+
+impl TryFrom<RawAttachment> for MsgAttachment {
+    type Error = String;
+
+    fn try_from(raw: RawAttachment) -> std::result::Result<Self, String> {
+        let msgtype = raw.msgtype;
+        let ret = match &*msgtype {
+            "image" => raw.image.map(MsgAttachment::Image),
+            "link" => raw.link.map(MsgAttachment::Link),
+            "miniprogram" => raw.miniprogram.map(MsgAttachment::Miniprogram),
+            "video" => raw.video.map(MsgAttachment::Video),
+            "file" => raw.file.map(MsgAttachment::File),
+            _ => return Err(format!("unknown attachment type: {}", msgtype)),
+        };
+        ret.ok_or_else(|| format!("missing {} attachment", msgtype))
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MsgText {
+    #[serde(default)]
+    pub content: String,
+}
+
+/// 企业群发消息, 由 `sender` 在客户端确认后发送
+#[derive(Debug, Clone, Serialize)]
+pub struct MsgTemplate {
+    pub chat_type: &'static str, // single-发送给客户, group-发送给客户群
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub external_userid: Vec<String>, // 只在 chat_type 为 single 时有效, 最多 1 万个
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sender: Option<String>, // chat_type 为 group 时必须指定
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<MsgText>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<MsgAttachment>,
+}
+
+impl MsgTemplate {
+    /// 群发给客户
+    pub fn single(external_userid: Vec<String>) -> Self {
+        MsgTemplate {
+            chat_type: "single",
+            external_userid,
+            sender: None,
+            text: None,
+            attachments: vec![],
+        }
+    }
+
+    /// 群发到 `sender` 作为群主的客户群
+    pub fn group(sender: impl ToString) -> Self {
+        MsgTemplate {
+            chat_type: "group",
+            external_userid: vec![],
+            sender: Some(sender.to_string()),
+            text: None,
+            attachments: vec![],
+        }
+    }
+
+    pub fn sender(mut self, sender: impl ToString) -> Self {
+        self.sender = Some(sender.to_string());
+        self
+    }
+
+    pub fn text(mut self, content: impl ToString) -> Self {
+        self.text = Some(MsgText {
+            content: content.to_string(),
+        });
+        self
+    }
+
+    pub fn attachment(mut self, a: MsgAttachment) -> Self {
+        self.attachments.push(a);
+        self
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AddMsgTemplateResponse {
+    #[serde(default)]
+    pub fail_list: Vec<String>, // 无效或者无法发送的 external_userid
+    pub msgid: String,
+}
+
+/// 企业或者成员创建的群发
+#[derive(Debug, Clone, Deserialize)]
+pub struct GroupMsg {
+    pub msgid: String,
+    #[serde(default)]
+    pub creator: String,
+    #[serde(default)]
+    pub create_time: String,
+    #[serde(default)]
+    pub create_type: u8, // 0-企业发表, 1-个人发表
+    #[serde(default)]
+    pub text: MsgText,
+    #[serde(default)]
+    pub attachments: Vec<MsgAttachment>,
+}
+
+/// 群发在成员处的执行状态
+#[derive(Debug, Clone, Deserialize)]
+pub struct GroupMsgTask {
+    pub userid: String,
+    pub status: u8, // 0-未发送, 2-已发送
+    #[serde(default)]
+    pub send_time: u64,
+}
+
+/// 群发给每个客户或者客户群的结果
+#[derive(Debug, Clone, Deserialize)]
+pub struct GroupMsgSendResult {
+    #[serde(default)]
+    pub external_userid: String,
+    #[serde(default)]
+    pub chat_id: String, // 群发到客户群时返回
+    pub userid: String,
+    pub status: u8, // 0-未发送, 1-已发送, 2-因客户不是好友导致发送失败, 3-因客户已经收到其他群发消息导致发送失败
+    #[serde(default)]
+    pub send_time: u64,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct GroupMsgListRequest<'a> {
+    chat_type: &'a str,
+    start_time: u64,
+    end_time: u64,
+    filter_type: u8,
+    limit: u32,
+    cursor: &'a str,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct GroupMsgListResponse {
+    #[serde(default)]
+    group_msg_list: Vec<GroupMsg>,
+    #[serde(default)]
+    next_cursor: String,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct GroupMsgTaskRequest<'a> {
+    msgid: &'a str,
+    limit: u32,
+    cursor: &'a str,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct GroupMsgTaskResponse {
+    #[serde(default)]
+    task_list: Vec<GroupMsgTask>,
+    #[serde(default)]
+    next_cursor: String,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct GroupMsgSendResultRequest<'a> {
+    msgid: &'a str,
+    userid: &'a str,
+    limit: u32,
+    cursor: &'a str,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct GroupMsgSendResultResponse {
+    #[serde(default)]
+    send_list: Vec<GroupMsgSendResult>,
+    #[serde(default)]
+    next_cursor: String,
+}
+
+/// 客户群发
+#[cfg(feature = "native")]
+impl Client {
+    /// 创建企业群发
+    pub async fn add_msg_template(&self, msg: &MsgTemplate) -> Result<AddMsgTemplateResponse> {
+        self.post("/cgi-bin/externalcontact/add_msg_template", msg)
+            .await
+    }
+
+    /// 获取一段时间内的群发记录, `chat_type` 为 single 或者 group, 时间跨度不超过一个月
+    pub async fn get_groupmsg_list_v2(
+        &self,
+        chat_type: &str,
+        start_time: u64,
+        end_time: u64,
+        cursor: &str,
+    ) -> Result<Page<GroupMsg>> {
+        let req = GroupMsgListRequest {
+            chat_type,
+            start_time,
+            end_time,
+            filter_type: 2, // 企业和个人发表的都返回
+            limit: 100,
+            cursor,
+        };
+        let ret: GroupMsgListResponse = self
+            .post("/cgi-bin/externalcontact/get_groupmsg_list_v2", &req)
+            .await?;
+        Ok(Page::new(ret.group_msg_list, ret.next_cursor, false))
+    }
+
+    /// 获取群发在各个成员处的执行状态, 每页最多 1000 条
+    pub async fn get_groupmsg_task(&self, msgid: &str, cursor: &str) -> Result<Page<GroupMsgTask>> {
+        let req = GroupMsgTaskRequest {
+            msgid,
+            limit: 1000,
+            cursor,
+        };
+        let ret: GroupMsgTaskResponse = self
+            .post("/cgi-bin/externalcontact/get_groupmsg_task", &req)
+            .await?;
+        Ok(Page::new(ret.task_list, ret.next_cursor, false))
+    }
+
+    /// 获取成员 `userid` 执行群发的结果, 每页最多 1000 条
+    pub async fn get_groupmsg_send_result(
+        &self,
+        msgid: &str,
+        userid: &str,
+        cursor: &str,
+    ) -> Result<Page<GroupMsgSendResult>> {
+        let req = GroupMsgSendResultRequest {
+            msgid,
+            userid,
+            limit: 1000,
+            cursor,
+        };
+        let ret: GroupMsgSendResultResponse = self
+            .post("/cgi-bin/externalcontact/get_groupmsg_send_result", &req)
+            .await?;
+        Ok(Page::new(ret.send_list, ret.next_cursor, false))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attachment_round_trip() {
+        let a = MsgAttachment::Miniprogram(MiniprogramAttachment {
+            title: "t".to_string(),
+            pic_media_id: "m".to_string(),
+            appid: "wx1".to_string(),
+            page: "/index".to_string(),
+        });
+        let v = serde_json::to_value(&a).unwrap();
+        assert_eq!(v["msgtype"], "miniprogram");
+        assert_eq!(v["miniprogram"]["appid"], "wx1");
+        assert!(v.get("image").is_none());
+
+        let b: MsgAttachment = serde_json::from_value(v).unwrap();
+        assert!(matches!(b, MsgAttachment::Miniprogram(m) if m.page == "/index"));
+
+        let bad = serde_json::json!({"msgtype": "image"});
+        assert!(serde_json::from_value::<MsgAttachment>(bad).is_err());
+    }
+}
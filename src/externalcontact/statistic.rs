@@ -0,0 +1,196 @@
+use serde::Deserialize;
+#[cfg(feature = "native")]
+use serde::Serialize;
+
+#[cfg(feature = "native")]
+use crate::client::Client;
+#[cfg(feature = "native")]
+use crate::Result;
+
+/// 成员一天的客户联系数据
+#[derive(Debug, Clone, Deserialize)]
+pub struct BehaviorData {
+    pub stat_time: u64, // 当天 0 点的时间戳
+    #[serde(default)]
+    pub chat_cnt: u64, // 聊天总数
+    #[serde(default)]
+    pub message_cnt: u64, // 发送消息数
+    #[serde(default)]
+    pub reply_percentage: Option<f64>, // 已回复聊天占比, 单位为百分比, 没有新会话时不返回
+    #[serde(default)]
+    pub avg_reply_time: Option<u64>, // 平均首次回复时长, 单位分钟
+    #[serde(default)]
+    pub negative_feedback_cnt: u64, // 删除或者拉黑成员的客户数
+    #[serde(default)]
+    pub new_apply_cnt: u64, // 发起申请数
+    #[serde(default)]
+    pub new_contact_cnt: u64, // 新增客户数
+}
+
+/// 客户群的统计数据
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct GroupChatStat {
+    pub new_chat_cnt: u64,             // 新增客户群数量
+    pub chat_total: u64,               // 截至当天的客户群总数
+    pub chat_has_msg: u64,             // 有发过消息的客户群数量
+    pub new_member_cnt: u64,           // 新增的客户群成员数
+    pub member_total: u64,             // 截至当天的客户群成员总数
+    pub member_has_msg: u64,           // 发过消息的群成员数
+    pub msg_total: u64,                // 客户群消息总数
+    pub migrate_trainee_chat_cnt: u64, // 截至当天新增迁移群数
+}
+
+/// 按群主聚合的客户群统计
+#[derive(Debug, Clone, Deserialize)]
+pub struct GroupChatOwnerStat {
+    pub owner: String,
+    pub data: GroupChatStat,
+}
+
+/// 按天聚合的客户群统计
+#[derive(Debug, Clone, Deserialize)]
+pub struct GroupChatDayStat {
+    pub stat_time: u64,
+    pub data: GroupChatStat,
+}
+
+/// 分页的群主统计结果, 以 offset 翻页
+#[derive(Debug, Clone)]
+pub struct GroupChatOwnerStats {
+    pub total: u64,
+    pub next_offset: Option<u64>, // 最后一页为 None
+    pub items: Vec<GroupChatOwnerStat>,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct OwnerFilter<'a> {
+    userid_list: &'a [&'a str],
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct GroupChatStatRequest<'a> {
+    day_begin_time: u64,
+    day_end_time: u64,
+    owner_filter: OwnerFilter<'a>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    offset: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<u32>,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct GroupChatStatResponse {
+    #[serde(default)]
+    total: u64,
+    #[serde(default)]
+    next_offset: u64,
+    #[serde(default)]
+    items: Vec<GroupChatOwnerStat>,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct GroupChatDayStatResponse {
+    #[serde(default)]
+    items: Vec<GroupChatDayStat>,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct BehaviorDataRequest<'a> {
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    userid: &'a [&'a str],
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    partyid: &'a [u64],
+    start_time: u64,
+    end_time: u64,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct BehaviorDataResponse {
+    #[serde(default)]
+    behavior_data: Vec<BehaviorData>,
+}
+
+/// 客户联系统计
+#[cfg(feature = "native")]
+impl Client {
+    /// 获取成员或者部门成员的联系客户数据, 按天汇总, 时间跨度不超过 30 天
+    ///
+    /// `userid` 和 `partyid` 不能同时为空, 同时指定时返回两者的并集
+    pub async fn get_user_behavior_data(
+        &self,
+        userid: &[&str],
+        partyid: &[u64],
+        start_time: u64,
+        end_time: u64,
+    ) -> Result<Vec<BehaviorData>> {
+        let req = BehaviorDataRequest {
+            userid,
+            partyid,
+            start_time,
+            end_time,
+        };
+        let ret: BehaviorDataResponse = self
+            .post("/cgi-bin/externalcontact/get_user_behavior_data", &req)
+            .await?;
+        Ok(ret.behavior_data)
+    }
+
+    /// 按群主获取客户群统计数据, 按新增群数降序排列, `limit` 最大为 1000
+    ///
+    /// `day_begin_time` 和 `day_end_time` 为当天 0 点的时间戳, 跨度不超过 30 天
+    pub async fn groupchat_statistic(
+        &self,
+        day_begin_time: u64,
+        day_end_time: u64,
+        owner: &[&str],
+        offset: u64,
+        limit: u32,
+    ) -> Result<GroupChatOwnerStats> {
+        let req = GroupChatStatRequest {
+            day_begin_time,
+            day_end_time,
+            owner_filter: OwnerFilter { userid_list: owner },
+            offset: Some(offset),
+            limit: Some(limit),
+        };
+        let ret: GroupChatStatResponse = self
+            .post("/cgi-bin/externalcontact/groupchat/statistic", &req)
+            .await?;
+        let next_offset = Some(ret.next_offset).filter(|o| *o > offset && *o < ret.total);
+        Ok(GroupChatOwnerStats {
+            total: ret.total,
+            next_offset,
+            items: ret.items,
+        })
+    }
+
+    /// 按天获取群主 `owner` 的客户群统计数据
+    pub async fn groupchat_statistic_by_day(
+        &self,
+        day_begin_time: u64,
+        day_end_time: u64,
+        owner: &[&str],
+    ) -> Result<Vec<GroupChatDayStat>> {
+        let req = GroupChatStatRequest {
+            day_begin_time,
+            day_end_time,
+            owner_filter: OwnerFilter { userid_list: owner },
+            offset: None,
+            limit: None,
+        };
+        let ret: GroupChatDayStatResponse = self
+            .post(
+                "/cgi-bin/externalcontact/groupchat/statistic_group_by_day",
+                &req,
+            )
+            .await?;
+        Ok(ret.items)
+    }
+}
@@ -0,0 +1,159 @@
+#[cfg(feature = "native")]
+use serde::de::IgnoredAny;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "native")]
+use crate::client::Client;
+#[cfg(feature = "native")]
+use crate::Result;
+
+/// 企业客户标签组
+#[derive(Debug, Clone, Deserialize)]
+pub struct CorpTagGroup {
+    pub group_id: String,
+    pub group_name: String,
+    #[serde(default)]
+    pub create_time: u64,
+    #[serde(default)]
+    pub order: u32, // 值越大越靠前
+    #[serde(default)]
+    pub deleted: bool,
+    #[serde(default)]
+    pub tag: Vec<CorpTag>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CorpTag {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub create_time: u64,
+    #[serde(default)]
+    pub order: u32,
+    #[serde(default)]
+    pub deleted: bool,
+}
+
+/// 添加企业客户标签, 指定 `group_id` 时添加到已有的标签组, 否则以 `group_name` 创建新的标签组
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct NewCorpTags {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order: Option<u32>,
+    pub tag: Vec<NewCorpTag>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct NewCorpTag {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order: Option<u32>,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct TagIdsRequest<'a> {
+    tag_id: &'a [&'a str],
+    group_id: &'a [&'a str],
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct EditCorpTagRequest<'a> {
+    id: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    order: Option<u32>,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct MarkTagRequest<'a> {
+    userid: &'a str,
+    external_userid: &'a str,
+    add_tag: &'a [&'a str],
+    remove_tag: &'a [&'a str],
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct CorpTagListResponse {
+    #[serde(default)]
+    tag_group: Vec<CorpTagGroup>,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct AddCorpTagResponse {
+    tag_group: CorpTagGroup,
+}
+
+/// 企业客户标签管理
+#[cfg(feature = "native")]
+impl Client {
+    /// 获取企业标签库, `tag_id` 和 `group_id` 都为空时返回所有标签, 同时指定时忽略 `group_id`
+    pub async fn get_corp_tag_list(
+        &self,
+        tag_id: &[&str],
+        group_id: &[&str],
+    ) -> Result<Vec<CorpTagGroup>> {
+        let req = TagIdsRequest { tag_id, group_id };
+        let ret: CorpTagListResponse = self
+            .post("/cgi-bin/externalcontact/get_corp_tag_list", &req)
+            .await?;
+        Ok(ret.tag_group)
+    }
+
+    /// 添加企业客户标签, 返回标签所在的标签组
+    pub async fn add_corp_tag(&self, tags: &NewCorpTags) -> Result<CorpTagGroup> {
+        let ret: AddCorpTagResponse = self
+            .post("/cgi-bin/externalcontact/add_corp_tag", tags)
+            .await?;
+        Ok(ret.tag_group)
+    }
+
+    /// 修改标签或者标签组的名字和次序, `id` 可以是标签 id 或者标签组 id
+    pub async fn edit_corp_tag(
+        &self,
+        id: &str,
+        name: Option<&str>,
+        order: Option<u32>,
+    ) -> Result<()> {
+        let req = EditCorpTagRequest { id, name, order };
+        let _: IgnoredAny = self
+            .post("/cgi-bin/externalcontact/edit_corp_tag", &req)
+            .await?;
+        Ok(())
+    }
+
+    /// 删除标签或者标签组, 标签组内的标签全部删除后标签组也会被删除
+    pub async fn del_corp_tag(&self, tag_id: &[&str], group_id: &[&str]) -> Result<()> {
+        let req = TagIdsRequest { tag_id, group_id };
+        let _: IgnoredAny = self
+            .post("/cgi-bin/externalcontact/del_corp_tag", &req)
+            .await?;
+        Ok(())
+    }
+
+    /// 为成员 `userid` 添加的客户打上或者移除企业标签, 两个列表不能同时为空
+    pub async fn mark_external_contact_tag(
+        &self,
+        userid: &str,
+        external_userid: &str,
+        add_tag: &[&str],
+        remove_tag: &[&str],
+    ) -> Result<()> {
+        let req = MarkTagRequest {
+            userid,
+            external_userid,
+            add_tag,
+            remove_tag,
+        };
+        let _: IgnoredAny = self.post("/cgi-bin/externalcontact/mark_tag", &req).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,253 @@
+#[cfg(feature = "native")]
+use futures::TryStreamExt;
+use serde::Deserialize;
+#[cfg(feature = "native")]
+use serde::Serialize;
+
+#[cfg(feature = "native")]
+use crate::client::Client;
+#[cfg(feature = "native")]
+use crate::page::paginate;
+#[cfg(feature = "native")]
+use crate::page::Page;
+#[cfg(feature = "native")]
+use crate::Result;
+
+/// 离职成员的待分配客户
+#[derive(Debug, Clone, Deserialize)]
+pub struct UnassignedCustomer {
+    pub handover_userid: String,
+    pub external_userid: String,
+    #[serde(default)]
+    pub dimission_time: u64,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct UnassignedResponse {
+    #[serde(default)]
+    pub info: Vec<UnassignedCustomer>,
+    #[serde(default)]
+    pub is_last: bool,
+    #[serde(default)]
+    pub next_cursor: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CustomerTransferResult {
+    pub external_userid: String,
+    pub errcode: u64, // 0 表示成功
+}
+
+/// 客户的接替状态
+#[derive(Debug, Clone, Deserialize)]
+pub struct TransferStatus {
+    pub external_userid: String,
+    pub status: u8, // 1-接替完毕, 2-等待接替, 3-客户拒绝接替, 4-接替成员客户达到上限, 5-无接替记录
+    #[serde(default)]
+    pub takeover_time: u64,
+}
+
+/// 转交失败的客户群
+#[derive(Debug, Clone, Deserialize)]
+pub struct GroupChatTransferFailure {
+    pub chat_id: String,
+    pub errcode: u64,
+    #[serde(default)]
+    pub errmsg: String,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct UnassignedRequest<'a> {
+    cursor: &'a str,
+    page_size: u32,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct TransferCustomerRequest<'a> {
+    handover_userid: &'a str,
+    takeover_userid: &'a str,
+    external_userid: &'a [&'a str],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    transfer_success_msg: Option<&'a str>, // 只在在职继承时有效
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct TransferResultRequest<'a> {
+    handover_userid: &'a str,
+    takeover_userid: &'a str,
+    cursor: &'a str,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct GroupChatTransferRequest<'a> {
+    chat_id_list: &'a [&'a str],
+    new_owner: &'a str,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct GroupChatTransferResponse {
+    #[serde(default)]
+    failed_chat_list: Vec<GroupChatTransferFailure>,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct TransferResultResponse {
+    #[serde(default)]
+    customer: Vec<TransferStatus>,
+    #[serde(default)]
+    next_cursor: String,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct TransferCustomerResponse {
+    #[serde(default)]
+    customer: Vec<CustomerTransferResult>,
+}
+
+/// 客户继承
+#[cfg(feature = "native")]
+impl Client {
+    /// 分配在职成员的客户, 每次最多 100 个, 客户会收到 `transfer_success_msg`, 为 None 时使用默认文案
+    ///
+    /// 客户可以在 24 小时内拒绝, 结果通过 `transfer_result` 查询
+    pub async fn transfer_customer(
+        &self,
+        handover_userid: &str,
+        takeover_userid: &str,
+        external_userid: &[&str],
+        transfer_success_msg: Option<&str>,
+    ) -> Result<Vec<CustomerTransferResult>> {
+        let req = TransferCustomerRequest {
+            handover_userid,
+            takeover_userid,
+            external_userid,
+            transfer_success_msg,
+        };
+        let resp: TransferCustomerResponse = self
+            .post("/cgi-bin/externalcontact/transfer_customer", &req)
+            .await?;
+        Ok(resp.customer)
+    }
+
+    /// 查询在职成员客户的接替状态, `cursor` 第一页传空字符串
+    pub async fn transfer_result(
+        &self,
+        handover_userid: &str,
+        takeover_userid: &str,
+        cursor: &str,
+    ) -> Result<Page<TransferStatus>> {
+        let req = TransferResultRequest {
+            handover_userid,
+            takeover_userid,
+            cursor,
+        };
+        let resp: TransferResultResponse = self
+            .post("/cgi-bin/externalcontact/transfer_result", &req)
+            .await?;
+        Ok(Page::new(resp.customer, resp.next_cursor, false))
+    }
+
+    /// 获取从 `handover_userid` 分配给 `takeover_userid` 的所有客户及其接替状态
+    pub async fn get_transfer_customer_list(
+        &self,
+        handover_userid: &str,
+        takeover_userid: &str,
+    ) -> Result<Vec<TransferStatus>> {
+        paginate(|cursor| async move {
+            self.transfer_result(handover_userid, takeover_userid, &cursor)
+                .await
+        })
+        .try_collect()
+        .await
+    }
+
+    /// 获取待分配的离职成员的客户, `cursor` 第一页传空字符串, `page_size` 最大为 1000
+    pub async fn get_unassigned_list(
+        &self,
+        cursor: &str,
+        page_size: u32,
+    ) -> Result<Page<UnassignedCustomer>> {
+        let req = UnassignedRequest { cursor, page_size };
+        let resp: UnassignedResponse = self
+            .post("/cgi-bin/externalcontact/get_unassigned_list", &req)
+            .await?;
+        Ok(Page::new(resp.info, resp.next_cursor, resp.is_last))
+    }
+
+    /// 分配离职成员的客户, 每次最多 100 个, 返回每个客户的分配结果
+    pub async fn resigned_transfer_customer(
+        &self,
+        handover_userid: &str,
+        takeover_userid: &str,
+        external_userid: &[&str],
+    ) -> Result<Vec<CustomerTransferResult>> {
+        let req = TransferCustomerRequest {
+            handover_userid,
+            takeover_userid,
+            external_userid,
+            transfer_success_msg: None,
+        };
+        let resp: TransferCustomerResponse = self
+            .post("/cgi-bin/externalcontact/resigned/transfer_customer", &req)
+            .await?;
+        Ok(resp.customer)
+    }
+
+    /// 查询离职成员客户的接替状态, `cursor` 第一页传空字符串
+    pub async fn resigned_transfer_result(
+        &self,
+        handover_userid: &str,
+        takeover_userid: &str,
+        cursor: &str,
+    ) -> Result<Page<TransferStatus>> {
+        let req = TransferResultRequest {
+            handover_userid,
+            takeover_userid,
+            cursor,
+        };
+        let resp: TransferResultResponse = self
+            .post("/cgi-bin/externalcontact/resigned/transfer_result", &req)
+            .await?;
+        Ok(Page::new(resp.customer, resp.next_cursor, false))
+    }
+
+    /// 将离职成员的客户群转交给 `new_owner`, 每次最多 100 个, 返回转交失败的群
+    pub async fn transfer_group_chat(
+        &self,
+        chat_id_list: &[&str],
+        new_owner: &str,
+    ) -> Result<Vec<GroupChatTransferFailure>> {
+        let req = GroupChatTransferRequest {
+            chat_id_list,
+            new_owner,
+        };
+        let resp: GroupChatTransferResponse = self
+            .post("/cgi-bin/externalcontact/groupchat/transfer", &req)
+            .await?;
+        Ok(resp.failed_chat_list)
+    }
+
+    /// 将在职成员的客户群转交给 `new_owner`, 每次最多 100 个, 返回转交失败的群
+    pub async fn onjob_transfer_group_chat(
+        &self,
+        chat_id_list: &[&str],
+        new_owner: &str,
+    ) -> Result<Vec<GroupChatTransferFailure>> {
+        let req = GroupChatTransferRequest {
+            chat_id_list,
+            new_owner,
+        };
+        let resp: GroupChatTransferResponse = self
+            .post("/cgi-bin/externalcontact/groupchat/onjob_transfer", &req)
+            .await?;
+        Ok(resp.failed_chat_list)
+    }
+}
@@ -0,0 +1,197 @@
+use std::net::IpAddr;
+use std::time::Duration;
+
+use log::{info, warn};
+use serde::Deserialize;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+
+use crate::client::Client;
+use crate::{Error, Result};
+
+/// 企业微信 "不在可信 IP 列表" 的错误码
+const UNTRUSTED_IP_ERRCODE: u64 = 60020;
+
+#[derive(Debug, Deserialize)]
+struct IpListResponse {
+    ip_list: Vec<String>,
+}
+
+/// 网络相关
+impl Client {
+    /// 获取企业微信 API 域名 IP 段
+    pub async fn get_api_domain_ip(&self) -> Result<Vec<String>> {
+        let ret: IpListResponse = self.get("/cgi-bin/get_api_domain_ip", &()).await?;
+        Ok(ret.ip_list)
+    }
+
+    /// 获取企业微信回调服务器的 IP 段, 可以配合 `ip_in_ranges` 校验回调请求的来源
+    pub async fn get_callback_ip(&self) -> Result<Vec<String>> {
+        let ret: IpListResponse = self.get("/cgi-bin/getcallbackip", &()).await?;
+        Ok(ret.ip_list)
+    }
+}
+
+/// `ip` 是否在 `ranges` 中, `ranges` 的元素为单个 IP 或者 CIDR, 比如 `101.226.103.0/25`
+pub fn ip_in_ranges(ranges: &[String], ip: IpAddr) -> bool {
+    ranges.iter().any(|r| range_contains(r, ip))
+}
+
+#[derive(Debug, Clone)]
+pub enum IpTrustEvent {
+    /// 当前出口 IP 不在用户配置的可信 IP 列表中, 调用即将因 60020 失败
+    Untrusted(IpAddr),
+    /// 企业微信已经拒绝了当前出口 IP (60020), 携带企业微信返回的来源 IP
+    Rejected(Option<IpAddr>),
+    /// 检查本身失败, 比如网络错误
+    CheckFailed(String),
+}
+
+pub struct IpTrustChecker {
+    trusted_ips: Vec<String>, // single IPs or CIDR ranges
+    egress_ip_url: String,
+    interval: Duration, // optional, default is 10 minutes
+    http_client: reqwest::Client,
+}
+
+impl IpTrustChecker {
+    /// `trusted_ips` 的格式同 `ip_in_ranges`, 可以是单个 IP 或者 CIDR
+    ///
+    /// `egress_ip_url` 用于查询出口 IP, 该地址需要以纯文本返回调用方的 IP, 比如 `https://api.ipify.org`
+    pub fn new(trusted_ips: Vec<String>, egress_ip_url: impl ToString) -> Self {
+        IpTrustChecker {
+            trusted_ips,
+            egress_ip_url: egress_ip_url.to_string(),
+            interval: Duration::from_secs(600),
+            http_client: reqwest::Client::new(),
+        }
+    }
+
+    pub fn interval(mut self, d: Duration) -> Self {
+        self.interval = d;
+        self
+    }
+
+    /// 执行一次检查, 返回检查过程中发现的问题, 没有问题时返回空列表
+    pub async fn check(&self, client: &Client) -> Vec<IpTrustEvent> {
+        let mut events = vec![];
+
+        match self.egress_ip().await {
+            Ok(ip) if !ip_in_ranges(&self.trusted_ips, ip) => {
+                events.push(IpTrustEvent::Untrusted(ip))
+            }
+            Ok(_) => {}
+            Err(e) => events.push(IpTrustEvent::CheckFailed(format!("{}", e))),
+        }
+
+        match client.get_api_domain_ip().await {
+            Ok(_) => {}
+            Err(Error::RequestFailed(UNTRUSTED_IP_ERRCODE, msg)) => {
+                events.push(IpTrustEvent::Rejected(parse_rejected_ip(&msg)))
+            }
+            Err(e) => events.push(IpTrustEvent::CheckFailed(format!("{}", e))),
+        }
+
+        events
+    }
+
+    /// 在后台周期性地检查, 发现的问题通过返回的 channel 通知调用方
+    pub fn spawn(self, client: Client) -> UnboundedReceiver<IpTrustEvent> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        tokio::spawn(async move { self.run(client, tx).await });
+        rx
+    }
+
+    async fn run(self, client: Client, sender: UnboundedSender<IpTrustEvent>) {
+        let mut interval = tokio::time::interval(self.interval);
+        loop {
+            interval.tick().await;
+            // a check that finds nothing sends nothing, so look at the receiver explicitly
+            if sender.is_closed() {
+                info!("ip trust event receiver dropped, exit checker");
+                return;
+            }
+            for event in self.check(&client).await {
+                warn!("ip trust check: {:?}", event);
+                if sender.send(event).is_err() {
+                    info!("ip trust event receiver dropped, exit checker");
+                    return;
+                }
+            }
+        }
+    }
+
+    async fn egress_ip(&self) -> anyhow::Result<IpAddr> {
+        let text = self
+            .http_client
+            .get(&self.egress_ip_url)
+            .send()
+            .await?
+            .text()
+            .await?;
+        let ip = text.trim().parse()?;
+        Ok(ip)
+    }
+}
+
+///////////////////////////// helper functions ///////////////////////////////////////////////
+
+// errmsg looks like: "not allow to access from your ip, hint: [...], from ip: 1.2.3.4, more info at ..."
+fn parse_rejected_ip(errmsg: &str) -> Option<IpAddr> {
+    let (_, rest) = errmsg.split_once("from ip:")?;
+    rest.split(',').next()?.trim().parse().ok()
+}
+
+fn range_contains(range: &str, ip: IpAddr) -> bool {
+    let (addr, prefix) = match range.split_once('/') {
+        Some((addr, prefix)) => (addr, prefix.trim().parse::<u32>().ok()),
+        None => (range, None),
+    };
+    let addr: IpAddr = match addr.trim().parse() {
+        Ok(addr) => addr,
+        Err(_) => return false,
+    };
+    match (addr, ip) {
+        (IpAddr::V4(a), IpAddr::V4(b)) => {
+            prefix_eq(u32::from(a) as u128, u32::from(b) as u128, 32, prefix)
+        }
+        (IpAddr::V6(a), IpAddr::V6(b)) => prefix_eq(u128::from(a), u128::from(b), 128, prefix),
+        _ => false,
+    }
+}
+
+// whether the first `prefix` of `bits` bits are equal, no prefix means the whole address
+fn prefix_eq(a: u128, b: u128, bits: u32, prefix: Option<u32>) -> bool {
+    let prefix = match prefix {
+        Some(p) if p <= bits => p,
+        Some(_) => return false,
+        None => bits,
+    };
+    if prefix == 0 {
+        return true;
+    }
+    let shift = bits - prefix;
+    (a >> shift) == (b >> shift)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ip_in_ranges() {
+        let ranges = vec!["101.226.103.0/25".to_string(), "183.3.234.5".to_string()];
+        assert!(ip_in_ranges(&ranges, "101.226.103.100".parse().unwrap()));
+        assert!(!ip_in_ranges(&ranges, "101.226.103.200".parse().unwrap()));
+        assert!(ip_in_ranges(&ranges, "183.3.234.5".parse().unwrap()));
+        assert!(!ip_in_ranges(&ranges, "183.3.234.6".parse().unwrap()));
+        assert!(!ip_in_ranges(&ranges, "::1".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_parse_rejected_ip() {
+        let msg = "not allow to access from your ip, hint: [1563171371_21_2b8b2e331ab99bc11ee25f1ee8a6c5f8], from ip: 113.108.1.2, more info at https://open.work.weixin.qq.com/devtool/query?e=60020";
+        assert_eq!(parse_rejected_ip(msg), Some("113.108.1.2".parse().unwrap()));
+
+        assert_eq!(parse_rejected_ip("invalid credential"), None);
+    }
+}
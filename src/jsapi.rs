@@ -0,0 +1,175 @@
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use log::warn;
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use tokio::sync::Mutex;
+
+use crate::client::{refresh_delay, Client};
+use crate::Result;
+
+/// 缓存的 jsapi_ticket, 与 access token 一样在过期前刷新
+#[derive(Debug, Default)]
+pub(crate) struct JsapiTickets {
+    corp: Mutex<Option<Ticket>>,
+    agent: Mutex<Option<Ticket>>,
+}
+
+#[derive(Debug)]
+struct Ticket {
+    value: String,
+    refresh_at: Instant,
+    expires_at: Instant,
+}
+
+#[derive(Debug, Deserialize)]
+struct TicketResponse {
+    ticket: String,
+    expires_in: u64,
+}
+
+/// wx.config 的参数, 序列化后可以直接传给 JS-SDK, 需要另外指定 jsApiList
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsapiConfig {
+    pub app_id: String, // 企业 id
+    pub timestamp: u64,
+    pub nonce_str: String,
+    pub signature: String,
+}
+
+/// wx.agentConfig 的参数
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentConfig {
+    pub corpid: String,
+    pub agentid: u64,
+    pub timestamp: u64,
+    pub nonce_str: String,
+    pub signature: String,
+}
+
+/// 计算 JS-SDK 的签名, `url` 为调用 JS 接口页面的完整 url, 不包含 # 及其后面的部分
+pub fn jsapi_signature(ticket: &str, nonce_str: &str, timestamp: u64, url: &str) -> String {
+    let url = url.split('#').next().unwrap_or_default();
+    let s = format!(
+        "jsapi_ticket={}&noncestr={}&timestamp={}&url={}",
+        ticket, nonce_str, timestamp, url
+    );
+    let mut hasher = Sha1::new();
+    hasher.input(s.as_bytes());
+    hex::encode(hasher.result())
+}
+
+fn nonce_and_timestamp() -> (String, u64) {
+    let nonce: String = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(16)
+        .collect();
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    (nonce, timestamp)
+}
+
+/// JS-SDK
+impl Client {
+    /// 生成页面 `url` 的 wx.config 参数
+    pub async fn jsapi_config(&self, url: &str) -> Result<JsapiConfig> {
+        let ticket = self.get_jsapi_ticket().await?;
+        let (nonce_str, timestamp) = nonce_and_timestamp();
+        Ok(JsapiConfig {
+            app_id: self.corp_id().to_string(),
+            timestamp,
+            signature: jsapi_signature(&ticket, &nonce_str, timestamp, url),
+            nonce_str,
+        })
+    }
+
+    /// 生成页面 `url` 的 wx.agentConfig 参数, `agent_id` 需要与获取 access token 的应用一致
+    pub async fn agent_config(&self, agent_id: u64, url: &str) -> Result<AgentConfig> {
+        let ticket = self.get_agent_jsapi_ticket().await?;
+        let (nonce_str, timestamp) = nonce_and_timestamp();
+        Ok(AgentConfig {
+            corpid: self.corp_id().to_string(),
+            agentid: agent_id,
+            timestamp,
+            signature: jsapi_signature(&ticket, &nonce_str, timestamp, url),
+            nonce_str,
+        })
+    }
+
+    /// 企业的 jsapi_ticket, 用于计算 wx.config 的签名
+    pub async fn get_jsapi_ticket(&self) -> Result<String> {
+        let cache = &self.jsapi_tickets.corp;
+        self.cached_ticket(cache, "/cgi-bin/get_jsapi_ticket", None)
+            .await
+    }
+
+    /// 应用的 jsapi_ticket, 用于计算 wx.agentConfig 的签名
+    pub async fn get_agent_jsapi_ticket(&self) -> Result<String> {
+        let cache = &self.jsapi_tickets.agent;
+        self.cached_ticket(cache, "/cgi-bin/ticket/get", Some("agent_config"))
+            .await
+    }
+
+    // the lock is held while fetching so that concurrent callers share one request
+    async fn cached_ticket(
+        &self,
+        cache: &Mutex<Option<Ticket>>,
+        path: &str,
+        ty: Option<&str>,
+    ) -> Result<String> {
+        let mut cache = cache.lock().await;
+        let now = Instant::now();
+        if let Some(t) = &*cache {
+            if now < t.refresh_at {
+                return Ok(t.value.clone());
+            }
+        }
+
+        let resp: Result<TicketResponse> = match ty {
+            Some(ty) => self.get(path, &[("type", ty)]).await,
+            None => self.get(path, &()).await,
+        };
+        match resp {
+            Ok(resp) => {
+                let value = resp.ticket.clone();
+                *cache = Some(Ticket {
+                    value: resp.ticket,
+                    refresh_at: now + refresh_delay(resp.expires_in),
+                    expires_at: now + Duration::from_secs(resp.expires_in),
+                });
+                Ok(value)
+            }
+            // keep serving the old ticket until it really expires
+            Err(e) => match &*cache {
+                Some(t) if now < t.expires_at => {
+                    warn!("refresh jsapi ticket failed, use the cached one: {}", e);
+                    Ok(t.value.clone())
+                }
+                _ => Err(e),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jsapi_signature() {
+        let ticket = "sM4AOVdWfPE4DxkXGEs8VMCPGGVi4C3VM0P37wVUCFvkVAy_90u5h9nbSlYy3-Sl-HhTdfl2fzFy1AOcHKP7qg";
+        let sign = jsapi_signature(
+            ticket,
+            "Wm3WZYTPz0wzccnW",
+            1414587457,
+            "http://mp.weixin.qq.com?params=value#top",
+        );
+        assert_eq!(sign, "0f9de62fce790f9a083d5c99e95740ceb90c27ed");
+    }
+}
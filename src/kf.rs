@@ -0,0 +1,602 @@
+use std::convert::TryFrom;
+
+#[cfg(feature = "native")]
+use futures::stream::{self, Stream};
+use serde::ser::{SerializeMap, Serializer};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "native")]
+use crate::client::Client;
+#[cfg(feature = "native")]
+use crate::Result;
+
+/// 微信客服收到或者发出的消息
+#[derive(Debug, Clone, Deserialize)]
+pub struct KfMessage {
+    pub msgid: String,
+    #[serde(default)]
+    pub open_kfid: String,
+    #[serde(default)]
+    pub external_userid: String,
+    #[serde(default)]
+    pub send_time: u64,
+    pub origin: u8, // 3-微信客户发送, 4-系统推送的事件, 5-接待人员在企业微信客户端发送
+    #[serde(default)]
+    pub servicer_userid: String, // origin 为 5 时返回
+    #[serde(flatten)]
+    pub content: KfMessageContent,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "RawContent")]
+pub enum KfMessageContent {
+    Text(KfText),
+    Image(KfMedia),
+    Voice(KfMedia),
+    Video(KfMedia),
+    File(KfMedia),
+    Location(KfLocation),
+    Event(Box<KfEvent>),
+    Other(String), // 暂不支持的消息类型, 值为 msgtype
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KfText {
+    pub content: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub menu_id: String, // 客户点击菜单消息时返回
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KfMedia {
+    pub media_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KfLocation {
+    pub latitude: f64,
+    pub longitude: f64,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub address: String,
+}
+
+/// 客服事件, 不同 `event_type` 返回的字段不同, 未返回的字段为空
+#[derive(Debug, Clone, Deserialize)]
+pub struct KfEvent {
+    pub event_type: String, // 比如 enter_session, msg_send_fail, servicer_status_change
+    #[serde(default)]
+    pub open_kfid: String,
+    #[serde(default)]
+    pub external_userid: String,
+    #[serde(default)]
+    pub scene: String, // enter_session 时进入会话的场景值
+    #[serde(default)]
+    pub scene_param: String,
+    #[serde(default)]
+    pub welcome_code: String, // 用于发送欢迎语, 20 秒内有效
+    #[serde(default)]
+    pub fail_msgid: String,
+    #[serde(default)]
+    pub fail_type: u32,
+    #[serde(default)]
+    pub servicer_userid: String,
+    #[serde(default)]
+    pub status: u8,
+    #[serde(default)]
+    pub change_type: u8,
+    #[serde(default)]
+    pub old_servicer_userid: String,
+    #[serde(default)]
+    pub new_servicer_userid: String,
+    #[serde(default)]
+    pub msg_code: String, // 用于发送会话结束语等事件响应消息
+}
+
+// the wire format: {"msgtype": "text", "text": {...}}
+#[derive(Debug, Deserialize)]
+struct RawContent {
+    msgtype: String,
+    text: Option<KfText>,
+    image: Option<KfMedia>,
+    voice: Option<KfMedia>,
+    video: Option<KfMedia>,
+    file: Option<KfMedia>,
+    location: Option<KfLocation>,
+    event: Option<KfEvent>,
+}
+
+impl TryFrom<RawContent> for KfMessageContent {
+    type Error = String;
+
+    fn try_from(raw: RawContent) -> std::result::Result<Self, String> {
+        let msgtype = raw.msgtype;
+        let ret = match &*msgtype {
+            "text" => raw.text.map(KfMessageContent::Text),
+            "image" => raw.image.map(KfMessageContent::Image),
+            "voice" => raw.voice.map(KfMessageContent::Voice),
+            "video" => raw.video.map(KfMessageContent::Video),
+            "file" => raw.file.map(KfMessageContent::File),
+            "location" => raw.location.map(KfMessageContent::Location),
+            "event" => raw.event.map(|e| KfMessageContent::Event(Box::new(e))),
+            _ => return Ok(KfMessageContent::Other(msgtype)),
+        };
+        ret.ok_or_else(|| format!("missing {} content", msgtype))
+    }
+}
+
+/// 通过微信客服发送的消息
+#[derive(Debug, Clone)]
+pub enum KfSendContent {
+    Text(KfText),
+    Image(KfMedia),
+    Voice(KfMedia),
+    Video(KfMedia),
+    File(KfMedia),
+    Link(KfLink),
+    Miniprogram(KfMiniprogram),
+    MsgMenu(KfMsgMenu),
+    Location(KfLocation),
+}
+
+impl KfSendContent {
+    pub fn text(content: impl ToString) -> Self {
+        KfSendContent::Text(KfText {
+            content: content.to_string(),
+            menu_id: String::new(),
+        })
+    }
+
+    // write the `msgtype` and the payload entries
+    #[cfg(feature = "native")]
+    fn serialize_entries<M: SerializeMap>(&self, map: &mut M) -> std::result::Result<(), M::Error> {
+        use KfSendContent::*;
+
+        match self {
+            Text(d) => entry(map, "text", d),
+            Image(d) => entry(map, "image", d),
+            Voice(d) => entry(map, "voice", d),
+            Video(d) => entry(map, "video", d),
+            File(d) => entry(map, "file", d),
+            Link(d) => entry(map, "link", d),
+            Miniprogram(d) => entry(map, "miniprogram", d),
+            MsgMenu(d) => entry(map, "msgmenu", d),
+            Location(d) => entry(map, "location", d),
+        }
+    }
+}
+
+#[cfg(feature = "native")]
+fn entry<M: SerializeMap>(
+    map: &mut M,
+    msgtype: &str,
+    value: &impl Serialize,
+) -> std::result::Result<(), M::Error> {
+    map.serialize_entry("msgtype", msgtype)?;
+    map.serialize_entry(msgtype, value)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct KfLink {
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub desc: Option<String>,
+    pub url: String,
+    pub thumb_media_id: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct KfMiniprogram {
+    pub appid: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    pub thumb_media_id: String,
+    pub pagepath: String,
+}
+
+/// 菜单消息, 客户点击后会收到对应的文本消息或者跳转
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct KfMsgMenu {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub head_content: Option<String>,
+    pub list: Vec<KfMenuItem>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tail_content: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub enum KfMenuItem {
+    Click {
+        id: String,
+        content: String,
+    }, // 点击后客户发送的文本消息中带有 menu_id
+    View {
+        url: String,
+        content: String,
+    },
+    Miniprogram {
+        appid: String,
+        pagepath: String,
+        content: String,
+    },
+    Text {
+        content: String,
+        no_newline: bool,
+    },
+}
+
+impl Serialize for KfMenuItem {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(2))?;
+        match self {
+            KfMenuItem::Click { id, content } => {
+                map.serialize_entry("type", "click")?;
+                map.serialize_entry("click", &MenuClick { id, content })?;
+            }
+            KfMenuItem::View { url, content } => {
+                map.serialize_entry("type", "view")?;
+                map.serialize_entry("view", &MenuView { url, content })?;
+            }
+            KfMenuItem::Miniprogram {
+                appid,
+                pagepath,
+                content,
+            } => {
+                map.serialize_entry("type", "miniprogram")?;
+                let d = MenuMiniprogram {
+                    appid,
+                    pagepath,
+                    content,
+                };
+                map.serialize_entry("miniprogram", &d)?;
+            }
+            KfMenuItem::Text {
+                content,
+                no_newline,
+            } => {
+                map.serialize_entry("type", "text")?;
+                let d = MenuText {
+                    content,
+                    no_newline: *no_newline as u8,
+                };
+                map.serialize_entry("text", &d)?;
+            }
+        }
+        map.end()
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct MenuClick<'a> {
+    id: &'a str,
+    content: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+struct MenuView<'a> {
+    url: &'a str,
+    content: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+struct MenuMiniprogram<'a> {
+    appid: &'a str,
+    pagepath: &'a str,
+    content: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+struct MenuText<'a> {
+    content: &'a str,
+    no_newline: u8,
+}
+
+#[cfg(feature = "native")]
+struct SendMsgRequest<'a> {
+    touser: &'a str,
+    open_kfid: &'a str,
+    content: &'a KfSendContent,
+}
+
+#[cfg(feature = "native")]
+impl Serialize for SendMsgRequest<'_> {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("touser", self.touser)?;
+        map.serialize_entry("open_kfid", self.open_kfid)?;
+        self.content.serialize_entries(&mut map)?;
+        map.end()
+    }
+}
+
+#[cfg(feature = "native")]
+struct SendMsgOnEventRequest<'a> {
+    code: &'a str,
+    content: &'a KfSendContent,
+}
+
+#[cfg(feature = "native")]
+impl Serialize for SendMsgOnEventRequest<'_> {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("code", self.code)?;
+        self.content.serialize_entries(&mut map)?;
+        map.end()
+    }
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct SendMsgResponse {
+    #[serde(default)]
+    msgid: String,
+}
+
+/// 客服会话状态
+#[derive(Debug, Clone, Deserialize)]
+pub struct KfServiceState {
+    pub service_state: u8, // 0-未处理, 1-由智能助手接待, 2-待接入池排队中, 3-由人工接待, 4-已结束或未开始
+    #[serde(default)]
+    pub servicer_userid: String, // 人工接待时返回
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct ServiceStateRequest<'a> {
+    open_kfid: &'a str,
+    external_userid: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    service_state: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    servicer_userid: Option<&'a str>,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct TransStateResponse {
+    #[serde(default)]
+    msg_code: String,
+}
+
+/// 一次 `kf_sync_msg` 的结果, 处理完消息后需要保存 `next_cursor`, 下次从该位置继续拉取
+#[derive(Debug, Clone, Deserialize)]
+pub struct KfSyncResult {
+    #[serde(default)]
+    pub next_cursor: String,
+    #[serde(default)]
+    pub has_more: u8,
+    #[serde(default)]
+    pub msg_list: Vec<KfMessage>,
+}
+
+impl KfSyncResult {
+    pub fn has_more(&self) -> bool {
+        self.has_more == 1
+    }
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct SyncMsgRequest<'a> {
+    #[serde(skip_serializing_if = "str::is_empty")]
+    cursor: &'a str,
+    #[serde(skip_serializing_if = "str::is_empty")]
+    token: &'a str,
+    #[serde(skip_serializing_if = "str::is_empty")]
+    open_kfid: &'a str,
+    limit: u32,
+}
+
+/// 微信客服
+#[cfg(feature = "native")]
+impl Client {
+    /// 拉取客服消息, 每次最多 1000 条
+    ///
+    /// `token` 为 `kf_msg_or_event` 回调事件中的 Token, 10 分钟内有效, 不指定时调用频率会受到严格限制.
+    /// `cursor` 第一次拉取传空字符串, 之后传上次返回的 `next_cursor`.
+    /// `open_kfid` 为空时拉取所有客服帐号的消息.
+    pub async fn kf_sync_msg(
+        &self,
+        token: &str,
+        cursor: &str,
+        open_kfid: &str,
+    ) -> Result<KfSyncResult> {
+        let req = SyncMsgRequest {
+            cursor,
+            token,
+            open_kfid,
+            limit: 1000,
+        };
+        self.post("/cgi-bin/kf/sync_msg", &req).await
+    }
+
+    /// 发送客服消息给客户 `touser`, 返回 msgid
+    ///
+    /// 客户主动发送消息后的 48 小时内, 最多可以发送 5 条消息
+    pub async fn kf_send_msg(
+        &self,
+        touser: &str,
+        open_kfid: &str,
+        content: &KfSendContent,
+    ) -> Result<String> {
+        let req = SendMsgRequest {
+            touser,
+            open_kfid,
+            content,
+        };
+        let ret: SendMsgResponse = self.post("/cgi-bin/kf/send_msg", &req).await?;
+        Ok(ret.msgid)
+    }
+
+    /// 发送欢迎语等事件响应消息, `code` 为事件中的 welcome_code 或者 msg_code, 只支持文本和菜单消息
+    pub async fn send_msg_on_event(&self, code: &str, content: &KfSendContent) -> Result<String> {
+        let req = SendMsgOnEventRequest { code, content };
+        let ret: SendMsgResponse = self.post("/cgi-bin/kf/send_msg_on_event", &req).await?;
+        Ok(ret.msgid)
+    }
+
+    /// 获取客户 `external_userid` 在客服帐号 `open_kfid` 下的会话状态
+    pub async fn kf_get_service_state(
+        &self,
+        open_kfid: &str,
+        external_userid: &str,
+    ) -> Result<KfServiceState> {
+        let req = ServiceStateRequest {
+            open_kfid,
+            external_userid,
+            service_state: None,
+            servicer_userid: None,
+        };
+        self.post("/cgi-bin/kf/service_state/get", &req).await
+    }
+
+    /// 变更会话状态, 比如从智能助手转给人工接待, 转为人工接待时需要指定 `servicer_userid`
+    ///
+    /// 返回的 msg_code 可以用于 `send_msg_on_event` 发送提示消息, 为空表示不能发送
+    pub async fn kf_trans_service_state(
+        &self,
+        open_kfid: &str,
+        external_userid: &str,
+        service_state: u8,
+        servicer_userid: Option<&str>,
+    ) -> Result<String> {
+        let req = ServiceStateRequest {
+            open_kfid,
+            external_userid,
+            service_state: Some(service_state),
+            servicer_userid,
+        };
+        let ret: TransStateResponse = self.post("/cgi-bin/kf/service_state/trans", &req).await?;
+        Ok(ret.msg_code)
+    }
+
+    /// 从 `cursor` 开始持续拉取客服消息, 直到 `has_more` 为 false, 出错后结束
+    ///
+    /// 每一项为一次拉取的结果, 可以在处理完每一批消息后保存其中的 `next_cursor`
+    pub fn kf_sync_stream<'a>(
+        &'a self,
+        token: &'a str,
+        open_kfid: &'a str,
+        cursor: String,
+    ) -> impl Stream<Item = Result<KfSyncResult>> + 'a {
+        stream::unfold(Some(cursor), move |cursor| async move {
+            let cursor = cursor?;
+            match self.kf_sync_msg(token, &cursor, open_kfid).await {
+                Ok(ret) => {
+                    let next = Some(ret.next_cursor.clone()).filter(|_| ret.has_more());
+                    Some((Ok(ret), next))
+                }
+                Err(e) => Some((Err(e), None)),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "native")]
+    #[test]
+    fn test_serialize_send_msg() {
+        let menu = KfSendContent::MsgMenu(KfMsgMenu {
+            head_content: Some("您对本次服务是否满意呢?".to_string()),
+            list: vec![
+                KfMenuItem::Click {
+                    id: "101".to_string(),
+                    content: "满意".to_string(),
+                },
+                KfMenuItem::Text {
+                    content: "欢迎再次光临".to_string(),
+                    no_newline: true,
+                },
+            ],
+            tail_content: None,
+        });
+        let req = SendMsgRequest {
+            touser: "wm1",
+            open_kfid: "wk1",
+            content: &menu,
+        };
+        let v = serde_json::to_value(&req).unwrap();
+        assert_eq!(v["msgtype"], "msgmenu");
+        assert_eq!(v["touser"], "wm1");
+        assert_eq!(v["msgmenu"]["list"][0]["type"], "click");
+        assert_eq!(v["msgmenu"]["list"][0]["click"]["id"], "101");
+        assert_eq!(v["msgmenu"]["list"][1]["text"]["no_newline"], 1);
+        assert!(v["msgmenu"].get("tail_content").is_none());
+
+        let req = SendMsgOnEventRequest {
+            code: "c",
+            content: &KfSendContent::text("hi"),
+        };
+        let v = serde_json::to_value(&req).unwrap();
+        assert_eq!(
+            v,
+            serde_json::json!({"code": "c", "msgtype": "text", "text": {"content": "hi"}})
+        );
+    }
+
+    #[test]
+    fn test_deserialize_sync_result() {
+        let data = r#"{
+            "errcode": 0,
+            "errmsg": "ok",
+            "next_cursor": "4gw7MepFLfgF2VC5npN",
+            "has_more": 1,
+            "msg_list": [
+                {
+                    "msgid": "from_msgid_4622416642169452483",
+                    "open_kfid": "wkAJ2GCAAASSm4_FhToWMFea0xAFfd3Q",
+                    "external_userid": "wmAJ2GCAAAme1XQRC-NI-q0_ZM9ukoAw",
+                    "send_time": 1615478585,
+                    "origin": 3,
+                    "msgtype": "text",
+                    "text": {"content": "你好"}
+                },
+                {
+                    "msgid": "2",
+                    "send_time": 1615478585,
+                    "origin": 4,
+                    "msgtype": "event",
+                    "event": {
+                        "event_type": "enter_session",
+                        "open_kfid": "wkAJ2GCAAASSm4_FhToWMFea0xAFfd3Q",
+                        "external_userid": "wmAJ2GCAAAme1XQRC-NI-q0_ZM9ukoAw",
+                        "scene": "123",
+                        "welcome_code": "aaaaaa"
+                    }
+                },
+                {
+                    "msgid": "3",
+                    "origin": 3,
+                    "msgtype": "channels_shop_product",
+                    "channels_shop_product": {}
+                }
+            ]
+        }"#;
+        let ret: KfSyncResult = serde_json::from_str(data).unwrap();
+        assert!(ret.has_more());
+        assert_eq!(ret.msg_list.len(), 3);
+        assert!(
+            matches!(&ret.msg_list[0].content, KfMessageContent::Text(t) if t.content == "你好")
+        );
+        assert!(
+            matches!(&ret.msg_list[1].content, KfMessageContent::Event(e) if e.welcome_code == "aaaaaa")
+        );
+        assert!(
+            matches!(&ret.msg_list[2].content, KfMessageContent::Other(t) if t == "channels_shop_product")
+        );
+    }
+}
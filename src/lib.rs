@@ -1,7 +1,79 @@
+#[cfg(feature = "types")]
+pub mod agent;
+#[cfg(feature = "native")]
+pub mod auth;
+#[cfg(feature = "types")]
+pub mod batch;
+#[cfg(feature = "native")]
+pub mod broadcast;
+#[cfg(feature = "types")]
+pub mod checkin;
+#[cfg(feature = "native")]
 pub mod client;
+#[cfg(feature = "types")]
+pub mod corpgroup;
+#[cfg(feature = "types")]
+pub mod correlation;
+#[cfg(feature = "types")]
+pub mod department;
+#[cfg(feature = "types")]
+pub mod dial;
 mod error;
+#[cfg(feature = "types")]
+pub mod exmail;
+#[cfg(feature = "types")]
+pub mod export;
+#[cfg(feature = "types")]
+pub mod externalcontact;
+#[cfg(feature = "native")]
+pub mod ip;
+#[cfg(feature = "native")]
+pub mod jsapi;
+#[cfg(feature = "types")]
+pub mod kf;
+#[cfg(feature = "types")]
+pub mod linkedcorp;
+#[cfg(feature = "types")]
+pub mod living;
+#[cfg(feature = "types")]
 pub mod media;
+#[cfg(feature = "types")]
+pub mod meeting;
+#[cfg(feature = "types")]
+pub mod meetingroom;
+#[cfg(feature = "types")]
 pub mod message;
+#[cfg(feature = "types")]
+pub mod msgaudit;
+#[cfg(feature = "types")]
+pub mod oa;
+#[cfg(feature = "types")]
+pub mod page;
+#[cfg(feature = "native")]
+pub mod poll;
+#[cfg(feature = "native")]
+pub mod provider;
+#[cfg(feature = "native")]
+pub mod scan;
+#[cfg(feature = "types")]
+pub mod schedule;
+#[cfg(feature = "types")]
+pub mod security;
+#[cfg(feature = "types")]
 pub mod server;
+#[cfg(feature = "types")]
+pub mod tag;
+#[cfg(feature = "types")]
+pub mod template_card;
+#[cfg(feature = "native")]
+pub mod throttle;
+#[cfg(feature = "types")]
+pub mod user;
+#[cfg(feature = "webhook")]
+pub mod webhook;
+#[cfg(feature = "types")]
+pub mod wedrive;
+#[cfg(feature = "native")]
+pub mod workflows;
 
 pub use error::*;
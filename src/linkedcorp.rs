@@ -0,0 +1,128 @@
+use serde::Deserialize;
+#[cfg(feature = "native")]
+use serde::Serialize;
+
+#[cfg(feature = "native")]
+use crate::client::Client;
+#[cfg(feature = "native")]
+use crate::Result;
+
+/// 应用的可见范围, 成员 id 形如 `CORPID/USERID`, 部门 id 形如 `LINKEDID/DEPARTMENTID`
+#[derive(Debug, Clone, Deserialize)]
+pub struct LinkedCorpPermList {
+    #[serde(default)]
+    pub userids: Vec<String>,
+    #[serde(default)]
+    pub department_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LinkedCorpUser {
+    pub userid: String,
+    pub name: String,
+    #[serde(default)]
+    pub department: Vec<String>,
+    #[serde(default)]
+    pub mobile: String,
+    #[serde(default)]
+    pub telephone: String,
+    #[serde(default)]
+    pub email: String,
+    #[serde(default)]
+    pub position: String,
+    #[serde(default)]
+    pub corpid: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LinkedCorpSimpleUser {
+    pub userid: String,
+    pub name: String,
+    #[serde(default)]
+    pub department: Vec<String>,
+    #[serde(default)]
+    pub corpid: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LinkedCorpDepartment {
+    pub department_id: String,
+    pub department_name: String,
+    pub parentid: String,
+    #[serde(default)]
+    pub order: u64,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct UserRequest<'a> {
+    userid: &'a str,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct DepartmentRequest<'a> {
+    department_id: &'a str,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct UserResponse {
+    user_info: LinkedCorpUser,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct SimpleListResponse {
+    userlist: Vec<LinkedCorpSimpleUser>,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct DepartmentListResponse {
+    department_list: Vec<LinkedCorpDepartment>,
+}
+
+/// 互联企业
+#[cfg(feature = "native")]
+impl Client {
+    /// 获取应用的可见范围
+    pub async fn linkedcorp_get_perm_list(&self) -> Result<LinkedCorpPermList> {
+        self.post(
+            "/cgi-bin/linkedcorp/agent/get_perm_list",
+            &serde_json::json!({}),
+        )
+        .await
+    }
+
+    /// 获取互联企业成员详细信息, `userid` 形如 `CORPID/USERID`
+    pub async fn linkedcorp_get_user(&self, userid: &str) -> Result<LinkedCorpUser> {
+        let req = UserRequest { userid };
+        let ret: UserResponse = self.post("/cgi-bin/linkedcorp/user/get", &req).await?;
+        Ok(ret.user_info)
+    }
+
+    /// 获取互联企业部门成员, `department_id` 形如 `LINKEDID/DEPARTMENTID`
+    pub async fn linkedcorp_simple_list_users(
+        &self,
+        department_id: &str,
+    ) -> Result<Vec<LinkedCorpSimpleUser>> {
+        let req = DepartmentRequest { department_id };
+        let ret: SimpleListResponse = self
+            .post("/cgi-bin/linkedcorp/user/simplelist", &req)
+            .await?;
+        Ok(ret.userlist)
+    }
+
+    /// 获取互联企业部门列表, `department_id` 形如 `LINKEDID/DEPARTMENTID`
+    pub async fn linkedcorp_list_departments(
+        &self,
+        department_id: &str,
+    ) -> Result<Vec<LinkedCorpDepartment>> {
+        let req = DepartmentRequest { department_id };
+        let ret: DepartmentListResponse = self
+            .post("/cgi-bin/linkedcorp/department/list", &req)
+            .await?;
+        Ok(ret.department_list)
+    }
+}
@@ -0,0 +1,310 @@
+#[cfg(feature = "native")]
+use serde::de::IgnoredAny;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "native")]
+use crate::client::Client;
+#[cfg(feature = "native")]
+use crate::page::Page;
+#[cfg(feature = "native")]
+use crate::Result;
+
+/// 预约直播, 创建和修改时使用, 修改时为 None 的字段不变
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Living {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub livingid: Option<String>, // 创建时不指定, 修改时必须指定
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub anchor_userid: Option<String>, // 主播, 创建时必须指定, 不能修改
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub theme: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub living_start: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub living_duration: Option<u64>, // 单位秒
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub living_type: Option<u8>, // 0-通用直播, 1-小班课, 2-大班课, 3-企业培训, 4-活动直播
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remind_time: Option<u64>, // 开播前多少秒提醒观众
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub agentid: Option<u64>, // 发送直播通知的应用
+}
+
+impl Living {
+    pub fn new(
+        anchor_userid: impl ToString,
+        theme: impl ToString,
+        start: u64,
+        duration: u64,
+    ) -> Self {
+        Living {
+            anchor_userid: Some(anchor_userid.to_string()),
+            theme: Some(theme.to_string()),
+            living_start: Some(start),
+            living_duration: Some(duration),
+            ..Default::default()
+        }
+    }
+
+    /// 修改直播 `livingid`
+    pub fn update(livingid: impl ToString) -> Self {
+        Living {
+            livingid: Some(livingid.to_string()),
+            ..Default::default()
+        }
+    }
+}
+
+/// 直播详情
+#[derive(Debug, Clone, Deserialize)]
+pub struct LivingInfo {
+    pub theme: String,
+    pub living_start: u64,
+    pub living_duration: u64,
+    pub status: u8, // 0-预约中, 1-直播中, 2-已结束, 3-已过期, 4-已取消
+    #[serde(default)]
+    pub reserve_start: u64,
+    #[serde(default)]
+    pub reserve_living_duration: u64,
+    #[serde(default)]
+    pub description: String,
+    pub anchor_userid: String,
+    #[serde(default)]
+    pub main_department: u64,
+    #[serde(default)]
+    pub viewer_num: u32,
+    #[serde(default)]
+    pub comment_num: u32,
+    #[serde(default)]
+    pub mic_num: u32,
+    #[serde(default)]
+    pub open_replay: u8, // 是否开启回放, 1-开启
+    #[serde(default)]
+    pub replay_status: u8, // 0-生成成功, 1-生成中, 2-已删除, 3-生成失败
+    #[serde(default)]
+    #[serde(rename = "type")]
+    pub ty: u8, // 同 living_type
+    #[serde(default)]
+    pub push_stream_url: String,
+    #[serde(default)]
+    pub online_count: u32,
+    #[serde(default)]
+    pub subscribe_count: u32,
+}
+
+/// 直播观众, 企业成员的 `userid` 不为空, 外部联系人的 `external_userid` 不为空
+#[derive(Debug, Clone, Deserialize)]
+pub struct LivingViewer {
+    #[serde(default)]
+    pub userid: String,
+    #[serde(default)]
+    pub external_userid: String,
+    #[serde(default)]
+    #[serde(rename = "type")]
+    pub ty: u8, // 外部联系人类型, 1-微信用户, 2-企业微信用户
+    #[serde(default)]
+    pub name: String, // 外部联系人的名称
+    #[serde(default)]
+    pub watch_time: u64, // 观看时长, 单位秒
+    #[serde(default)]
+    pub is_comment: u8, // 是否评论, 1-是
+    #[serde(default)]
+    pub is_mic: u8, // 是否连麦, 1-是
+}
+
+impl LivingViewer {
+    pub fn is_external(&self) -> bool {
+        self.userid.is_empty()
+    }
+}
+
+/// 通过直播分享链接进入直播的观众信息
+#[derive(Debug, Clone, Deserialize)]
+pub struct LivingShareInfo {
+    pub livingid: String,
+    #[serde(default)]
+    pub viewer_userid: String,
+    #[serde(default)]
+    pub viewer_external_userid: String,
+    #[serde(default)]
+    pub invitor_userid: String, // 分享者
+    #[serde(default)]
+    pub invitor_external_userid: String,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct CreateLivingResponse {
+    livingid: String,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct LivingIdRequest<'a> {
+    livingid: &'a str,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct LivingInfoResponse {
+    living_info: LivingInfo,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct UserLivingRequest<'a> {
+    userid: &'a str,
+    cursor: &'a str,
+    limit: u32,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct UserLivingResponse {
+    #[serde(default)]
+    livingid_list: Vec<String>,
+    #[serde(default)]
+    next_cursor: String,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct WatchStatRequest<'a> {
+    livingid: &'a str,
+    next_key: &'a str,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct WatchStatResponse {
+    #[serde(default)]
+    ending: u8, // 1-已拉取完
+    #[serde(default)]
+    next_key: String,
+    #[serde(default)]
+    stat_info: WatchStatInfo,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Default, Deserialize)]
+struct WatchStatInfo {
+    #[serde(default)]
+    users: Vec<LivingViewer>,
+    #[serde(default)]
+    external_users: Vec<LivingViewer>,
+}
+
+#[cfg(feature = "native")]
+impl WatchStatResponse {
+    fn into_page(self) -> Page<LivingViewer> {
+        let mut viewers = self.stat_info.users;
+        viewers.extend(self.stat_info.external_users);
+        Page::new(viewers, self.next_key, self.ending == 1)
+    }
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct ShareInfoRequest<'a> {
+    ww_share_code: &'a str,
+}
+
+/// 直播
+#[cfg(feature = "native")]
+impl Client {
+    /// 创建预约直播, 返回直播 id
+    pub async fn create_living(&self, living: &Living) -> Result<String> {
+        let ret: CreateLivingResponse = self.post("/cgi-bin/living/create", living).await?;
+        Ok(ret.livingid)
+    }
+
+    /// 修改预约直播, `living.livingid` 必须指定
+    pub async fn modify_living(&self, living: &Living) -> Result<()> {
+        let _: IgnoredAny = self.post("/cgi-bin/living/modify", living).await?;
+        Ok(())
+    }
+
+    pub async fn cancel_living(&self, livingid: &str) -> Result<()> {
+        let req = LivingIdRequest { livingid };
+        let _: IgnoredAny = self.post("/cgi-bin/living/cancel", &req).await?;
+        Ok(())
+    }
+
+    pub async fn get_living_info(&self, livingid: &str) -> Result<LivingInfo> {
+        let ret: LivingInfoResponse = self
+            .get("/cgi-bin/living/get_living_info", &[("livingid", livingid)])
+            .await?;
+        Ok(ret.living_info)
+    }
+
+    /// 获取成员近 180 天内创建的直播 id, 每页最多 100 个
+    pub async fn get_user_all_livingid(&self, userid: &str, cursor: &str) -> Result<Page<String>> {
+        let req = UserLivingRequest {
+            userid,
+            cursor,
+            limit: 100,
+        };
+        let ret: UserLivingResponse = self
+            .post("/cgi-bin/living/get_user_all_livingid", &req)
+            .await?;
+        Ok(Page::new(ret.livingid_list, ret.next_cursor, false))
+    }
+}
+
+/// 直播观看统计和回放
+#[cfg(feature = "native")]
+impl Client {
+    /// 获取直播的观看明细, 只有直播结束后才能获取, 企业成员和外部联系人都在同一页中返回
+    ///
+    /// 第一页 `next_key` 传空字符串, 可以配合 `page::paginate` 使用
+    pub async fn get_watch_stat(
+        &self,
+        livingid: &str,
+        next_key: &str,
+    ) -> Result<Page<LivingViewer>> {
+        let req = WatchStatRequest { livingid, next_key };
+        let ret: WatchStatResponse = self.post("/cgi-bin/living/get_watch_stat", &req).await?;
+        Ok(ret.into_page())
+    }
+
+    /// 通过分享链接中的 `ww_share_code` 获取观众和分享者
+    pub async fn get_living_share_info(&self, ww_share_code: &str) -> Result<LivingShareInfo> {
+        let req = ShareInfoRequest { ww_share_code };
+        self.post("/cgi-bin/living/get_living_share_info", &req)
+            .await
+    }
+
+    pub async fn delete_living_replay_data(&self, livingid: &str) -> Result<()> {
+        let req = LivingIdRequest { livingid };
+        let _: IgnoredAny = self
+            .post("/cgi-bin/living/delete_replay_data", &req)
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "native"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_watch_stat_page() {
+        let data = r#"{
+            "ending": 1,
+            "next_key": "NEXT",
+            "stat_info": {
+                "users": [{"userid": "zhangsan", "watch_time": 30, "is_comment": 1, "is_mic": 0}],
+                "external_users": [{"external_userid": "wm1", "type": 1, "name": "lisi", "watch_time": 10}]
+            }
+        }"#;
+        let ret: WatchStatResponse = serde_json::from_str(data).unwrap();
+        let page = ret.into_page();
+        assert!(!page.has_more());
+        assert_eq!(page.items.len(), 2);
+        assert!(!page.items[0].is_external());
+        assert!(page.items[1].is_external());
+        assert_eq!(page.items[1].name, "lisi");
+    }
+}
@@ -8,6 +8,7 @@ pub enum FileType {
 }
 
 impl FileType {
+    #[cfg(feature = "native")]
     pub(crate) fn type_desc(&self) -> &'static str {
         use FileType::*;
         match self {
@@ -19,9 +20,28 @@ impl FileType {
     }
 }
 
+/// 附件资源的使用场景
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttachmentType {
+    Moment,       // 朋友圈
+    ProductAlbum, // 商品图册
+}
+
+impl AttachmentType {
+    #[cfg(feature = "native")]
+    pub(crate) fn value(&self) -> u8 {
+        match self {
+            AttachmentType::Moment => 1,
+            AttachmentType::ProductAlbum => 2,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct UploadFileResponse {
+    #[cfg(feature = "native")]
     pub(crate) errcode: u64,
+    #[cfg(feature = "native")]
     pub(crate) errmsg: String,
     #[serde(rename = "type")]
     #[serde(default)]
@@ -34,8 +54,99 @@ pub struct UploadFileResponse {
 
 #[derive(Debug, Deserialize)]
 pub struct UploadImageResponse {
+    #[cfg(feature = "native")]
     pub(crate) errcode: u64,
+    #[cfg(feature = "native")]
     pub(crate) errmsg: String,
     #[serde(default)]
     pub url: String,
 }
+
+/// 异步上传任务的结果
+#[derive(Debug, Clone, Deserialize)]
+pub struct UploadByUrlResult {
+    pub status: u8, // 1-处理中，2-完成，3-异常失败
+    #[serde(default)]
+    pub detail: UploadByUrlDetail,
+}
+
+impl UploadByUrlResult {
+    pub fn is_finished(&self) -> bool {
+        self.status == 2 || self.status == 3
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct UploadByUrlDetail {
+    #[serde(default)]
+    pub errcode: u64,
+    #[serde(default)]
+    pub errmsg: String,
+    #[serde(default)]
+    pub media_id: String, // 3 天内有效
+    #[serde(default)]
+    pub created_at: String,
+}
+
+// uploadimg only accepts jpg and png images between 5B and 2MB
+#[cfg(feature = "native")]
+pub(crate) const MAX_IMAGE_SIZE: usize = 2 * 1024 * 1024;
+#[cfg(feature = "native")]
+pub(crate) const MIN_IMAGE_SIZE: usize = 5;
+
+/// 根据文件头判断图片格式, 返回 MIME 类型, 只识别 jpg 和 png
+#[cfg(feature = "native")]
+pub(crate) fn sniff_image(data: &[u8]) -> Option<&'static str> {
+    if data.starts_with(&[0xff, 0xd8, 0xff]) {
+        Some("image/jpeg")
+    } else if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("image/png")
+    } else {
+        None
+    }
+}
+
+/// 临时素材文件
+#[derive(Debug, Clone)]
+pub struct MediaFile {
+    pub file_name: Option<String>,    // Content-Disposition 中的文件名
+    pub content_type: Option<String>, // 如 image/jpeg
+    pub data: Vec<u8>,
+}
+
+// extract the file name from a header like `attachment; filename="a.jpg"`
+#[cfg(feature = "native")]
+pub(crate) fn parse_file_name(disposition: &str) -> Option<String> {
+    disposition.split(';').find_map(|s| {
+        let v = s.trim().strip_prefix("filename=")?;
+        let v = v.trim_matches('"');
+        if v.is_empty() {
+            None
+        } else {
+            Some(v.to_string())
+        }
+    })
+}
+
+#[cfg(all(test, feature = "native"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sniff_image() {
+        assert_eq!(sniff_image(&[0xff, 0xd8, 0xff, 0xe0]), Some("image/jpeg"));
+        assert_eq!(sniff_image(b"\x89PNG\r\n\x1a\n\0\0"), Some("image/png"));
+        assert_eq!(sniff_image(b"GIF89a"), None);
+    }
+
+    #[test]
+    fn test_parse_file_name() {
+        let n = parse_file_name(r#"attachment; filename="MEDIA_ID.jpg""#);
+        assert_eq!(n.as_deref(), Some("MEDIA_ID.jpg"));
+        assert_eq!(
+            parse_file_name("attachment; filename=a.amr").as_deref(),
+            Some("a.amr")
+        );
+        assert_eq!(parse_file_name("attachment"), None);
+    }
+}
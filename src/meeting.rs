@@ -0,0 +1,263 @@
+#[cfg(feature = "native")]
+use serde::de::IgnoredAny;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "native")]
+use crate::client::Client;
+#[cfg(feature = "native")]
+use crate::page::Page;
+#[cfg(feature = "native")]
+use crate::Result;
+
+/// 预约会议, 创建和修改时使用, 修改时为 None 的字段不变
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Meeting {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meetingid: Option<String>, // 创建时不指定, 修改时必须指定
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub admin_userid: Option<String>, // 会议管理员, 创建时必须指定
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meeting_start: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meeting_duration: Option<u64>, // 单位秒, 最少 300
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub agentid: Option<u64>, // 发送会议通知的应用
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invitees: Option<Users>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub settings: Option<MeetingSettings>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cal_id: Option<String>, // 会议所属的日历
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reminders: Option<MeetingReminders>,
+}
+
+impl Meeting {
+    pub fn new(
+        admin_userid: impl ToString,
+        title: impl ToString,
+        start: u64,
+        duration: u64,
+    ) -> Self {
+        Meeting {
+            admin_userid: Some(admin_userid.to_string()),
+            title: Some(title.to_string()),
+            meeting_start: Some(start),
+            meeting_duration: Some(duration),
+            ..Default::default()
+        }
+    }
+
+    /// 修改会议 `meetingid`
+    pub fn update(meetingid: impl ToString) -> Self {
+        Meeting {
+            meetingid: Some(meetingid.to_string()),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Users {
+    #[serde(default)]
+    pub userid: Vec<String>,
+}
+
+impl Users {
+    pub fn new(userids: &[&str]) -> Self {
+        Users {
+            userid: userids.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MeetingSettings {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>, // 4 到 6 位数字
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enable_waiting_room: Option<bool>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_enter_before_host: Option<bool>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remind_scope: Option<u8>, // 会议开始时来电提醒, 1-不提醒, 2-仅提醒主持人, 3-提醒所有成员, 4-指定部分人员
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enable_enter_mute: Option<u8>, // 0-关闭, 1-开启, 2-超过 6 人后自动开启
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_external_user: Option<bool>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enable_screen_watermark: Option<bool>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hosts: Option<Users>, // 主持人
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ring_users: Option<Users>, // remind_scope 为 4 时来电提醒的成员
+}
+
+/// 会议的重复和提醒设置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MeetingReminders {
+    #[serde(default)]
+    pub is_repeat: u8, // 0-不重复, 1-重复
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repeat_type: Option<u8>, // 0-每天, 1-每周, 2-每月, 7-每个工作日
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repeat_until: Option<u64>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repeat_interval: Option<u32>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub remind_before: Vec<u64>, // 提前提醒的秒数, 比如 0, 300, 900
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateMeetingResponse {
+    pub meetingid: String,
+    #[serde(default)]
+    pub excess_users: Vec<String>, // 参会人数超过上限时无法邀请的成员
+}
+
+/// 会议详情
+#[derive(Debug, Clone, Deserialize)]
+pub struct MeetingInfo {
+    pub admin_userid: String,
+    pub title: String,
+    pub meeting_start: u64,
+    pub meeting_duration: u64,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub location: String,
+    #[serde(default)]
+    pub main_department: u64,
+    pub status: u8, // 1-待开始, 2-会议中, 3-已结束, 4-已取消, 5-已过期
+    #[serde(default)]
+    pub meeting_type: u8, // 0-一次性会议, 1-周期性会议
+    #[serde(default)]
+    pub attendees: MeetingAttendees,
+    #[serde(default)]
+    pub settings: MeetingSettings,
+    #[serde(default)]
+    pub cal_id: String,
+    #[serde(default)]
+    pub reminders: MeetingReminders,
+    #[serde(default)]
+    pub meeting_code: String,
+    #[serde(default)]
+    pub meeting_link: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct MeetingAttendees {
+    #[serde(default)]
+    pub member: Vec<MeetingMember>,
+    #[serde(default)]
+    pub tmp_external_user: Vec<MeetingMember>, // 外部参会人, userid 为临时 id
+}
+
+/// 参会人及其参会情况
+#[derive(Debug, Clone, Deserialize)]
+pub struct MeetingMember {
+    #[serde(default)]
+    #[serde(alias = "tmp_external_userid")]
+    pub userid: String,
+    #[serde(default)]
+    pub status: u8, // 1-已参与, 2-未参与
+    #[serde(default)]
+    pub first_join_time: u64,
+    #[serde(default)]
+    pub last_quit_time: u64,
+    #[serde(default)]
+    pub total_join_count: u32,
+    #[serde(default)]
+    pub cumulative_time: u64, // 累计参会时长, 单位秒
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct UserMeetingRequest<'a> {
+    userid: &'a str,
+    cursor: &'a str,
+    begin_time: u64,
+    end_time: u64,
+    limit: u32,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct UserMeetingResponse {
+    #[serde(default)]
+    meetingid_list: Vec<String>,
+    #[serde(default)]
+    next_cursor: String,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct MeetingIdRequest<'a> {
+    meetingid: &'a str,
+}
+
+/// 会议
+#[cfg(feature = "native")]
+impl Client {
+    /// 创建预约会议
+    pub async fn create_meeting(&self, meeting: &Meeting) -> Result<CreateMeetingResponse> {
+        self.post("/cgi-bin/meeting/create", meeting).await
+    }
+
+    /// 修改预约会议, `meeting.meetingid` 必须指定
+    pub async fn update_meeting(&self, meeting: &Meeting) -> Result<()> {
+        let _: IgnoredAny = self.post("/cgi-bin/meeting/update", meeting).await?;
+        Ok(())
+    }
+
+    pub async fn cancel_meeting(&self, meetingid: &str) -> Result<()> {
+        let req = MeetingIdRequest { meetingid };
+        let _: IgnoredAny = self.post("/cgi-bin/meeting/cancel", &req).await?;
+        Ok(())
+    }
+
+    pub async fn get_meeting_info(&self, meetingid: &str) -> Result<MeetingInfo> {
+        let req = MeetingIdRequest { meetingid };
+        self.post("/cgi-bin/meeting/get_info", &req).await
+    }
+
+    /// 获取成员在一段时间内的会议 id, 时间跨度不超过 31 天, 每页最多 100 个
+    pub async fn get_user_meetingid(
+        &self,
+        userid: &str,
+        begin_time: u64,
+        end_time: u64,
+        cursor: &str,
+    ) -> Result<Page<String>> {
+        let req = UserMeetingRequest {
+            userid,
+            cursor,
+            begin_time,
+            end_time,
+            limit: 100,
+        };
+        let ret: UserMeetingResponse = self
+            .post("/cgi-bin/meeting/get_user_meetingid", &req)
+            .await?;
+        Ok(Page::new(ret.meetingid_list, ret.next_cursor, false))
+    }
+}
@@ -0,0 +1,325 @@
+use serde::de::Deserializer;
+#[cfg(feature = "native")]
+use serde::de::IgnoredAny;
+use serde::ser::Serializer;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "native")]
+use crate::client::Client;
+#[cfg(feature = "native")]
+use crate::Result;
+
+/// 会议室的设备, 可以用 `|` 组合, 接口中以设备 id 列表的形式传递
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Equipment(u8);
+
+impl Equipment {
+    pub const TV: Equipment = Equipment(1 << 1);
+    pub const PHONE: Equipment = Equipment(1 << 2);
+    pub const PROJECTOR: Equipment = Equipment(1 << 3);
+    pub const WHITEBOARD: Equipment = Equipment(1 << 4);
+    pub const VIDEO: Equipment = Equipment(1 << 5);
+
+    pub fn empty() -> Self {
+        Equipment(0)
+    }
+
+    pub fn contains(&self, other: Equipment) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    // equipment ids used by the api: 1-电视, 2-电话, 3-投影, 4-白板, 5-视频
+    fn ids(&self) -> Vec<u8> {
+        (1..=5).filter(|i| self.0 & (1 << i) != 0).collect()
+    }
+
+    fn from_ids(ids: &[u8]) -> Self {
+        let bits = ids
+            .iter()
+            .filter(|i| (1..=5).contains(*i))
+            .fold(0, |acc, i| acc | (1 << i));
+        Equipment(bits)
+    }
+}
+
+impl std::ops::BitOr for Equipment {
+    type Output = Equipment;
+
+    fn bitor(self, rhs: Equipment) -> Equipment {
+        Equipment(self.0 | rhs.0)
+    }
+}
+
+impl Serialize for Equipment {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.ids().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Equipment {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let ids = Vec::<u8>::deserialize(deserializer)?;
+        Ok(Equipment::from_ids(&ids))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Coordinate {
+    pub latitude: String,
+    pub longitude: String,
+}
+
+/// 会议室
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MeetingRoom {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meetingroom_id: Option<u64>, // 添加时不指定
+    pub name: String,
+    pub capacity: u32,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub city: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub building: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub floor: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Equipment::is_empty")]
+    pub equipment: Equipment,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub coordinate: Option<Coordinate>,
+    #[serde(default)]
+    #[serde(skip_serializing)]
+    pub need_approval: bool, // 只在读取时返回
+}
+
+/// 查询会议室的条件, 都不指定时返回所有会议室
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MeetingRoomFilter {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub city: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub building: Option<String>, // 需要同时指定 city
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub floor: Option<String>, // 需要同时指定 building
+    #[serde(skip_serializing_if = "Equipment::is_empty")]
+    pub equipment: Equipment,
+}
+
+/// 预定会议室
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BookingRequest {
+    pub meetingroom_id: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    pub start_time: u64, // 以半小时为单位, 比如 9:00 或者 9:30
+    pub end_time: u64,
+    pub booker: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub attendees: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BookingResponse {
+    pub booking_id: String,
+    #[serde(default)]
+    pub schedule_id: String, // 同时创建的日程
+}
+
+/// 会议室在一段时间内的预定情况
+#[derive(Debug, Clone, Deserialize)]
+pub struct MeetingRoomBookings {
+    pub meetingroom_id: u64,
+    #[serde(default)]
+    pub schedule: Vec<Booking>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Booking {
+    pub booking_id: String,
+    #[serde(default)]
+    pub meeting_id: String,
+    #[serde(default)]
+    pub schedule_id: String,
+    pub start_time: u64,
+    pub end_time: u64,
+    #[serde(default)]
+    pub booker: String,
+    #[serde(default)]
+    pub status: u8, // 0-已预定, 1-已取消, 2-申请中, 3-审批中
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct CancelBookRequest<'a> {
+    booking_id: &'a str,
+    keep_schedule: u8,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct BookingInfoRequest<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    meetingroom_id: Option<u64>,
+    start_time: u64,
+    end_time: u64,
+    #[serde(flatten)]
+    filter: Option<&'a MeetingRoomFilter>,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct BookingInfoResponse {
+    #[serde(default)]
+    booking_list: Vec<MeetingRoomBookings>,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct BookingByMeetingRequest<'a> {
+    meetingroom_id: u64,
+    meeting_id: &'a str,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct BookingByMeetingResponse {
+    schedule: Booking,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct MeetingRoomIdRequest {
+    meetingroom_id: u64,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct AddMeetingRoomResponse {
+    meetingroom_id: u64,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct MeetingRoomListResponse {
+    #[serde(default)]
+    meetingroom_list: Vec<MeetingRoom>,
+}
+
+/// 会议室
+#[cfg(feature = "native")]
+impl Client {
+    /// 添加会议室, 返回会议室 id
+    pub async fn add_meeting_room(&self, room: &MeetingRoom) -> Result<u64> {
+        let ret: AddMeetingRoomResponse = self.post("/cgi-bin/oa/meetingroom/add", room).await?;
+        Ok(ret.meetingroom_id)
+    }
+
+    pub async fn list_meeting_rooms(&self, filter: &MeetingRoomFilter) -> Result<Vec<MeetingRoom>> {
+        let ret: MeetingRoomListResponse =
+            self.post("/cgi-bin/oa/meetingroom/list", filter).await?;
+        Ok(ret.meetingroom_list)
+    }
+
+    /// 编辑会议室, `room.meetingroom_id` 必须指定
+    pub async fn edit_meeting_room(&self, room: &MeetingRoom) -> Result<()> {
+        let _: IgnoredAny = self.post("/cgi-bin/oa/meetingroom/edit", room).await?;
+        Ok(())
+    }
+
+    pub async fn del_meeting_room(&self, meetingroom_id: u64) -> Result<()> {
+        let req = MeetingRoomIdRequest { meetingroom_id };
+        let _: IgnoredAny = self.post("/cgi-bin/oa/meetingroom/del", &req).await?;
+        Ok(())
+    }
+
+    /// 预定会议室, 同时会在预定人的日历中创建日程
+    pub async fn book_meeting_room(&self, req: &BookingRequest) -> Result<BookingResponse> {
+        self.post("/cgi-bin/oa/meetingroom/book", req).await
+    }
+
+    /// 取消预定, `keep_schedule` 为 true 时保留对应的日程
+    pub async fn cancel_meeting_room_booking(
+        &self,
+        booking_id: &str,
+        keep_schedule: bool,
+    ) -> Result<()> {
+        let req = CancelBookRequest {
+            booking_id,
+            keep_schedule: keep_schedule as u8,
+        };
+        let _: IgnoredAny = self
+            .post("/cgi-bin/oa/meetingroom/cancel_book", &req)
+            .await?;
+        Ok(())
+    }
+
+    /// 查询一段时间内会议室的预定情况, `meetingroom_id` 为 None 时按 `filter` 查询多个会议室
+    pub async fn get_meeting_room_booking_info(
+        &self,
+        meetingroom_id: Option<u64>,
+        start_time: u64,
+        end_time: u64,
+        filter: Option<&MeetingRoomFilter>,
+    ) -> Result<Vec<MeetingRoomBookings>> {
+        let req = BookingInfoRequest {
+            meetingroom_id,
+            start_time,
+            end_time,
+            filter,
+        };
+        let ret: BookingInfoResponse = self
+            .post("/cgi-bin/oa/meetingroom/get_booking_info", &req)
+            .await?;
+        Ok(ret.booking_list)
+    }
+
+    /// 查询会议 `meeting_id` 关联的会议室预定
+    pub async fn get_booking_info_by_meeting_id(
+        &self,
+        meetingroom_id: u64,
+        meeting_id: &str,
+    ) -> Result<Booking> {
+        let req = BookingByMeetingRequest {
+            meetingroom_id,
+            meeting_id,
+        };
+        let ret: BookingByMeetingResponse = self
+            .post(
+                "/cgi-bin/oa/meetingroom/bookinfo/get_booking_info_by_meeting_id",
+                &req,
+            )
+            .await?;
+        Ok(ret.schedule)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_equipment() {
+        let e = Equipment::TV | Equipment::WHITEBOARD;
+        assert!(e.contains(Equipment::TV));
+        assert!(!e.contains(Equipment::PHONE));
+        assert_eq!(serde_json::to_string(&e).unwrap(), "[1,4]");
+
+        let e: Equipment = serde_json::from_str("[2,3,9]").unwrap();
+        assert_eq!(e, Equipment::PHONE | Equipment::PROJECTOR);
+    }
+}
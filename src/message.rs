@@ -3,21 +3,38 @@ use serde::ser::{SerializeMap, Serializer};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::template_card::TemplateCard;
+
 #[derive(Debug, Deserialize)]
 pub struct MessageResponse {
+    #[allow(dead_code)]
     errcode: u64,
+    #[allow(dead_code)]
     errmsg: String,
     pub invaliduser: Option<String>,
     pub invalidparty: Option<String>,
     pub invalidtag: Option<String>,
+    #[serde(default)]
+    pub msgid: String, // 用于撤回消息
+    #[serde(default)]
+    pub response_code: String, // 仅模板卡片消息返回, 用于更新卡片, 72 小时内有效且只能使用一次
 }
 
 #[derive(Error, Debug)]
 pub enum MessageBuildError {
     #[error("receiver can not be empty")]
     EmptyReceiver,
+    #[error("field {0} exceeds {1} characters")]
+    FieldTooLong(&'static str, usize),
+    #[error("field {0} has more than {1} items")]
+    TooManyItems(&'static str, usize),
+    #[error("field {0} is required")]
+    MissingField(&'static str),
+    #[error("invalid field {0}: {1}")]
+    InvalidField(&'static str, String),
 }
 
+#[derive(Clone)]
 pub struct MessageBuilder {
     to_users: Vec<String>,
     to_parties: Vec<String>,
@@ -43,24 +60,26 @@ pub struct Message {
     duplicate_check_interval: Option<u32>, // 表示是否重复消息检查的时间间隔，默认1800s，最大不超过4小时
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 enum MessageType {
     Text(Text),
+    Markdown(Text),
     File(File),
     Image(Image),
+    TemplateCard(Box<TemplateCard>),
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 struct Text {
     content: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 struct File {
     media_id: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 struct Image {
     media_id: String,
 }
@@ -84,6 +103,12 @@ impl MessageBuilder {
         Self::new(agent_id, data)
     }
 
+    /// markdown 消息, 目前仅支持 markdown 语法的子集
+    pub fn new_markdown(agent_id: u64, content: String) -> Self {
+        let data = MessageType::Markdown(Text { content });
+        Self::new(agent_id, data)
+    }
+
     pub fn new_file(agent_id: u64, media_id: String) -> Self {
         let data = MessageType::File(File { media_id });
         Self::new(agent_id, data)
@@ -94,6 +119,19 @@ impl MessageBuilder {
         Self::new(agent_id, data)
     }
 
+    /// 模板卡片, 通过 `TemplateCardBuilder` 构造
+    pub fn new_template_card(agent_id: u64, card: TemplateCard) -> Self {
+        Self::new(agent_id, MessageType::TemplateCard(Box::new(card)))
+    }
+
+    /// 清空已添加的成员, 部门和标签
+    pub fn clear_receivers(mut self) -> Self {
+        self.to_users.clear();
+        self.to_parties.clear();
+        self.to_tags.clear();
+        self
+    }
+
     pub fn with_user(mut self, user: String) -> Self {
         self.to_users.push(user);
         self
@@ -150,6 +188,16 @@ impl MessageBuilder {
     }
 }
 
+impl Message {
+    /// 模板卡片消息的 task_id
+    pub fn task_id(&self) -> Option<&str> {
+        match &self.data {
+            MessageType::TemplateCard(card) => card.task_id(),
+            _ => None,
+        }
+    }
+}
+
 impl Serialize for Message {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -182,6 +230,10 @@ impl Serialize for Message {
                 map.serialize_entry("msgtype", "text")?;
                 map.serialize_entry("text", t)?;
             }
+            Markdown(t) => {
+                map.serialize_entry("msgtype", "markdown")?;
+                map.serialize_entry("markdown", t)?;
+            }
             File(t) => {
                 map.serialize_entry("msgtype", "file")?;
                 map.serialize_entry("file", t)?;
@@ -190,6 +242,10 @@ impl Serialize for Message {
                 map.serialize_entry("msgtype", "image")?;
                 map.serialize_entry("image", t)?;
             }
+            TemplateCard(t) => {
+                map.serialize_entry("msgtype", "template_card")?;
+                map.serialize_entry("template_card", t)?;
+            }
         }
 
         map.end()
@@ -0,0 +1,206 @@
+use std::convert::TryFrom;
+
+use serde::Deserialize;
+
+/// 解密后的存档消息
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChatMessage {
+    pub msgid: String,
+    pub action: String, // send-发送, recall-撤回, switch-切换企业
+    #[serde(default)]
+    pub from: String,
+    #[serde(default)]
+    pub tolist: Vec<String>,
+    #[serde(default)]
+    pub roomid: String, // 单聊时为空
+    #[serde(default)]
+    pub msgtime: u64, // 单位毫秒
+    #[serde(flatten)]
+    pub content: ChatContent,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "RawContent")]
+pub enum ChatContent {
+    Text(ChatText),
+    Image(ChatMedia),
+    Voice(ChatMedia),
+    Video(ChatMedia),
+    Emotion(ChatMedia),
+    File(ChatMedia),
+    Revoke(ChatRevoke),
+    Agree(ChatAgree),
+    Disagree(ChatAgree),
+    Card(ChatCard),
+    Location(ChatLocation),
+    Link(ChatLink),
+    Weapp(ChatWeapp),
+    Switch(ChatSwitch),
+    Other(String), // 暂不支持的消息类型, 值为 msgtype
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChatText {
+    pub content: String,
+}
+
+/// 图片, 语音, 视频, 表情和文件, 可以用 `sdkfileid` 通过存档 sdk 拉取媒体文件
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChatMedia {
+    pub sdkfileid: String,
+    #[serde(default)]
+    pub md5sum: String,
+    #[serde(default)]
+    #[serde(alias = "voice_size", alias = "imagesize")]
+    pub filesize: u64,
+    #[serde(default)]
+    pub play_length: u64, // 语音和视频的时长, 单位秒
+    #[serde(default)]
+    pub filename: String,
+    #[serde(default)]
+    pub fileext: String,
+    #[serde(default)]
+    pub width: u32, // 表情的宽高
+    #[serde(default)]
+    pub height: u32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChatRevoke {
+    pub pre_msgid: String, // 被撤回的消息
+}
+
+/// 外部联系人同意或者不同意存档
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChatAgree {
+    pub userid: String,
+    #[serde(default)]
+    pub agree_time: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChatCard {
+    #[serde(default)]
+    pub corpname: String,
+    pub userid: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChatLocation {
+    pub longitude: f64,
+    pub latitude: f64,
+    #[serde(default)]
+    pub address: String,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub zoom: u32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChatLink {
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    pub link_url: String,
+    #[serde(default)]
+    pub image_url: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChatWeapp {
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub username: String, // 小程序的原始 id
+    #[serde(default)]
+    pub displayname: String,
+}
+
+/// 成员切换企业的日志, 这种消息没有 msgtype
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChatSwitch {
+    pub time: u64,
+    pub user: String,
+}
+
+// the wire format: {"msgtype": "text", "text": {...}}, except switch: {"time": .., "user": ..}
+#[derive(Debug, Deserialize)]
+struct RawContent {
+    msgtype: Option<String>,
+    text: Option<ChatText>,
+    image: Option<ChatMedia>,
+    voice: Option<ChatMedia>,
+    video: Option<ChatMedia>,
+    emotion: Option<ChatMedia>,
+    file: Option<ChatMedia>,
+    revoke: Option<ChatRevoke>,
+    agree: Option<ChatAgree>,
+    disagree: Option<ChatAgree>,
+    card: Option<ChatCard>,
+    location: Option<ChatLocation>,
+    link: Option<ChatLink>,
+    weapp: Option<ChatWeapp>,
+    time: Option<u64>,
+    user: Option<String>,
+}
+
+impl TryFrom<RawContent> for ChatContent {
+    type Error = String;
+
+    fn try_from(raw: RawContent) -> std::result::Result<Self, String> {
+        let msgtype = match raw.msgtype {
+            Some(msgtype) => msgtype,
+            None => {
+                return match (raw.time, raw.user) {
+                    (Some(time), Some(user)) => Ok(ChatContent::Switch(ChatSwitch { time, user })),
+                    _ => Err("missing msgtype".to_string()),
+                }
+            }
+        };
+        let ret = match &*msgtype {
+            "text" => raw.text.map(ChatContent::Text),
+            "image" => raw.image.map(ChatContent::Image),
+            "voice" => raw.voice.map(ChatContent::Voice),
+            "video" => raw.video.map(ChatContent::Video),
+            "emotion" => raw.emotion.map(ChatContent::Emotion),
+            "file" => raw.file.map(ChatContent::File),
+            "revoke" => raw.revoke.map(ChatContent::Revoke),
+            "agree" => raw.agree.map(ChatContent::Agree),
+            "disagree" => raw.disagree.map(ChatContent::Disagree),
+            "card" => raw.card.map(ChatContent::Card),
+            "location" => raw.location.map(ChatContent::Location),
+            "link" => raw.link.map(ChatContent::Link),
+            "weapp" => raw.weapp.map(ChatContent::Weapp),
+            _ => return Ok(ChatContent::Other(msgtype)),
+        };
+        ret.ok_or_else(|| format!("missing {} content", msgtype))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chat_message() {
+        let data = r#"{"msgid": "1", "action": "send", "from": "zhangsan", "tolist": ["wm1"], "roomid": "", "msgtime": 1547087894783, "msgtype": "voice", "voice": {"md5sum": "abc", "voice_size": 4355, "play_length": 10, "sdkfileid": "kcyZjZ"}}"#;
+        let msg: ChatMessage = serde_json::from_str(data).unwrap();
+        match msg.content {
+            ChatContent::Voice(v) => assert_eq!(v.filesize, 4355),
+            c => panic!("unexpected content: {:?}", c),
+        }
+
+        let data =
+            r#"{"msgid": "2", "action": "switch", "time": 1554119421840, "user": "zhangsan"}"#;
+        let msg: ChatMessage = serde_json::from_str(data).unwrap();
+        assert!(matches!(msg.content, ChatContent::Switch(s) if s.user == "zhangsan"));
+
+        let data = r#"{"msgid": "3", "action": "send", "msgtype": "vote", "vote": {}}"#;
+        let msg: ChatMessage = serde_json::from_str(data).unwrap();
+        assert!(matches!(msg.content, ChatContent::Other(t) if t == "vote"));
+    }
+}
@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+
+use rsa::pkcs1::DecodeRsaPrivateKey;
+use rsa::pkcs8::DecodePrivateKey;
+use rsa::{Pkcs1v15Encrypt, RsaPrivateKey};
+use serde::Deserialize;
+
+use super::ChatMessage;
+use crate::server::crypto::aes_decrypt;
+use crate::{Error, Result};
+
+/// 存档 sdk `GetChatData` 返回的一条加密消息
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChatData {
+    pub seq: u64, // 下次拉取时使用最大的 seq
+    pub msgid: String,
+    pub publickey_ver: u32, // 加密使用的公钥版本, 用于选择对应的私钥
+    pub encrypt_random_key: String,
+    pub encrypt_chat_msg: String,
+}
+
+/// `GetChatData` 的返回结果
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChatDataList {
+    #[serde(default)]
+    pub chatdata: Vec<ChatData>,
+}
+
+/// 解密存档消息, 需要配置管理后台中每个公钥版本对应的私钥
+///
+/// 先用私钥以 RSA PKCS#1 v1.5 解密 `encrypt_random_key` 得到随机密钥,
+/// 再以随机密钥 AES-256-CBC 解密 `encrypt_chat_msg`, iv 为密钥的前 16 字节
+#[derive(Debug, Clone, Default)]
+pub struct ChatDecryptor {
+    keys: HashMap<u32, RsaPrivateKey>,
+}
+
+impl ChatDecryptor {
+    pub fn new() -> Self {
+        ChatDecryptor::default()
+    }
+
+    /// 添加公钥版本 `publickey_ver` 对应的 PEM 格式私钥, 支持 PKCS#1 和 PKCS#8
+    pub fn key(mut self, publickey_ver: u32, private_key_pem: &str) -> Result<Self> {
+        let key = RsaPrivateKey::from_pkcs1_pem(private_key_pem)
+            .or_else(|_| RsaPrivateKey::from_pkcs8_pem(private_key_pem))
+            .map_err(|e| Error::DecryptFailed(format!("invalid private key: {}", e)))?;
+        self.keys.insert(publickey_ver, key);
+        Ok(self)
+    }
+
+    pub fn decrypt(&self, data: &ChatData) -> Result<ChatMessage> {
+        let plain = self.decrypt_raw(data)?;
+        let msg = serde_json::from_slice(&plain)?;
+        Ok(msg)
+    }
+
+    /// 解密得到消息的 json, 可以用于处理 `ChatContent` 暂不支持的消息类型
+    pub fn decrypt_raw(&self, data: &ChatData) -> Result<Vec<u8>> {
+        let key = self.keys.get(&data.publickey_ver).ok_or_else(|| {
+            let msg = format!("no private key of version {}", data.publickey_ver);
+            Error::DecryptFailed(msg)
+        })?;
+
+        let random_key = base64_decode(&data.encrypt_random_key)?;
+        let random_key = key
+            .decrypt(Pkcs1v15Encrypt, &random_key)
+            .map_err(|e| Error::DecryptFailed(format!("rsa decrypt failed: {}", e)))?;
+        if random_key.len() != 32 {
+            return Err(Error::DecryptFailed(
+                "invalid random key length".to_string(),
+            ));
+        }
+
+        let chat_msg = base64_decode(&data.encrypt_chat_msg)?;
+        aes_decrypt(&random_key, &chat_msg).map_err(|e| Error::DecryptFailed(format!("{}", e)))
+    }
+}
+
+fn base64_decode(s: &str) -> Result<Vec<u8>> {
+    base64::decode(s).map_err(|_| Error::DecryptFailed("invalid base64 string".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use aes::Aes256;
+    use block_modes::block_padding::Pkcs7;
+    use block_modes::{BlockMode, Cbc};
+    use rsa::pkcs1::{EncodeRsaPrivateKey, LineEnding};
+    use rsa::RsaPublicKey;
+
+    use super::*;
+    use crate::msgaudit::ChatContent;
+
+    #[test]
+    fn test_decrypt() {
+        let mut rng = rsa::rand_core::OsRng;
+        let private_key = RsaPrivateKey::new(&mut rng, 1024).unwrap();
+        let pem = private_key.to_pkcs1_pem(LineEnding::LF).unwrap();
+        let public_key = RsaPublicKey::from(&private_key);
+
+        let random_key = *b"0123456789abcdef0123456789abcdef";
+        let encrypted_key = public_key
+            .encrypt(&mut rng, Pkcs1v15Encrypt, &random_key)
+            .unwrap();
+        let msg = r#"{"msgid": "1", "action": "send", "from": "zhangsan", "tolist": ["lisi"], "msgtime": 1, "msgtype": "text", "text": {"content": "你好"}}"#;
+        let cipher = Cbc::<Aes256, Pkcs7>::new_var(&random_key, &random_key[..16]).unwrap();
+        let data = ChatData {
+            seq: 1,
+            msgid: "1".to_string(),
+            publickey_ver: 2,
+            encrypt_random_key: base64::encode(encrypted_key),
+            encrypt_chat_msg: base64::encode(cipher.encrypt_vec(msg.as_bytes())),
+        };
+
+        let decryptor = ChatDecryptor::new().key(2, &pem).unwrap();
+        let msg = decryptor.decrypt(&data).unwrap();
+        assert!(matches!(msg.content, ChatContent::Text(t) if t.content == "你好"));
+
+        let decryptor = ChatDecryptor::new().key(1, &pem).unwrap();
+        assert!(decryptor.decrypt(&data).is_err());
+    }
+}
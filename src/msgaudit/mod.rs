@@ -0,0 +1,143 @@
+//! 会话内容存档
+
+mod chat;
+#[cfg(feature = "msgaudit")]
+mod decrypt;
+
+pub use chat::*;
+#[cfg(feature = "msgaudit")]
+pub use decrypt::*;
+
+use serde::Deserialize;
+#[cfg(feature = "native")]
+use serde::Serialize;
+
+#[cfg(feature = "native")]
+use crate::client::Client;
+#[cfg(feature = "native")]
+use crate::Result;
+
+/// 同意存档的情况
+#[derive(Debug, Clone, Deserialize)]
+pub struct AgreeInfo {
+    #[serde(default)]
+    pub status_change_time: u64,
+    #[serde(default)]
+    pub userid: String, // 单聊时的成员
+    #[serde(rename = "exteranalopenid")] // 接口的字段名就是这样拼写的
+    pub external_userid: String,
+    pub agree_status: String, // Agree 或者 Disagree
+}
+
+impl AgreeInfo {
+    pub fn is_agreed(&self) -> bool {
+        self.agree_status == "Agree"
+    }
+}
+
+/// 内部群的信息, 外部群请使用客户群接口
+#[derive(Debug, Clone, Deserialize)]
+pub struct AuditGroupChat {
+    #[serde(default)]
+    pub roomname: String,
+    #[serde(default)]
+    pub creator: String,
+    #[serde(default)]
+    pub room_create_time: u64,
+    #[serde(default)]
+    pub notice: String,
+    #[serde(default)]
+    pub members: Vec<AuditGroupMember>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AuditGroupMember {
+    pub memberid: String,
+    #[serde(default)]
+    pub jointime: u64,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct PermitUserRequest {
+    #[serde(rename = "type")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ty: Option<u8>,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct PermitUserResponse {
+    #[serde(default)]
+    ids: Vec<String>,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct SingleAgreeRequest<'a> {
+    info: Vec<SingleAgreeItem<'a>>,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct SingleAgreeItem<'a> {
+    userid: &'a str,
+    exteranalopenid: &'a str,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct RoomIdRequest<'a> {
+    roomid: &'a str,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct AgreeResponse {
+    #[serde(default)]
+    agreeinfo: Vec<AgreeInfo>,
+}
+
+/// 会话内容存档
+#[cfg(feature = "native")]
+impl Client {
+    /// 获取开启了会话存档的成员, `ty` 1-办公版, 2-服务版, 3-企业版, 为 None 时返回所有版本的成员
+    pub async fn get_permit_user_list(&self, ty: Option<u8>) -> Result<Vec<String>> {
+        let req = PermitUserRequest { ty };
+        let ret: PermitUserResponse = self
+            .post("/cgi-bin/msgaudit/get_permit_user_list", &req)
+            .await?;
+        Ok(ret.ids)
+    }
+
+    /// 查询外部联系人是否同意和成员的单聊被存档, `pairs` 为 (userid, external_userid)
+    pub async fn check_single_agree(&self, pairs: &[(&str, &str)]) -> Result<Vec<AgreeInfo>> {
+        let info = pairs
+            .iter()
+            .map(|&(userid, exteranalopenid)| SingleAgreeItem {
+                userid,
+                exteranalopenid,
+            })
+            .collect();
+        let req = SingleAgreeRequest { info };
+        let ret: AgreeResponse = self
+            .post("/cgi-bin/msgaudit/check_single_agree", &req)
+            .await?;
+        Ok(ret.agreeinfo)
+    }
+
+    /// 查询群里的外部联系人是否同意群聊被存档
+    pub async fn check_room_agree(&self, roomid: &str) -> Result<Vec<AgreeInfo>> {
+        let req = RoomIdRequest { roomid };
+        let ret: AgreeResponse = self
+            .post("/cgi-bin/msgaudit/check_room_agree", &req)
+            .await?;
+        Ok(ret.agreeinfo)
+    }
+
+    /// 获取存档中的内部群信息
+    pub async fn get_audit_group_chat(&self, roomid: &str) -> Result<AuditGroupChat> {
+        let req = RoomIdRequest { roomid };
+        self.post("/cgi-bin/msgaudit/groupchat/get", &req).await
+    }
+}
@@ -0,0 +1,710 @@
+#[cfg(feature = "native")]
+use futures::Stream;
+#[cfg(feature = "native")]
+use serde::de::IgnoredAny;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+#[cfg(feature = "native")]
+use crate::client::Client;
+#[cfg(feature = "native")]
+use crate::page::paginate;
+#[cfg(feature = "native")]
+use crate::page::Page;
+#[cfg(feature = "native")]
+use crate::Result;
+
+/// 审批申请, 以成员 `creator_userid` 的身份提交
+///
+/// ```ignore
+/// let req = ApprovalRequest::new("zhangsan", "3Tka1eD6v6JfzhDMqPd3aMkFdxqtJMc2ZRioeFXk")
+///     .use_template_approver()
+///     .content(ApplyContent::text("Text-1569573760849", "报销事由"))
+///     .content(ApplyContent::money("Money-1569573760850", 100.5))
+///     .summary("报销 100.5 元");
+/// let sp_no = client.apply_event(&req).await?;
+/// ```
+#[derive(Debug, Clone, Serialize)]
+pub struct ApprovalRequest {
+    creator_userid: String,
+    template_id: String,
+    use_template_approver: u8, // 1-使用模板中设置的审批流程
+    #[serde(skip_serializing_if = "Option::is_none")]
+    choose_department: Option<u64>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    approver: Vec<ApplyApprover>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    notifyer: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    notify_type: Option<u8>,
+    apply_data: ApplyData,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    summary_list: Vec<SummaryInfo>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct ApplyApprover {
+    attr: u8, // 1-或签, 2-会签
+    userid: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+struct ApplyData {
+    contents: Vec<ApplyContent>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct SummaryInfo {
+    summary_info: Vec<SummaryText>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct SummaryText {
+    text: String,
+    lang: &'static str,
+}
+
+impl ApprovalRequest {
+    pub fn new(creator_userid: impl ToString, template_id: impl ToString) -> Self {
+        ApprovalRequest {
+            creator_userid: creator_userid.to_string(),
+            template_id: template_id.to_string(),
+            use_template_approver: 0,
+            choose_department: None,
+            approver: vec![],
+            notifyer: vec![],
+            notify_type: None,
+            apply_data: ApplyData::default(),
+            summary_list: vec![],
+        }
+    }
+
+    /// 使用模板中设置的审批人, 此时不需要指定 `approver`
+    pub fn use_template_approver(mut self) -> Self {
+        self.use_template_approver = 1;
+        self
+    }
+
+    /// 提交人有多个部门时, 以哪个部门的身份提交
+    pub fn choose_department(mut self, department_id: u64) -> Self {
+        self.choose_department = Some(department_id);
+        self
+    }
+
+    /// 添加一个审批节点, `all` 为 true 时需要所有人同意 (会签), 否则一人同意即可 (或签)
+    pub fn approver(mut self, userids: &[&str], all: bool) -> Self {
+        self.approver.push(ApplyApprover {
+            attr: if all { 2 } else { 1 },
+            userid: userids.iter().map(|s| s.to_string()).collect(),
+        });
+        self
+    }
+
+    /// 抄送人, `notify_type` 1-提单时抄送, 2-单据通过后抄送, 3-提单和单据通过后抄送
+    pub fn notifyer(mut self, userids: &[&str], notify_type: u8) -> Self {
+        self.notifyer = userids.iter().map(|s| s.to_string()).collect();
+        self.notify_type = Some(notify_type);
+        self
+    }
+
+    /// 填写一个控件的值
+    pub fn content(mut self, content: ApplyContent) -> Self {
+        self.apply_data.contents.push(content);
+        self
+    }
+
+    /// 审批消息中的摘要, 最多 3 行
+    pub fn summary(mut self, text: impl ToString) -> Self {
+        self.summary_list.push(SummaryInfo {
+            summary_info: vec![SummaryText {
+                text: text.to_string(),
+                lang: "zh_CN",
+            }],
+        });
+        self
+    }
+}
+
+/// 审批申请中一个控件的值, `id` 为模板中的控件 id, 可以通过 `gettemplatedetail` 查询
+#[derive(Debug, Clone, Serialize)]
+pub struct ApplyContent {
+    control: &'static str,
+    id: String,
+    value: Value,
+}
+
+impl ApplyContent {
+    fn new(control: &'static str, id: impl ToString, value: Value) -> Self {
+        ApplyContent {
+            control,
+            id: id.to_string(),
+            value,
+        }
+    }
+
+    pub fn text(id: impl ToString, text: impl ToString) -> Self {
+        ApplyContent::new("Text", id, json!({ "text": text.to_string() }))
+    }
+
+    pub fn textarea(id: impl ToString, text: impl ToString) -> Self {
+        ApplyContent::new("Textarea", id, json!({ "text": text.to_string() }))
+    }
+
+    pub fn number(id: impl ToString, n: f64) -> Self {
+        ApplyContent::new("Number", id, json!({ "new_number": n.to_string() }))
+    }
+
+    pub fn money(id: impl ToString, amount: f64) -> Self {
+        let amount = format!("{:.2}", amount);
+        ApplyContent::new("Money", id, json!({ "new_money": amount }))
+    }
+
+    /// 日期控件, `with_hour` 为 true 时精确到小时
+    pub fn date(id: impl ToString, timestamp: u64, with_hour: bool) -> Self {
+        let ty = if with_hour { "hour" } else { "day" };
+        let value = json!({ "date": { "type": ty, "s_timestamp": timestamp.to_string() } });
+        ApplyContent::new("Date", id, value)
+    }
+
+    /// 单选或者多选控件, `keys` 为选项的 key, 多于一个时为多选
+    pub fn selector(id: impl ToString, keys: &[&str]) -> Self {
+        let ty = if keys.len() > 1 { "multi" } else { "single" };
+        let options: Vec<_> = keys.iter().map(|k| json!({ "key": k })).collect();
+        let value = json!({ "selector": { "type": ty, "options": options } });
+        ApplyContent::new("Selector", id, value)
+    }
+
+    /// 成员控件
+    pub fn members(id: impl ToString, userids: &[&str]) -> Self {
+        let members: Vec<_> = userids.iter().map(|u| json!({ "userid": u })).collect();
+        ApplyContent::new("Contact", id, json!({ "members": members }))
+    }
+
+    /// 部门控件
+    pub fn departments(id: impl ToString, department_ids: &[u64]) -> Self {
+        let departments: Vec<_> = department_ids
+            .iter()
+            .map(|d| json!({ "openapi_id": d.to_string() }))
+            .collect();
+        ApplyContent::new("Contact", id, json!({ "departments": departments }))
+    }
+
+    /// 附件控件, `media_ids` 为上传的临时素材
+    pub fn files(id: impl ToString, media_ids: &[&str]) -> Self {
+        let files: Vec<_> = media_ids.iter().map(|m| json!({ "file_id": m })).collect();
+        ApplyContent::new("File", id, json!({ "files": files }))
+    }
+
+    /// 明细控件, 每一行为该行各个子控件的值
+    pub fn table(id: impl ToString, rows: Vec<Vec<ApplyContent>>) -> Self {
+        let children: Vec<_> = rows.into_iter().map(|row| json!({ "list": row })).collect();
+        ApplyContent::new("Table", id, json!({ "children": children }))
+    }
+
+    /// 直接指定控件类型和值, 用于没有提供辅助函数的控件
+    pub fn raw(control: &'static str, id: impl ToString, value: Value) -> Self {
+        ApplyContent::new(control, id, value)
+    }
+}
+
+/// 查询审批单号时的过滤条件, 多个条件之间为且的关系
+#[derive(Debug, Clone, Serialize)]
+pub struct ApprovalFilter {
+    key: &'static str,
+    value: String,
+}
+
+impl ApprovalFilter {
+    pub fn template_id(id: impl ToString) -> Self {
+        ApprovalFilter {
+            key: "template_id",
+            value: id.to_string(),
+        }
+    }
+
+    pub fn creator(userid: impl ToString) -> Self {
+        ApprovalFilter {
+            key: "creator",
+            value: userid.to_string(),
+        }
+    }
+
+    pub fn department(department_id: u64) -> Self {
+        ApprovalFilter {
+            key: "department",
+            value: department_id.to_string(),
+        }
+    }
+
+    /// 1-审批中, 2-已通过, 3-已驳回, 4-已撤销, 6-通过后撤销, 7-已删除, 10-已支付
+    pub fn sp_status(status: u8) -> Self {
+        ApprovalFilter {
+            key: "sp_status",
+            value: status.to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct ApprovalInfoRequest<'a> {
+    starttime: u64,
+    endtime: u64,
+    new_cursor: &'a str,
+    size: u32,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    filters: &'a [ApprovalFilter],
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct ApprovalInfoResponse {
+    #[serde(default)]
+    sp_no_list: Vec<String>,
+    #[serde(default)]
+    new_next_cursor: String,
+}
+
+/// 审批单详情
+#[derive(Debug, Clone, Deserialize)]
+pub struct ApprovalDetail {
+    pub sp_no: String,
+    pub sp_name: String,
+    pub sp_status: u8, // 1-审批中, 2-已通过, 3-已驳回, 4-已撤销, 6-通过后撤销, 7-已删除, 10-已支付
+    pub template_id: String,
+    pub apply_time: u64,
+    pub applyer: Applyer,
+    #[serde(default)]
+    pub sp_record: Vec<ApprovalNode>,
+    #[serde(default)]
+    pub notifyer: Vec<UserRef>,
+    #[serde(default)]
+    pub apply_data: ApprovalData,
+    #[serde(default)]
+    pub comments: Vec<ApprovalComment>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Applyer {
+    pub userid: String,
+    #[serde(default)]
+    pub partyid: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UserRef {
+    pub userid: String,
+}
+
+/// 一个审批节点
+#[derive(Debug, Clone, Deserialize)]
+pub struct ApprovalNode {
+    pub sp_status: u8,    // 1-审批中, 2-已同意, 3-已驳回, 4-已转审
+    pub approverattr: u8, // 1-或签, 2-会签
+    #[serde(default)]
+    pub details: Vec<ApprovalNodeDetail>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ApprovalNodeDetail {
+    pub approver: UserRef,
+    #[serde(default)]
+    pub speech: String, // 审批意见
+    pub sp_status: u8, // 1-审批中, 2-已同意, 3-已驳回, 4-已转审
+    #[serde(default)]
+    pub sptime: u64,
+    #[serde(default)]
+    pub media_id: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ApprovalData {
+    #[serde(default)]
+    pub contents: Vec<ApprovalControl>,
+}
+
+/// 审批单中已填写的控件, 不同控件的值结构不同, 以 json 的形式保留
+#[derive(Debug, Clone, Deserialize)]
+pub struct ApprovalControl {
+    pub control: String, // Text, Number, Money, Date, Selector, Contact, File, Table 等
+    pub id: String,
+    #[serde(default)]
+    pub title: Vec<LangText>,
+    #[serde(default)]
+    pub value: Value,
+}
+
+impl ApprovalControl {
+    /// 控件的中文标题
+    pub fn title(&self) -> Option<&str> {
+        self.title
+            .iter()
+            .find(|t| t.lang == "zh_CN")
+            .or_else(|| self.title.first())
+            .map(|t| t.text.as_str())
+    }
+
+    /// 文本, 多行文本控件的值
+    pub fn text(&self) -> Option<&str> {
+        self.value.get("text")?.as_str()
+    }
+
+    /// 数字和金额控件的值
+    pub fn number(&self) -> Option<f64> {
+        let v = self
+            .value
+            .get("new_number")
+            .or_else(|| self.value.get("new_money"))?;
+        v.as_str()?.parse().ok()
+    }
+
+    /// 附件控件中的文件 id, 可以通过 `Client::download_media` 下载
+    pub fn file_ids(&self) -> Vec<&str> {
+        let files = match self.value.get("files").and_then(|f| f.as_array()) {
+            Some(files) => files,
+            None => return vec![],
+        };
+        files
+            .iter()
+            .filter_map(|f| f.get("file_id")?.as_str())
+            .collect()
+    }
+
+    /// 明细控件的每一行
+    pub fn table_rows(&self) -> Vec<Vec<ApprovalControl>> {
+        let children = match self.value.get("children").and_then(|c| c.as_array()) {
+            Some(children) => children,
+            None => return vec![],
+        };
+        children
+            .iter()
+            .filter_map(|row| serde_json::from_value(row.get("list")?.clone()).ok())
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LangText {
+    pub text: String,
+    #[serde(default)]
+    pub lang: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ApprovalComment {
+    #[serde(rename = "commentUserInfo")]
+    pub comment_user_info: UserRef,
+    pub commenttime: u64,
+    #[serde(default)]
+    pub commentcontent: String,
+    #[serde(default)]
+    pub commentid: String,
+    #[serde(default)]
+    pub media_id: Vec<String>,
+}
+
+/// 企业的假期设置
+#[derive(Debug, Clone, Deserialize)]
+pub struct VacationConf {
+    pub id: u64,
+    pub name: String,
+    #[serde(default)]
+    pub time_attr: u8, // 0-以天为单位请假, 1-以小时为单位请假
+    #[serde(default)]
+    pub duration_type: u8, // 0-按自然日计算时长, 1-按工作日计算时长
+    #[serde(default)]
+    pub quota_attr: VacationQuotaAttr,
+    #[serde(default)]
+    pub perday_duration: u64, // 一天的时长, 单位秒
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct VacationQuotaAttr {
+    #[serde(rename = "type")]
+    #[serde(default)]
+    pub ty: u8, // 0-不限额, 1-每年自动发放, 2-按照入职日期发放, 3-手动发放
+    #[serde(default)]
+    pub autoreset_time: u64,
+    #[serde(default)]
+    pub autoreset_duration: u64,
+}
+
+/// 成员一种假期的余额, 时长单位均为秒
+#[derive(Debug, Clone, Deserialize)]
+pub struct VacationQuota {
+    pub id: u64,
+    #[serde(default)]
+    pub vacationname: String,
+    #[serde(default)]
+    pub assignduration: u64,
+    #[serde(default)]
+    pub usedduration: u64,
+    #[serde(default)]
+    pub leftduration: u64,
+    #[serde(default)]
+    pub real_assignduration: u64,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct VacationListResponse<T> {
+    #[serde(default = "Vec::new")]
+    lists: Vec<T>,
+}
+
+/// 汇报规则在一个周期内的统计
+#[derive(Debug, Clone, Deserialize)]
+pub struct JournalStat {
+    pub template_id: String,
+    #[serde(default)]
+    pub template_name: String,
+    #[serde(default)]
+    pub report_type: u8, // 1-日报, 2-周报, 3-月报
+    pub cycle_begin_time: u64,
+    pub cycle_end_time: u64,
+    #[serde(default)]
+    pub stat_begin_time: u64,
+    #[serde(default)]
+    pub stat_end_time: u64,
+    #[serde(default)]
+    pub report_list: Vec<JournalUserReports>, // 已汇报的成员
+    #[serde(default)]
+    pub unreport_list: Vec<JournalUserReports>, // 未汇报的成员
+}
+
+impl JournalStat {
+    /// 已汇报成员的占比
+    pub fn completion_rate(&self) -> f64 {
+        let total = self.report_list.len() + self.unreport_list.len();
+        if total == 0 {
+            return 1.0;
+        }
+        self.report_list.len() as f64 / total as f64
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct JournalUserReports {
+    pub user: UserRef,
+    #[serde(default)]
+    pub itemlist: Vec<JournalItem>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct JournalItem {
+    #[serde(default)]
+    pub journaluuid: String, // 未汇报时为空
+    #[serde(default)]
+    pub reporttime: u64,
+    #[serde(default)]
+    pub flag: u8, // 0-正常, 1-迟交
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct JournalStatRequest<'a> {
+    template_id: &'a str,
+    starttime: u64,
+    endtime: u64,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct JournalStatResponse {
+    #[serde(default)]
+    data: Vec<JournalStat>,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct SetQuotaRequest<'a> {
+    userid: &'a str,
+    vacation_id: u64,
+    leftduration: u64,
+    time_attr: u8,
+    #[serde(skip_serializing_if = "str::is_empty")]
+    remarks: &'a str,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct UseridRequest<'a> {
+    userid: &'a str,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct SpNoRequest<'a> {
+    sp_no: &'a str,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct ApprovalDetailResponse {
+    info: ApprovalDetail,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct ApplyEventResponse {
+    sp_no: String,
+}
+
+/// 审批
+#[cfg(feature = "native")]
+impl Client {
+    /// 提交审批申请, 返回审批单号
+    pub async fn apply_event(&self, req: &ApprovalRequest) -> Result<String> {
+        let ret: ApplyEventResponse = self.post("/cgi-bin/oa/applyevent", req).await?;
+        Ok(ret.sp_no)
+    }
+
+    /// 查询一段时间内提交的审批单号, 时间跨度不超过 31 天, 每页最多 100 个
+    pub async fn get_approval_info(
+        &self,
+        start: u64,
+        end: u64,
+        filters: &[ApprovalFilter],
+        cursor: &str,
+    ) -> Result<Page<String>> {
+        let req = ApprovalInfoRequest {
+            starttime: start,
+            endtime: end,
+            new_cursor: cursor,
+            size: 100,
+            filters,
+        };
+        let ret: ApprovalInfoResponse = self.post("/cgi-bin/oa/getapprovalinfo", &req).await?;
+        Ok(Page::new(ret.sp_no_list, ret.new_next_cursor, false))
+    }
+
+    pub async fn get_approval_detail(&self, sp_no: &str) -> Result<ApprovalDetail> {
+        let req = SpNoRequest { sp_no };
+        let ret: ApprovalDetailResponse = self.post("/cgi-bin/oa/getapprovaldetail", &req).await?;
+        Ok(ret.info)
+    }
+
+    /// 获取企业的假期设置
+    pub async fn get_vacation_corp_conf(&self) -> Result<Vec<VacationConf>> {
+        let ret: VacationListResponse<VacationConf> =
+            self.get("/cgi-bin/oa/vacation/getcorpconf", &()).await?;
+        Ok(ret.lists)
+    }
+
+    /// 获取成员的假期余额
+    pub async fn get_user_vacation_quota(&self, userid: &str) -> Result<Vec<VacationQuota>> {
+        let req = UseridRequest { userid };
+        let ret: VacationListResponse<VacationQuota> = self
+            .post("/cgi-bin/oa/vacation/getuservacationquota", &req)
+            .await?;
+        Ok(ret.lists)
+    }
+
+    /// 修改成员某种假期的余额, `leftduration` 单位为秒, `time_attr` 需要与假期设置一致
+    ///
+    /// `remarks` 为修改原因, 会显示在成员的假期余额记录中, 最多 200 个字符
+    pub async fn set_one_user_quota(
+        &self,
+        userid: &str,
+        vacation_id: u64,
+        leftduration: u64,
+        time_attr: u8,
+        remarks: &str,
+    ) -> Result<()> {
+        let req = SetQuotaRequest {
+            userid,
+            vacation_id,
+            leftduration,
+            time_attr,
+            remarks,
+        };
+        let _: IgnoredAny = self
+            .post("/cgi-bin/oa/vacation/setoneuserquota", &req)
+            .await?;
+        Ok(())
+    }
+
+    /// 获取汇报规则 `template_id` 在一段时间内各个周期的统计, 时间跨度不超过一个月
+    pub async fn get_journal_stat_list(
+        &self,
+        template_id: &str,
+        start: u64,
+        end: u64,
+    ) -> Result<Vec<JournalStat>> {
+        let req = JournalStatRequest {
+            template_id,
+            starttime: start,
+            endtime: end,
+        };
+        let ret: JournalStatResponse = self.post("/cgi-bin/oa/journal/get_stat_list", &req).await?;
+        Ok(ret.data)
+    }
+
+    /// 以 `Stream` 的方式返回一段时间内的所有审批单号, 自动翻页
+    pub fn approval_sp_nos<'a>(
+        &'a self,
+        start: u64,
+        end: u64,
+        filters: &'a [ApprovalFilter],
+    ) -> impl Stream<Item = Result<String>> + 'a {
+        paginate(
+            move |cursor| async move { self.get_approval_info(start, end, filters, &cursor).await },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_approval_control() {
+        let data = r#"{
+            "control": "Table",
+            "id": "Table-1",
+            "title": [{"text": "明细", "lang": "zh_CN"}],
+            "value": {
+                "children": [{
+                    "list": [
+                        {"control": "Money", "id": "Money-1", "value": {"new_money": "12.50"}},
+                        {"control": "File", "id": "File-1", "value": {"files": [{"file_id": "f1"}]}}
+                    ]
+                }]
+            }
+        }"#;
+        let c: ApprovalControl = serde_json::from_str(data).unwrap();
+        assert_eq!(c.title(), Some("明细"));
+        let rows = c.table_rows();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0][0].number(), Some(12.5));
+        assert_eq!(rows[0][1].file_ids(), vec!["f1"]);
+        assert_eq!(rows[0][1].text(), None);
+    }
+
+    #[test]
+    fn test_serialize_apply_event() {
+        let req = ApprovalRequest::new("zhangsan", "tpl")
+            .approver(&["lisi", "wangwu"], true)
+            .content(ApplyContent::money("Money-1", 100.5))
+            .content(ApplyContent::selector("Selector-1", &["option-1"]))
+            .content(ApplyContent::table(
+                "Table-1",
+                vec![vec![ApplyContent::text("Text-1", "a")]],
+            ))
+            .summary("报销");
+        let v = serde_json::to_value(&req).unwrap();
+        assert_eq!(v["approver"][0]["attr"], 2);
+        assert_eq!(v["use_template_approver"], 0);
+        assert!(v.get("notifyer").is_none());
+
+        let contents = &v["apply_data"]["contents"];
+        assert_eq!(contents[0]["control"], "Money");
+        assert_eq!(contents[0]["value"]["new_money"], "100.50");
+        assert_eq!(contents[1]["value"]["selector"]["type"], "single");
+        assert_eq!(
+            contents[2]["value"]["children"][0]["list"][0]["value"]["text"],
+            "a"
+        );
+        assert_eq!(v["summary_list"][0]["summary_info"][0]["lang"], "zh_CN");
+    }
+}
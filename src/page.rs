@@ -0,0 +1,90 @@
+use std::future::Future;
+
+use futures::stream::{self, Stream, StreamExt};
+
+use crate::Result;
+
+/// 分页接口返回的一页数据
+#[derive(Debug, Clone)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<String>, // 最后一页为 None
+    pub total: Option<u64>,          // 部分接口会返回总数
+}
+
+impl<T> Page<T> {
+    pub fn has_more(&self) -> bool {
+        self.next_cursor.is_some()
+    }
+
+    // a blank cursor also means there are no more pages
+    #[cfg_attr(not(feature = "native"), allow(dead_code))]
+    pub(crate) fn new(items: Vec<T>, next_cursor: String, is_last: bool) -> Self {
+        let next_cursor = if is_last || next_cursor.is_empty() {
+            None
+        } else {
+            Some(next_cursor)
+        };
+        Page {
+            items,
+            next_cursor,
+            total: None,
+        }
+    }
+}
+
+/// 将分页接口转换为 `Stream`, `f` 的参数为游标, 第一页为空字符串, 出错后结束
+///
+/// ```ignore
+/// let customers: Vec<_> = paginate(|cursor| async move {
+///     client.get_unassigned_list(&cursor, 1000).await
+/// })
+/// .try_collect()
+/// .await?;
+/// ```
+pub fn paginate<T, F, Fut>(f: F) -> impl Stream<Item = Result<T>>
+where
+    F: FnMut(String) -> Fut,
+    Fut: Future<Output = Result<Page<T>>>,
+{
+    stream::unfold((Some(String::new()), f), |(cursor, mut f)| async move {
+        let page = match f(cursor?).await {
+            Ok(page) => page,
+            Err(e) => return Some((vec![Err(e)], (None, f))),
+        };
+        let items = page.items.into_iter().map(Ok).collect();
+        Some((items, (page.next_cursor, f)))
+    })
+    .flat_map(stream::iter)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+    use futures::TryStreamExt;
+
+    #[test]
+    fn test_paginate() {
+        let pages = [
+            Page::new(vec![1, 2], "a".into(), false),
+            Page::new(vec![], "b".into(), false),
+            Page::new(vec![3], "".into(), false),
+        ];
+        let s = paginate(|cursor| {
+            let page = match &*cursor {
+                "" => pages[0].clone(),
+                "a" => pages[1].clone(),
+                "b" => pages[2].clone(),
+                _ => unreachable!(),
+            };
+            async move { Ok(page) }
+        });
+        let items: Vec<i32> = block_on(s.try_collect()).unwrap();
+        assert_eq!(items, vec![1, 2, 3]);
+
+        let s = paginate(|_| async { Err::<Page<i32>, _>(crate::Error::PollTimeout) });
+        let items: Vec<_> = block_on(s.collect());
+        assert_eq!(items.len(), 1);
+    }
+}
@@ -0,0 +1,180 @@
+use std::future::Future;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::batch::BatchJobResult;
+use crate::client::Client;
+use crate::export::ExportResult;
+use crate::externalcontact::MomentTaskResult;
+use crate::media::UploadByUrlResult;
+use crate::{Error, Result};
+
+const MIN_POLL_INTERVAL: Duration = Duration::from_secs(1);
+const MAX_POLL_INTERVAL: Duration = Duration::from_secs(30);
+const MAX_RATE_LIMIT_RETRIES: u32 = 5;
+
+// call `f` again after the hinted delay when it is rate limited, give up after a few times
+pub(crate) async fn retry_rate_limited<T, F, Fut>(mut f: F) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut retries = 0;
+    loop {
+        match f().await {
+            Err(Error::RateLimited {
+                retry_after_hint, ..
+            }) if retries < MAX_RATE_LIMIT_RETRIES => {
+                retries += 1;
+                tokio::time::sleep(retry_after_hint).await;
+            }
+            ret => return ret,
+        }
+    }
+}
+
+// call `f` until `is_done` returns true, the interval between two calls doubles each time
+pub(crate) async fn poll_until<T, F, Fut>(mut f: F, is_done: impl Fn(&T) -> bool) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut interval = MIN_POLL_INTERVAL;
+    loop {
+        let ret = retry_rate_limited(&mut f).await?;
+        if is_done(&ret) {
+            return Ok(ret);
+        }
+        tokio::time::sleep(interval).await;
+        interval = (interval * 2).min(MAX_POLL_INTERVAL);
+    }
+}
+
+/// 提交后需要轮询结果的异步任务
+#[async_trait]
+pub trait Job: Send + Sync + 'static {
+    type Output: Send + 'static;
+
+    /// 查询一次任务结果, 任务未完成时返回 None
+    async fn poll(&self, client: &Client) -> Result<Option<Self::Output>>;
+}
+
+/// 通过 channel 通知的任务结果
+pub struct JobResult<J: Job> {
+    pub job: J,
+    pub result: Result<J::Output>,
+}
+
+/// 在后台跟踪已提交的任务, 任务完成后调用回调或者通过 channel 通知
+pub struct Poller {
+    client: Client,
+    timeout: Option<Duration>,
+}
+
+impl Poller {
+    pub fn new(client: Client) -> Self {
+        Poller {
+            client,
+            timeout: None,
+        }
+    }
+
+    /// 单个任务的最长跟踪时间, 超时后结果为 `Error::PollTimeout`
+    pub fn timeout(mut self, d: Duration) -> Self {
+        self.timeout = Some(d);
+        self
+    }
+
+    pub fn track<J, F>(&self, job: J, callback: F)
+    where
+        J: Job,
+        F: FnOnce(Result<J::Output>) + Send + 'static,
+    {
+        let client = self.client.clone();
+        let timeout = self.timeout;
+        tokio::spawn(async move {
+            let ret = wait(&client, &job, timeout).await;
+            callback(ret);
+        });
+    }
+
+    pub fn track_to<J: Job>(&self, job: J, sender: UnboundedSender<JobResult<J>>) {
+        let client = self.client.clone();
+        let timeout = self.timeout;
+        tokio::spawn(async move {
+            let result = wait(&client, &job, timeout).await;
+            // the receiver may be dropped, nothing to do then
+            let _ = sender.send(JobResult { job, result });
+        });
+    }
+}
+
+// poll `job` until it is finished, fails with `Error::PollTimeout` after `timeout`
+pub(crate) async fn wait<J: Job>(
+    client: &Client,
+    job: &J,
+    timeout: Option<Duration>,
+) -> Result<J::Output> {
+    let f = poll_until(|| job.poll(client), Option::is_some);
+    let ret = match timeout {
+        Some(d) => tokio::time::timeout(d, f)
+            .await
+            .map_err(|_| Error::PollTimeout)??,
+        None => f.await?,
+    };
+    Ok(ret.unwrap())
+}
+
+/// 异步批量任务, 参见 `Client::sync_users` 等
+pub struct BatchJob(pub String);
+
+#[async_trait]
+impl Job for BatchJob {
+    type Output = BatchJobResult;
+
+    async fn poll(&self, client: &Client) -> Result<Option<BatchJobResult>> {
+        let ret = client.get_batch_result(&self.0).await?;
+        Ok(Some(ret).filter(BatchJobResult::is_finished))
+    }
+}
+
+/// 通过 url 异步上传素材的任务, 参见 `Client::upload_media_by_url`
+pub struct UploadByUrlJob(pub String);
+
+#[async_trait]
+impl Job for UploadByUrlJob {
+    type Output = UploadByUrlResult;
+
+    async fn poll(&self, client: &Client) -> Result<Option<UploadByUrlResult>> {
+        let ret = client.get_upload_by_url_result(&self.0).await?;
+        Ok(Some(ret).filter(UploadByUrlResult::is_finished))
+    }
+}
+
+/// 创建客户朋友圈的任务, 参见 `Client::add_moment_task`
+pub struct MomentJob(pub String);
+
+#[async_trait]
+impl Job for MomentJob {
+    type Output = MomentTaskResult;
+
+    async fn poll(&self, client: &Client) -> Result<Option<MomentTaskResult>> {
+        let ret = client.get_moment_task_result(&self.0).await?;
+        Ok(Some(ret).filter(MomentTaskResult::is_finished))
+    }
+}
+
+/// 异步导出任务, 参见 `Client::export_user` 等
+pub struct ExportJob(pub String);
+
+#[async_trait]
+impl Job for ExportJob {
+    type Output = ExportResult;
+
+    async fn poll(&self, client: &Client) -> Result<Option<ExportResult>> {
+        let ret = client.get_export_result(&self.0).await?;
+        Ok(Some(ret).filter(ExportResult::is_finished))
+    }
+}
@@ -0,0 +1,186 @@
+use std::sync::mpsc;
+use std::sync::{Arc, RwLock};
+
+use log::info;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::client::{parse_response, Health, RefreshedToken, TokenSource, WX_URL};
+use crate::{Error, Result};
+
+/// 第三方服务商的 Client, 和 `Client` 一样在后台自动刷新 provider_access_token 和 suite_access_token
+///
+/// 可以廉价地 clone, 所有副本共享同一组 token
+#[derive(Clone)]
+pub struct ProviderClient {
+    corp_id: String,
+    provider_token: RefreshedToken,
+    suite: Option<Suite>,
+    http_client: reqwest::Client,
+}
+
+#[derive(Clone)]
+struct Suite {
+    suite_id: String,
+    suite_ticket: Arc<RwLock<String>>,
+    token: RefreshedToken,
+}
+
+/// 预授权码, 用于构造第三方应用的授权链接
+#[derive(Debug, Clone, Deserialize)]
+pub struct PreAuthCode {
+    pub pre_auth_code: String,
+    pub expires_in: u64,
+}
+
+/// 企业授权第三方应用后获得的永久授权码
+#[derive(Debug, Clone, Deserialize)]
+pub struct PermanentCode {
+    pub permanent_code: String,
+    #[serde(default)]
+    pub access_token: String, // 授权企业的 access token
+    #[serde(default)]
+    pub expires_in: u64,
+    pub auth_corp_info: AuthCorpInfo,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AuthCorpInfo {
+    pub corpid: String,
+    #[serde(default)]
+    pub corp_name: String,
+    #[serde(default)]
+    pub corp_type: String, // verified-已认证, unverified-未认证
+    #[serde(default)]
+    pub corp_full_name: String,
+}
+
+#[derive(Debug, Serialize)]
+struct PermanentCodeRequest<'a> {
+    auth_code: &'a str,
+}
+
+impl ProviderClient {
+    /// 构造时会同步获取一次 provider_access_token, 获取失败时返回错误
+    pub fn new(corp_id: &str, provider_secret: &str) -> Result<Self> {
+        let source = TokenSource::Provider {
+            corpid: corp_id.to_string(),
+            provider_secret: provider_secret.to_string(),
+        };
+        let (tx, rx) = mpsc::channel();
+        let provider_token = RefreshedToken::start(source, Some(tx));
+        rx.recv().unwrap()?;
+
+        info!("construct ProviderClient success");
+        Ok(ProviderClient {
+            corp_id: corp_id.to_string(),
+            provider_token,
+            suite: None,
+            http_client: reqwest::Client::new(),
+        })
+    }
+
+    /// 管理第三方应用 `suite_id` 的 suite_access_token
+    ///
+    /// suite_access_token 需要企业微信每十分钟推送一次的 suite_ticket, 在收到第一个 suite_ticket
+    /// 之前所有使用它的调用都返回 `Error::TokenUnavailable`
+    pub fn with_suite(self, suite_id: &str, suite_secret: &str) -> Self {
+        let suite_ticket = Arc::new(RwLock::new("".to_string()));
+        let source = TokenSource::Suite {
+            suite_id: suite_id.to_string(),
+            suite_secret: suite_secret.to_string(),
+            suite_ticket: suite_ticket.clone(),
+        };
+        let suite = Suite {
+            suite_id: suite_id.to_string(),
+            suite_ticket,
+            token: RefreshedToken::start(source, None),
+        };
+        ProviderClient {
+            suite: Some(suite),
+            ..self
+        }
+    }
+
+    /// 保存回调推送的 suite_ticket, 还没有可用的 suite_access_token 时立即刷新
+    ///
+    /// 没有调用 `with_suite` 时返回 `Error::TokenUnavailable`
+    pub fn set_suite_ticket(&self, suite_ticket: &str) -> Result<()> {
+        let suite = self.suite()?;
+        *suite.suite_ticket.write().unwrap() = suite_ticket.to_string();
+        if suite.token.get().is_err() {
+            suite.token.wake();
+        }
+        Ok(())
+    }
+
+    pub fn corp_id(&self) -> &str {
+        &self.corp_id
+    }
+
+    pub fn suite_id(&self) -> Option<&str> {
+        self.suite.as_ref().map(|s| &*s.suite_id)
+    }
+
+    pub fn provider_access_token(&self) -> Result<String> {
+        self.provider_token.get()
+    }
+
+    pub fn suite_access_token(&self) -> Result<String> {
+        self.suite()?.token.get()
+    }
+
+    pub fn provider_health(&self) -> Health {
+        self.provider_token.health()
+    }
+
+    /// 没有调用 `with_suite` 时返回 None
+    pub fn suite_health(&self) -> Option<Health> {
+        self.suite.as_ref().map(|s| s.token.health())
+    }
+
+    fn suite(&self) -> Result<&Suite> {
+        self.suite.as_ref().ok_or(Error::TokenUnavailable)
+    }
+
+    async fn suite_get<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        let url = format!("{}{}", WX_URL, path);
+        let token = self.suite_access_token()?;
+        let req = self
+            .http_client
+            .get(&url)
+            .query(&[("suite_access_token", token)]);
+        let bytes = req.send().await?.bytes().await?;
+        parse_response(&bytes)
+    }
+
+    async fn suite_post<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &(impl Serialize + ?Sized),
+    ) -> Result<T> {
+        let url = format!("{}{}", WX_URL, path);
+        let token = self.suite_access_token()?;
+        let req = self
+            .http_client
+            .post(&url)
+            .query(&[("suite_access_token", token)])
+            .json(body);
+        let bytes = req.send().await?.bytes().await?;
+        parse_response(&bytes)
+    }
+}
+
+/// 第三方应用授权
+impl ProviderClient {
+    pub async fn get_pre_auth_code(&self) -> Result<PreAuthCode> {
+        self.suite_get("/cgi-bin/service/get_pre_auth_code").await
+    }
+
+    /// 根据授权成功回调中的 `auth_code` 获取企业的永久授权码
+    pub async fn get_permanent_code(&self, auth_code: &str) -> Result<PermanentCode> {
+        let req = PermanentCodeRequest { auth_code };
+        self.suite_post("/cgi-bin/service/get_permanent_code", &req)
+            .await
+    }
+}
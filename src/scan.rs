@@ -0,0 +1,18 @@
+use async_trait::async_trait;
+
+use crate::media::MediaFile;
+
+/// 扫描结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScanDecision {
+    Allow,
+    Quarantine(String), // 隔离, 携带原因, 文件不会交给业务逻辑
+}
+
+/// 下载收到的文件和图片后, 交给业务逻辑之前的扫描, 比如接入 ClamAV 或者云端的病毒扫描
+///
+/// 通过 `Client::with_media_scanner` 设置后, `download_media` 会先扫描文件
+#[async_trait]
+pub trait MediaScanner: Send + Sync + 'static {
+    async fn scan(&self, media_id: &str, file: &MediaFile) -> ScanDecision;
+}
@@ -0,0 +1,209 @@
+#[cfg(feature = "native")]
+use serde::de::IgnoredAny;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "native")]
+use crate::client::Client;
+#[cfg(feature = "native")]
+use crate::page::Page;
+#[cfg(feature = "native")]
+use crate::{Error, Result};
+
+/// 日程, 创建和修改时使用, 也是读取时的返回
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Schedule {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schedule_id: Option<String>, // 创建时不指定, 修改时必须指定
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub organizer: Option<String>, // 只能在创建时指定
+    pub start_time: u64,
+    pub end_time: u64,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub attendees: Vec<Attendee>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reminders: Option<Reminders>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cal_id: Option<String>, // 不指定时为组织者的默认日历
+    #[serde(default)]
+    #[serde(skip_serializing)]
+    pub status: u8, // 0-正常, 1-已取消, 只在读取时返回
+}
+
+impl Schedule {
+    pub fn new(organizer: impl ToString, start_time: u64, end_time: u64) -> Self {
+        Schedule {
+            organizer: Some(organizer.to_string()),
+            start_time,
+            end_time,
+            ..Default::default()
+        }
+    }
+
+    pub fn attendee(mut self, userid: impl ToString) -> Self {
+        self.attendees.push(Attendee {
+            userid: userid.to_string(),
+            response_status: 0,
+        });
+        self
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Attendee {
+    pub userid: String,
+    #[serde(default)]
+    #[serde(skip_serializing)]
+    pub response_status: u8, // 0-未处理, 1-接受, 2-暂定, 3-拒绝, 只在读取时返回
+}
+
+/// 日程的提醒和重复规则
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Reminders {
+    #[serde(default)]
+    pub is_remind: u8,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remind_before_event_secs: Option<u64>, // 0, 300, 900, 3600, 86400 等
+    #[serde(default)]
+    pub is_repeat: u8,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repeat_type: Option<u8>, // 0-每日, 1-每周, 2-每月, 5-每年, 7-工作日
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repeat_until: Option<u64>, // 不指定时一直重复
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_custom_repeat: Option<u8>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repeat_interval: Option<u32>, // 自定义重复时每隔几个周期重复一次
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub repeat_day_of_week: Vec<u8>, // 1 到 7
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub repeat_day_of_month: Vec<u8>, // 1 到 31
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<i8>, // 比如 8 表示东八区
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct ScheduleRequest<'a> {
+    schedule: &'a Schedule,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    agentid: Option<u64>,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct AddScheduleResponse {
+    schedule_id: String,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct GetScheduleRequest<'a> {
+    schedule_id_list: &'a [&'a str],
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct ScheduleListResponse {
+    #[serde(default)]
+    schedule_list: Vec<Schedule>,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct ScheduleIdRequest<'a> {
+    schedule_id: &'a str,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct ByCalendarRequest<'a> {
+    cal_id: &'a str,
+    offset: u32,
+    limit: u32,
+}
+
+#[cfg(feature = "native")]
+const BY_CALENDAR_LIMIT: u32 = 1000;
+
+/// 日程
+#[cfg(feature = "native")]
+impl Client {
+    /// 创建日程, 返回日程 id, 指定 `agentid` 时由该应用发送日程通知
+    pub async fn add_schedule(&self, schedule: &Schedule, agentid: Option<u64>) -> Result<String> {
+        let req = ScheduleRequest { schedule, agentid };
+        let ret: AddScheduleResponse = self.post("/cgi-bin/oa/schedule/add", &req).await?;
+        Ok(ret.schedule_id)
+    }
+
+    /// 修改日程, `schedule.schedule_id` 必须指定, 会覆盖参与者等字段
+    pub async fn update_schedule(&self, schedule: &Schedule) -> Result<()> {
+        let req = ScheduleRequest {
+            schedule,
+            agentid: None,
+        };
+        let _: IgnoredAny = self.post("/cgi-bin/oa/schedule/update", &req).await?;
+        Ok(())
+    }
+
+    /// 批量获取日程详情, 每次最多 1000 个
+    pub async fn get_schedules(&self, schedule_id_list: &[&str]) -> Result<Vec<Schedule>> {
+        let req = GetScheduleRequest { schedule_id_list };
+        let ret: ScheduleListResponse = self.post("/cgi-bin/oa/schedule/get", &req).await?;
+        Ok(ret.schedule_list)
+    }
+
+    pub async fn del_schedule(&self, schedule_id: &str) -> Result<()> {
+        let req = ScheduleIdRequest { schedule_id };
+        let _: IgnoredAny = self.post("/cgi-bin/oa/schedule/del", &req).await?;
+        Ok(())
+    }
+
+    /// 获取日历 `cal_id` 下的日程, 每页最多 1000 个
+    ///
+    /// 接口以 offset 翻页, 这里将 offset 作为游标, 第一页传空字符串, 可以配合 `page::paginate` 使用
+    pub async fn get_schedules_by_calendar(
+        &self,
+        cal_id: &str,
+        cursor: &str,
+    ) -> Result<Page<Schedule>> {
+        let offset = if cursor.is_empty() {
+            0
+        } else {
+            let e = |_| Error::InvalidResponse(format!("invalid cursor: {}", cursor));
+            cursor.parse().map_err(e)?
+        };
+        let req = ByCalendarRequest {
+            cal_id,
+            offset,
+            limit: BY_CALENDAR_LIMIT,
+        };
+        let ret: ScheduleListResponse = self
+            .post("/cgi-bin/oa/schedule/get_by_calendar", &req)
+            .await?;
+        let is_last = (ret.schedule_list.len() as u32) < BY_CALENDAR_LIMIT;
+        let next = offset + ret.schedule_list.len() as u32;
+        Ok(Page::new(ret.schedule_list, next.to_string(), is_last))
+    }
+}
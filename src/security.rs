@@ -0,0 +1,353 @@
+#[cfg(feature = "native")]
+use serde::de::IgnoredAny;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "native")]
+use crate::client::Client;
+#[cfg(feature = "native")]
+use crate::page::Page;
+#[cfg(feature = "native")]
+use crate::Result;
+
+/// 设备类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceType {
+    Corp,     // 企业设备
+    Personal, // 个人设备
+    Trusted,  // 可信企业设备
+}
+
+impl DeviceType {
+    #[cfg(feature = "native")]
+    fn code(self) -> u8 {
+        match self {
+            DeviceType::Corp => 1,
+            DeviceType::Personal => 2,
+            DeviceType::Trusted => 3,
+        }
+    }
+}
+
+/// 导入的可信设备, 需要指定 `system` 和至少一个硬件标识
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TrustDevice {
+    pub system: String, // Windows 或 Mac
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub mac_addr: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub motherboard_uuid: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub harddisk_uuid: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub domain: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pc_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seq_no: Option<String>, // Mac 的序列号
+}
+
+/// 导入结果, `device_index` 从 1 开始对应导入时的顺序
+#[derive(Debug, Clone, Deserialize)]
+pub struct TrustDeviceImportResult {
+    pub device_index: u32,
+    #[serde(default)]
+    pub device_code: String,
+    pub status: u8, // 1-导入成功, 2-导入失败, 3-设备已存在
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DeviceInfo {
+    pub device_code: String,
+    #[serde(default)]
+    pub system: String,
+    #[serde(default)]
+    pub mac_addr: Vec<String>,
+    #[serde(default)]
+    pub motherboard_uuid: String,
+    #[serde(default)]
+    pub harddisk_uuid: Vec<String>,
+    #[serde(default)]
+    pub domain: String,
+    #[serde(default)]
+    pub pc_name: String,
+    #[serde(default)]
+    pub seq_no: String,
+    #[serde(default)]
+    pub last_login_time: u64,
+    #[serde(default)]
+    pub last_login_userid: String,
+    #[serde(default)]
+    pub confirm_timestamp: u64,
+    #[serde(default)]
+    pub confirm_userid: String,
+    #[serde(default)]
+    pub approved_userid: String,
+    #[serde(default)]
+    pub source: u8, // 1-成员确认, 2-管理员导入, 3-成员自主申报
+    #[serde(default)]
+    pub status: u8, // 1-已导入未登录, 2-待邀请, 3-待管理员确认, 4-未确认, 5-已确认, 6-已驳回
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ApproveDeviceResult {
+    #[serde(default)]
+    pub success_list: Vec<String>,
+    #[serde(default)]
+    pub fail_list: Vec<String>,
+}
+
+/// 文件操作类型, 未知的类型保存在 `Other` 中
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "u32", into = "u32")]
+pub enum FileOperType {
+    Download,
+    Export,
+    Upload,
+    Delete,
+    Share,
+    Forward, // 转发到企业微信
+    Other(u32),
+}
+
+impl From<u32> for FileOperType {
+    fn from(v: u32) -> Self {
+        match v {
+            101 => FileOperType::Download,
+            102 => FileOperType::Export,
+            103 => FileOperType::Upload,
+            104 => FileOperType::Delete,
+            105 => FileOperType::Share,
+            106 => FileOperType::Forward,
+            v => FileOperType::Other(v),
+        }
+    }
+}
+
+impl From<FileOperType> for u32 {
+    fn from(ty: FileOperType) -> Self {
+        match ty {
+            FileOperType::Download => 101,
+            FileOperType::Export => 102,
+            FileOperType::Upload => 103,
+            FileOperType::Delete => 104,
+            FileOperType::Share => 105,
+            FileOperType::Forward => 106,
+            FileOperType::Other(v) => v,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct FileOperation {
+    #[serde(rename = "type")]
+    pub ty: FileOperType,
+    pub source: u32, // 文件来源, 1-聊天, 2-微盘, 3-邮件, 4-文档, 5-日程...
+}
+
+/// 文件操作记录, 外部联系人操作时 `userid` 为空
+#[derive(Debug, Clone, Deserialize)]
+pub struct FileOperRecord {
+    pub time: u64,
+    #[serde(default)]
+    pub userid: String,
+    #[serde(default)]
+    pub external_user: Option<FileOperExternalUser>,
+    pub operation: FileOperation,
+    #[serde(default)]
+    pub file_info: String, // 文件名
+    #[serde(default)]
+    pub file_md5: String,
+    #[serde(default)]
+    pub file_size: u64,
+    #[serde(default)]
+    pub applicant_name: String, // 发送到第三方应用时的应用名
+    #[serde(default)]
+    pub device_type: u8, // 1-企业可信设备, 2-个人设备
+    #[serde(default)]
+    pub device_code: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FileOperExternalUser {
+    #[serde(rename = "type")]
+    pub ty: u8, // 1-微信用户, 2-企业微信用户
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub corp_name: String,
+}
+
+/// 文件操作记录的过滤条件, 时间跨度不超过 14 天
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FileOperFilter {
+    pub start_time: u64,
+    pub end_time: u64,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub userid_list: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operation: Option<FileOperation>,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct FileOperRequest<'a> {
+    #[serde(flatten)]
+    filter: &'a FileOperFilter,
+    cursor: &'a str,
+    limit: u32,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct FileOperResponse {
+    #[serde(default)]
+    has_more: bool,
+    #[serde(default)]
+    next_cursor: String,
+    #[serde(default)]
+    record_list: Vec<FileOperRecord>,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct ImportRequest<'a> {
+    #[serde(rename = "type")]
+    ty: u8,
+    device_list: &'a [TrustDevice],
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct ImportResponse {
+    #[serde(default)]
+    result: Vec<TrustDeviceImportResult>,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct ListDeviceRequest<'a> {
+    start_time: u64,
+    end_time: u64,
+    #[serde(rename = "type")]
+    ty: u8,
+    cursor: &'a str,
+    limit: u32,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct ListDeviceResponse {
+    #[serde(default)]
+    device_list: Vec<DeviceInfo>,
+    #[serde(default)]
+    next_cursor: String,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct DeviceCodeRequest<'a> {
+    #[serde(rename = "type")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ty: Option<u8>,
+    device_code_list: &'a [&'a str],
+}
+
+/// 可信设备
+#[cfg(feature = "native")]
+impl Client {
+    /// 导入可信设备, 单次最多导入 100 个
+    pub async fn import_trust_device(
+        &self,
+        ty: DeviceType,
+        devices: &[TrustDevice],
+    ) -> Result<Vec<TrustDeviceImportResult>> {
+        let req = ImportRequest {
+            ty: ty.code(),
+            device_list: devices,
+        };
+        let ret: ImportResponse = self
+            .post("/cgi-bin/security/trustdevice/import", &req)
+            .await?;
+        Ok(ret.result)
+    }
+
+    /// 获取一段时间内 (按确认时间) 的设备, 时间跨度不超过 30 天, 每页最多 100 个
+    pub async fn list_trust_device(
+        &self,
+        ty: DeviceType,
+        start_time: u64,
+        end_time: u64,
+        cursor: &str,
+    ) -> Result<Page<DeviceInfo>> {
+        let req = ListDeviceRequest {
+            start_time,
+            end_time,
+            ty: ty.code(),
+            cursor,
+            limit: 100,
+        };
+        let ret: ListDeviceResponse = self
+            .post("/cgi-bin/security/trustdevice/list", &req)
+            .await?;
+        Ok(Page::new(ret.device_list, ret.next_cursor, false))
+    }
+
+    pub async fn delete_trust_device(&self, ty: DeviceType, device_codes: &[&str]) -> Result<()> {
+        let req = DeviceCodeRequest {
+            ty: Some(ty.code()),
+            device_code_list: device_codes,
+        };
+        let _: IgnoredAny = self
+            .post("/cgi-bin/security/trustdevice/delete", &req)
+            .await?;
+        Ok(())
+    }
+
+    /// 确认成员申报的设备为可信设备
+    pub async fn approve_trust_device(&self, device_codes: &[&str]) -> Result<ApproveDeviceResult> {
+        let req = DeviceCodeRequest {
+            ty: None,
+            device_code_list: device_codes,
+        };
+        self.post("/cgi-bin/security/trustdevice/approve", &req)
+            .await
+    }
+}
+
+/// 文件防泄漏
+#[cfg(feature = "native")]
+impl Client {
+    /// 获取成员的文件操作记录, 每页最多 1000 条, 第一页 `cursor` 传空字符串
+    pub async fn get_file_oper_record(
+        &self,
+        filter: &FileOperFilter,
+        cursor: &str,
+    ) -> Result<Page<FileOperRecord>> {
+        let req = FileOperRequest {
+            filter,
+            cursor,
+            limit: 1000,
+        };
+        let ret: FileOperResponse = self
+            .post("/cgi-bin/security/get_file_oper_record", &req)
+            .await?;
+        Ok(Page::new(ret.record_list, ret.next_cursor, !ret.has_more))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_oper_type() {
+        let data = r#"{"time": 1, "userid": "zhangsan", "operation": {"type": 101, "source": 2}}"#;
+        let record: FileOperRecord = serde_json::from_str(data).unwrap();
+        assert_eq!(record.operation.ty, FileOperType::Download);
+
+        let op: FileOperation = serde_json::from_str(r#"{"type": 999, "source": 1}"#).unwrap();
+        assert_eq!(op.ty, FileOperType::Other(999));
+        let value = serde_json::to_value(op).unwrap();
+        assert_eq!(value["type"], 999);
+    }
+}
@@ -0,0 +1,302 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+
+use super::{ApprovalInfo, Event, RecvMessage, RecvMessageType};
+
+// number of finished approvals remembered to drop their late or duplicate events
+const MAX_FINISHED: usize = 1024;
+// number of approvals tracked since they were first seen unfinished, abandoned ones are dropped
+const MAX_PENDING: usize = 4096;
+
+/// 审批单的状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApprovalState {
+    Submitted,
+    Approving(usize), // 当前所在审批节点的下标
+    Approved,
+    Rejected,
+    Withdrawn, // 撤销, 通过后撤销或者已删除
+}
+
+impl ApprovalState {
+    /// 根据审批状态通知事件计算状态, 无法识别的状态返回 None
+    pub fn from_info(info: &ApprovalInfo) -> Option<ApprovalState> {
+        let state = match info.sp_status {
+            1 if info.statu_change_event == 1 => ApprovalState::Submitted,
+            1 => {
+                let node = info
+                    .sp_records
+                    .iter()
+                    .position(|r| r.sp_status == 1)
+                    .unwrap_or(0);
+                ApprovalState::Approving(node)
+            }
+            2 | 10 => ApprovalState::Approved,
+            3 => ApprovalState::Rejected,
+            4 | 6 | 7 => ApprovalState::Withdrawn,
+            _ => return None,
+        };
+        Some(state)
+    }
+
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            ApprovalState::Approved | ApprovalState::Rejected | ApprovalState::Withdrawn
+        )
+    }
+
+    // the order of the states, an approval only moves forward. Submitted is the
+    // same as being at the first node, and only Approved can still be withdrawn
+    fn order(&self) -> (u8, usize) {
+        match self {
+            ApprovalState::Submitted => (0, 0),
+            ApprovalState::Approving(node) => (0, *node),
+            ApprovalState::Approved | ApprovalState::Rejected => (1, 0),
+            ApprovalState::Withdrawn => (2, 0),
+        }
+    }
+
+    fn can_move_to(&self, to: ApprovalState) -> bool {
+        *self != ApprovalState::Rejected && self.order() < to.order()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Transition {
+    pub sp_no: String,
+    pub from: Option<ApprovalState>, // 第一次收到该审批单的事件时为 None
+    pub to: ApprovalState,
+}
+
+#[async_trait]
+pub trait ApprovalHandler: Send + Sync + 'static {
+    async fn on_transition(&self, transition: &Transition, info: &ApprovalInfo);
+}
+
+/// 将 `sys_approval_change` 事件转换为审批单的状态迁移
+///
+/// 状态不变的重复事件和迟到的旧事件会被忽略, 已结束的审批单只保留最近的 1024 个,
+/// 未结束的审批单只跟踪最近开始的 4096 个
+pub struct ApprovalMachine<H: ApprovalHandler> {
+    handler: H,
+    states: Mutex<States>,
+}
+
+#[derive(Default)]
+struct States {
+    states: HashMap<String, ApprovalState>, // sp_no -> state
+    finished: VecDeque<String>,             // terminal sp_no, oldest first
+    pending: VecDeque<String>,              // sp_no first seen unfinished, oldest first
+}
+
+impl<H: ApprovalHandler> ApprovalMachine<H> {
+    pub fn new(handler: H) -> Self {
+        ApprovalMachine {
+            handler,
+            states: Mutex::new(States::default()),
+        }
+    }
+
+    /// 处理收到的消息, 如果产生了状态迁移则调用回调并返回该迁移
+    pub async fn handle(&self, msg: &RecvMessage) -> Option<Transition> {
+        let info = match &msg.msg_ty {
+            RecvMessageType::Event(Event::ApprovalChange(info)) => info,
+            _ => return None,
+        };
+        let transition = self.advance(info)?;
+        self.handler.on_transition(&transition, info).await;
+        Some(transition)
+    }
+
+    pub fn state(&self, sp_no: &str) -> Option<ApprovalState> {
+        self.states.lock().unwrap().states.get(sp_no).copied()
+    }
+
+    /// 不再跟踪该审批单, 比如审批单到达终态并且处理完成后
+    pub fn forget(&self, sp_no: &str) {
+        self.states.lock().unwrap().states.remove(sp_no);
+    }
+
+    fn advance(&self, info: &ApprovalInfo) -> Option<Transition> {
+        let to = ApprovalState::from_info(info)?;
+        let mut states = self.states.lock().unwrap();
+        let from = states.states.get(&info.sp_no).copied();
+        if let Some(from) = from {
+            if !from.can_move_to(to) {
+                return None;
+            }
+        }
+        states.states.insert(info.sp_no.clone(), to);
+        if to.is_terminal() {
+            states.finish(&info.sp_no);
+        } else if from.is_none() {
+            states.start(&info.sp_no);
+        }
+        Some(Transition {
+            sp_no: info.sp_no.clone(),
+            from,
+            to,
+        })
+    }
+}
+
+impl States {
+    fn start(&mut self, sp_no: &str) {
+        self.pending.push_back(sp_no.to_string());
+        while self.pending.len() > MAX_PENDING {
+            let sp_no = self.pending.pop_front().unwrap();
+            // finished ones are evicted by `finish`
+            if self.states.get(&sp_no).is_some_and(|s| !s.is_terminal()) {
+                self.states.remove(&sp_no);
+            }
+        }
+    }
+
+    fn finish(&mut self, sp_no: &str) {
+        self.finished.push_back(sp_no.to_string());
+        while self.finished.len() > MAX_FINISHED {
+            let sp_no = self.finished.pop_front().unwrap();
+            // it may have been forgotten and tracked again since
+            if self.states.get(&sp_no).is_some_and(|s| s.is_terminal()) {
+                self.states.remove(&sp_no);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::SpRecord;
+
+    struct Noop;
+
+    #[async_trait]
+    impl ApprovalHandler for Noop {
+        async fn on_transition(&self, _: &Transition, _: &ApprovalInfo) {}
+    }
+
+    fn info(sp_status: u32, event: u32, records: &[u32]) -> ApprovalInfo {
+        info_of("202006040001", sp_status, event, records)
+    }
+
+    fn info_of(sp_no: &str, sp_status: u32, event: u32, records: &[u32]) -> ApprovalInfo {
+        let sp_records = records
+            .iter()
+            .map(|s| SpRecord {
+                sp_status: *s,
+                approver_attr: 1,
+                details: vec![],
+            })
+            .collect();
+        ApprovalInfo {
+            sp_no: sp_no.into(),
+            sp_name: "请假".into(),
+            sp_status,
+            template_id: "".into(),
+            apply_time: 0,
+            applyer: "WuJunJie".into(),
+            sp_records,
+            statu_change_event: event,
+        }
+    }
+
+    #[test]
+    fn test_transitions() {
+        let m = ApprovalMachine::new(Noop);
+
+        let t = m.advance(&info(1, 1, &[1, 1])).unwrap();
+        assert_eq!(t.from, None);
+        assert_eq!(t.to, ApprovalState::Submitted);
+
+        let t = m.advance(&info(1, 2, &[2, 1])).unwrap();
+        assert_eq!(t.from, Some(ApprovalState::Submitted));
+        assert_eq!(t.to, ApprovalState::Approving(1));
+
+        // 催办 does not change the state
+        assert!(m.advance(&info(1, 5, &[2, 1])).is_none());
+
+        let t = m.advance(&info(2, 2, &[2, 2])).unwrap();
+        assert_eq!(t.to, ApprovalState::Approved);
+        assert!(t.to.is_terminal());
+        assert_eq!(m.state("202006040001"), Some(ApprovalState::Approved));
+    }
+
+    #[test]
+    fn test_duplicate_events() {
+        let m = ApprovalMachine::new(Noop);
+        assert!(m.advance(&info(1, 1, &[1, 1])).is_some());
+
+        // still at the first node, e.g. 催办 right after submitting
+        assert!(m.advance(&info(1, 5, &[1, 1])).is_none());
+        assert_eq!(m.state("202006040001"), Some(ApprovalState::Submitted));
+
+        assert!(m.advance(&info(1, 2, &[2, 1])).is_some());
+        assert!(m.advance(&info(1, 2, &[2, 1])).is_none());
+        assert!(m.advance(&info(3, 3, &[2, 3])).is_some());
+        assert!(m.advance(&info(3, 3, &[2, 3])).is_none());
+    }
+
+    #[test]
+    fn test_out_of_order_events() {
+        let m = ApprovalMachine::new(Noop);
+        assert!(m.advance(&info(1, 1, &[1, 1])).is_some());
+        assert!(m.advance(&info(2, 2, &[2, 2])).is_some());
+
+        // late events can not move a finished approval back
+        assert!(m.advance(&info(1, 2, &[2, 1])).is_none());
+        assert!(m.advance(&info(1, 1, &[1, 1])).is_none());
+        assert!(m.advance(&info(3, 3, &[2, 3])).is_none());
+        assert_eq!(m.state("202006040001"), Some(ApprovalState::Approved));
+
+        // an approved approval can still be withdrawn, but nothing happens after that
+        let t = m.advance(&info(6, 6, &[2, 2])).unwrap();
+        assert_eq!(t.from, Some(ApprovalState::Approved));
+        assert_eq!(t.to, ApprovalState::Withdrawn);
+        assert!(m.advance(&info(2, 2, &[2, 2])).is_none());
+
+        // an earlier node arriving late is dropped as well
+        let m = ApprovalMachine::new(Noop);
+        assert!(m.advance(&info(1, 2, &[2, 2, 1])).is_some());
+        assert!(m.advance(&info(1, 2, &[2, 1, 1])).is_none());
+        assert_eq!(m.state("202006040001"), Some(ApprovalState::Approving(2)));
+    }
+
+    #[test]
+    fn test_evict_finished() {
+        let m = ApprovalMachine::new(Noop);
+        assert!(m.advance(&info_of("pending", 1, 1, &[1])).is_some());
+        for i in 0..MAX_FINISHED + 10 {
+            assert!(m.advance(&info_of(&i.to_string(), 3, 3, &[3])).is_some());
+        }
+        let states = m.states.lock().unwrap();
+        assert_eq!(states.states.len(), MAX_FINISHED + 1);
+        assert!(!states.states.contains_key("0"));
+        assert!(states.states.contains_key("pending"));
+    }
+
+    #[test]
+    fn test_evict_pending() {
+        let m = ApprovalMachine::new(Noop);
+        for i in 0..MAX_PENDING + 10 {
+            assert!(m.advance(&info_of(&i.to_string(), 1, 1, &[1])).is_some());
+        }
+        // moving forward does not count as a new approval
+        assert!(m.advance(&info_of("20", 1, 2, &[2, 1])).is_some());
+        {
+            let states = m.states.lock().unwrap();
+            assert_eq!(states.states.len(), MAX_PENDING);
+            assert!(!states.states.contains_key("0"));
+            assert!(!states.states.contains_key("9"));
+            assert!(states.states.contains_key("10"));
+        }
+
+        // an evicted approval is tracked again as a new one
+        let t = m.advance(&info_of("0", 1, 2, &[2, 1])).unwrap();
+        assert_eq!(t.from, None);
+        assert!(!m.states.lock().unwrap().states.contains_key("10"));
+    }
+}
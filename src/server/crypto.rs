@@ -1,26 +1,32 @@
+#[cfg(feature = "native")]
 use std::string::ToString;
 
 use aes::Aes256;
 use block_modes::block_padding::Pkcs7;
 use block_modes::{BlockMode, Cbc};
+#[cfg(feature = "native")]
 use byteorder::{BigEndian, ByteOrder};
+#[cfg(feature = "native")]
 use sha1::{Digest, Sha1};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub(crate) enum CryptoError {
+    #[cfg(feature = "native")]
     #[error("invalid aes key, reason: {0}")]
     InvalidAesKey(&'static str),
     #[error("invalid decrypt data, reason: {0}")]
     InvalidDecryptData(&'static str),
 }
 
+#[cfg(feature = "native")]
 #[derive(Debug)]
 pub(crate) struct Crypto {
     token: String,
     aes_key: Vec<u8>,
 }
 
+#[cfg(feature = "native")]
 pub(crate) struct Payload {
     pub data: Vec<u8>,
     pub receiver_id: Vec<u8>,
@@ -28,20 +34,13 @@ pub(crate) struct Payload {
 
 type Aes256Cbc = Cbc<Aes256, Pkcs7>;
 
+#[cfg(feature = "native")]
 impl Crypto {
     pub(crate) fn new(
         token: impl ToString,
         encoding_aes_key: impl AsRef<[u8]>,
     ) -> Result<Crypto, CryptoError> {
-        let bytes = encoding_aes_key.as_ref();
-        if bytes.len() != 43 {
-            return Err(CryptoError::InvalidAesKey("length must be 43"));
-        }
-        let mut buf = Vec::with_capacity(bytes.len());
-        buf.extend_from_slice(bytes);
-        buf.push(b'=');
-        let aes_key = base64::decode(&buf)
-            .map_err(|_| CryptoError::InvalidAesKey("invalid base64 string"))?;
+        let aes_key = decode_aes_key(encoding_aes_key)?;
         let token = token.to_string();
         Ok(Crypto { token, aes_key })
     }
@@ -50,7 +49,7 @@ impl Crypto {
         let time_s = format!("{}", timestamp);
         let nonce_s = format!("{}", nonce);
 
-        let mut items = vec![self.token.clone(), time_s, nonce_s, msg_encrypt];
+        let mut items = [self.token.clone(), time_s, nonce_s, msg_encrypt];
         items.sort();
         let data = items.join("");
 
@@ -71,34 +70,56 @@ impl Crypto {
         buf.extend_from_slice(&payload.data);
         buf.extend_from_slice(&payload.receiver_id);
 
-        let cipher = Aes256Cbc::new_var(&aes_key, &iv).unwrap();
+        let cipher = Aes256Cbc::new_var(aes_key, iv).unwrap();
         let encrypted = cipher.encrypt_vec(&buf);
         base64::encode(encrypted)
     }
 
     pub(crate) fn decrypt(&self, data: impl AsRef<[u8]>) -> Result<Payload, CryptoError> {
-        // TODO: get this from cipher
-        let block_size = 16;
-
         let aes_msg = base64::decode(data)
             .map_err(|_| CryptoError::InvalidDecryptData("invalid base64 string"))?;
 
-        let aes_key = &self.aes_key;
-        let iv = &aes_key[0..block_size];
-
-        let cipher = Aes256Cbc::new_var(&aes_key, &iv).unwrap();
-        let decrypted = cipher
-            .decrypt_vec(&aes_msg)
-            .map_err(|_| CryptoError::InvalidDecryptData("invalid length"))?;
+        let decrypted = aes_decrypt(&self.aes_key, &aes_msg)?;
+        if decrypted.len() < 20 {
+            return Err(CryptoError::InvalidDecryptData("invalid length"));
+        }
         let msg_len = BigEndian::read_u32(&decrypted[16..20]) as usize;
-        let rcv_id_idx = 20 + msg_len;
+        let rcv_id_idx = match 20usize.checked_add(msg_len) {
+            Some(idx) if idx <= decrypted.len() => idx,
+            _ => return Err(CryptoError::InvalidDecryptData("invalid message length")),
+        };
         let data = Vec::from(&decrypted[20..rcv_id_idx]);
         let receiver_id = Vec::from(&decrypted[rcv_id_idx..]);
         Ok(Payload { data, receiver_id })
     }
 }
 
-#[cfg(test)]
+// the encoding aes key is the base64 encoded aes key without the trailing '='
+#[cfg(feature = "native")]
+pub(crate) fn decode_aes_key(encoding_aes_key: impl AsRef<[u8]>) -> Result<Vec<u8>, CryptoError> {
+    let bytes = encoding_aes_key.as_ref();
+    if bytes.len() != 43 {
+        return Err(CryptoError::InvalidAesKey("length must be 43"));
+    }
+    let mut buf = Vec::with_capacity(bytes.len());
+    buf.extend_from_slice(bytes);
+    buf.push(b'=');
+    base64::decode(&buf).map_err(|_| CryptoError::InvalidAesKey("invalid base64 string"))
+}
+
+// aes-256-cbc with the first 16 bytes of the key as iv
+pub(crate) fn aes_decrypt(aes_key: &[u8], data: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    // TODO: get this from cipher
+    let block_size = 16;
+    let iv = &aes_key[0..block_size];
+
+    let cipher = Aes256Cbc::new_var(aes_key, iv).unwrap();
+    cipher
+        .decrypt_vec(data)
+        .map_err(|_| CryptoError::InvalidDecryptData("invalid length"))
+}
+
+#[cfg(all(test, feature = "native"))]
 mod tests {
     use super::*;
 
@@ -128,6 +149,30 @@ mod tests {
         assert!(r2.is_err());
     }
 
+    #[test]
+    fn test_invalid_message_length() {
+        let token = "QDG6eK";
+        let encoding_aes_key = "4Ma3YBrSBbX2aez8MJpXGBne5LSDwgGqHbhM9WPYIws";
+        let crypto = Crypto::new(token, encoding_aes_key).unwrap();
+
+        let encrypt = |msg_len: u32, body: &[u8]| {
+            let mut buf = vec![0; 20];
+            BigEndian::write_u32(&mut buf[16..], msg_len);
+            buf.extend_from_slice(body);
+            let key = &crypto.aes_key;
+            let cipher = Aes256Cbc::new_var(key, &key[0..16]).unwrap();
+            base64::encode(cipher.encrypt_vec(&buf))
+        };
+
+        // the length field is larger than the message
+        assert!(crypto.decrypt(encrypt(1_000_000, b"abc")).is_err());
+        assert!(crypto.decrypt(encrypt(u32::MAX, b"abc")).is_err());
+        // the length field fits exactly, with an empty receiver id
+        let ret = crypto.decrypt(encrypt(3, b"abc")).unwrap();
+        assert_eq!(ret.data, b"abc");
+        assert!(ret.receiver_id.is_empty());
+    }
+
     #[test]
     fn test_sign() {
         let token = "QDG6eK";
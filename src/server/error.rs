@@ -1,7 +1,7 @@
 use thiserror::Error;
 
 #[derive(Error, Debug)]
-pub(crate) enum MessageError {
+pub enum MessageError {
     #[error("message invalid signature")]
     InvalidSignature,
     #[error("message invalid receiver")]
@@ -16,6 +16,8 @@ pub(crate) enum MessageError {
     InvalidFieldType(String),
     #[error("message invalid message type: {0}")]
     InvalidMessageType(String),
+    #[error("message invalid media: {0}")]
+    InvalidMedia(String),
 }
 
-pub(crate) type Result<T> = std::result::Result<T, MessageError>;
+pub type Result<T> = std::result::Result<T, MessageError>;
@@ -0,0 +1,24 @@
+use std::time::Duration;
+
+/// 企业微信要求 5 秒内响应回调, 超时后会重试, 导致重复投递
+pub const ACK_BUDGET: Duration = Duration::from_secs(5);
+
+/// 处理一次回调请求各阶段的耗时
+#[derive(Debug, Clone, Default)]
+pub struct CallbackTiming {
+    pub read_body: Duration,
+    pub verify: Duration, // 校验签名
+    pub decrypt: Duration,
+    pub parse: Duration,
+    pub handler: Duration,
+    pub total: Duration,
+}
+
+/// 回调耗时的观察者, 可以用来上报监控指标
+pub trait LatencyObserver: Send + Sync + 'static {
+    /// 每个成功解析的回调请求都会调用
+    fn observe(&self, timing: &CallbackTiming);
+
+    /// 总耗时超过 `Builder::latency_warning` 设置的阈值时调用, 默认什么也不做
+    fn on_budget_warning(&self, _timing: &CallbackTiming) {}
+}
@@ -1,11 +1,23 @@
+#[cfg(feature = "native")]
 mod app;
+mod approval;
+#[cfg(any(feature = "native", feature = "msgaudit"))]
 pub mod crypto;
 pub mod error;
+#[cfg(feature = "native")]
+mod latency;
 mod recv_message;
 mod send_message;
+#[cfg(feature = "native")]
+#[allow(clippy::module_inception)]
 mod server;
 
+#[cfg(feature = "native")]
 pub use app::*;
+pub use approval::*;
+#[cfg(feature = "native")]
+pub use latency::*;
 pub use recv_message::*;
 pub use send_message::*;
+#[cfg(feature = "native")]
 pub use server::*;
@@ -2,6 +2,7 @@ use std::str::FromStr;
 
 use xmltree::Element;
 
+#[cfg(feature = "native")]
 use super::crypto::Crypto;
 use super::error::{MessageError, Result};
 
@@ -11,11 +12,10 @@ pub struct RecvMessage {
     pub from_user_name: String,
     pub agent_id: u64,
     pub create_time: u64,
-    pub msg_id: u64,
+    pub msg_id: u64, // 事件没有消息 id, 此时为 0
     pub msg_ty: RecvMessageType,
 }
 
-// TODO: add event types
 #[derive(Debug, Clone)]
 #[non_exhaustive]
 pub enum RecvMessageType {
@@ -25,6 +25,58 @@ pub enum RecvMessageType {
     Video(RecvVideo),
     Location(Location),
     Link(Link),
+    Event(Event),
+}
+
+// TODO: add more event types
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum Event {
+    ApprovalChange(ApprovalInfo),         // sys_approval_change
+    ChangeContact(ContactChange),         // change_contact
+    TemplateCardEvent(TemplateCardEvent), // template_card_event, 点击模板卡片按钮
+    Other(String),                        // 暂不支持解析的事件, 携带事件类型
+}
+
+#[derive(Debug, Clone)]
+pub struct ContactChange {
+    pub change_type: String, // create_user, update_user, delete_user, create_party ...
+    pub user_id: String,     // 成员变更事件的 userid, 部门和标签变更事件为空
+}
+
+#[derive(Debug, Clone)]
+pub struct TemplateCardEvent {
+    pub card_type: String,
+    pub event_key: String, // 点击的按钮的 key
+    pub task_id: String,
+    pub response_code: String, // 用于更新卡片, 72 小时内有效且只能使用一次
+}
+
+#[derive(Debug, Clone)]
+pub struct ApprovalInfo {
+    pub sp_no: String,
+    pub sp_name: String,
+    pub sp_status: u32, // 1-审批中；2-已通过；3-已驳回；4-已撤销；6-通过后撤销；7-已删除；10-已支付
+    pub template_id: String,
+    pub apply_time: u64,
+    pub applyer: String,           // 申请人 userid
+    pub sp_records: Vec<SpRecord>, // 审批流程, 每个审批节点一项
+    pub statu_change_event: u32, // 1-提单；2-同意；3-驳回；4-转审；5-催办；6-撤销；8-通过后撤销；10-添加备注 ...
+}
+
+#[derive(Debug, Clone)]
+pub struct SpRecord {
+    pub sp_status: u32,     // 1-审批中；2-已同意；3-已驳回；4-已转审
+    pub approver_attr: u32, // 1-或签；2-会签
+    pub details: Vec<SpRecordDetail>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SpRecordDetail {
+    pub approver: String, // 审批人 userid
+    pub speech: String,
+    pub sp_status: u32,
+    pub sp_time: u64,
 }
 
 #[derive(Debug, Clone)]
@@ -88,49 +140,116 @@ macro_rules! try_field_parse {
     };
 }
 
+macro_rules! try_child {
+    ($name:expr, $element:expr) => {
+        match $element.get_child($name) {
+            Some(d) => d,
+            None => return Err(MessageError::MissingField($name)),
+        }
+    };
+}
+
+// fields with empty content are omitted by the parser
+macro_rules! field_or_default {
+    ($name:expr, $element:expr) => {
+        fetch($name, &$element).unwrap_or_default().to_string()
+    };
+}
+
 fn fetch<'a>(name: &str, element: &'a Element) -> Option<&'a str> {
     let child = element.get_child(name)?;
-    child.children.get(0)?.as_text()
+    child.children.first()?.as_text()
 }
 
-impl RecvMessage {
-    pub(crate) fn parse(
-        data: impl AsRef<[u8]>,
-        crypto: &Crypto,
-        timestamp: u64,
-        nonce: u64,
-        msg_signature: &str,
-    ) -> Result<RecvMessage> {
+/// 回调请求中的外层 xml, 消息内容仍然是加密的
+#[derive(Debug, Clone)]
+pub struct EncryptedEnvelope {
+    pub to_user_name: String,  // 企业 id
+    pub agent_id: Option<u64>, // 通讯录等回调没有 AgentID
+    pub encrypt: String,
+}
+
+impl EncryptedEnvelope {
+    pub fn parse(data: impl AsRef<[u8]>) -> Result<EncryptedEnvelope> {
         let xml = Element::parse(data.as_ref())
             .map_err(|e| MessageError::ParseFailed(format!("{}", e)))?;
 
         let to_user_name = try_field!("ToUserName", xml);
-        let agent_id = try_field_parse!("AgentID", xml, u64);
-        let msg_encrypt = try_field!("Encrypt", xml);
-
-        let sign = crypto.sign(msg_encrypt.clone(), timestamp, nonce);
+        let agent_id = match fetch("AgentID", &xml) {
+            Some(_) => Some(try_field_parse!("AgentID", xml, u64)),
+            None => None,
+        };
+        let encrypt = try_field!("Encrypt", xml);
+        Ok(EncryptedEnvelope {
+            to_user_name,
+            agent_id,
+            encrypt,
+        })
+    }
 
+    /// 校验签名
+    #[cfg(feature = "native")]
+    pub(crate) fn verify(
+        &self,
+        crypto: &Crypto,
+        timestamp: u64,
+        nonce: u64,
+        msg_signature: &str,
+    ) -> Result<()> {
+        let sign = crypto.sign(self.encrypt.clone(), timestamp, nonce);
         if sign != msg_signature {
             return Err(MessageError::InvalidSignature);
         }
+        Ok(())
+    }
 
+    /// 解密并返回明文的 xml, 调用前需要先通过 `verify` 校验签名
+    #[cfg(feature = "native")]
+    pub(crate) fn decrypt(&self, crypto: &Crypto) -> Result<Vec<u8>> {
         let payload = crypto
-            .decrypt(&msg_encrypt)
+            .decrypt(&self.encrypt)
             .map_err(|e| MessageError::DecryptFailed(format!("{}", e)))?;
-        let msg = payload.data;
-        let receiver_id = payload.receiver_id;
-        if &*receiver_id != to_user_name.as_bytes() {
+        if &*payload.receiver_id != self.to_user_name.as_bytes() {
             return Err(MessageError::InvalidReceiver);
         }
+        Ok(payload.data)
+    }
+}
+
+/// 解密后的明文消息
+pub type PlainMessage = RecvMessage;
+
+impl RecvMessage {
+    /// 图片, 语音和视频消息中可以通过 `Client::download_media` 下载的 media_id
+    pub fn media_id(&self) -> Option<&str> {
+        match &self.msg_ty {
+            RecvMessageType::Picture(p) => Some(&p.media_id),
+            RecvMessageType::Voice(v) => Some(&v.media_id),
+            RecvMessageType::Video(v) => Some(&v.media_id),
+            _ => None,
+        }
+    }
 
-        let inner_xml = Element::parse(&*msg)
+    /// 解析解密后的明文 xml
+    pub fn parse(data: impl AsRef<[u8]>) -> Result<RecvMessage> {
+        let inner_xml = Element::parse(data.as_ref())
             .map_err(|e| MessageError::ParseFailed(format!("inner: {}", e)))?;
 
+        let to_user_name = try_field!("ToUserName", inner_xml);
+        // AgentID is absent in contact change events
+        let agent_id = match fetch("AgentID", &inner_xml) {
+            Some(_) => try_field_parse!("AgentID", inner_xml, u64),
+            None => 0,
+        };
         let from_user_name = try_field!("FromUserName", inner_xml);
         let create_time = try_field_parse!("CreateTime", inner_xml, u64);
-        let msg_id = try_field_parse!("MsgId", inner_xml, u64);
+        let msg_ty = try_field!("MsgType", inner_xml);
+        let msg_id = match &*msg_ty {
+            "event" => 0,
+            _ => try_field_parse!("MsgId", inner_xml, u64),
+        };
 
-        let msg_ty = match &*try_field!("MsgType", inner_xml) {
+        let msg_ty = match &*msg_ty {
             "text" => {
                 let content = try_field!("Content", inner_xml);
                 RecvMessageType::Text(content)
@@ -156,6 +275,7 @@ impl RecvMessage {
                 };
                 RecvMessageType::Video(video)
             }
+            "event" => RecvMessageType::Event(parse_event(&inner_xml)?),
             ty => return Err(MessageError::InvalidMessageType(ty.to_string())), // TODO
         };
 
@@ -169,3 +289,222 @@ impl RecvMessage {
         })
     }
 }
+
+fn parse_event(xml: &Element) -> Result<Event> {
+    let event = match &*try_field!("Event", xml) {
+        "sys_approval_change" => {
+            let info = try_child!("ApprovalInfo", xml);
+            Event::ApprovalChange(parse_approval_info(info)?)
+        }
+        "change_contact" => Event::ChangeContact(ContactChange {
+            change_type: try_field!("ChangeType", xml),
+            user_id: field_or_default!("UserID", xml),
+        }),
+        "template_card_event" => Event::TemplateCardEvent(TemplateCardEvent {
+            card_type: field_or_default!("CardType", xml),
+            event_key: try_field!("EventKey", xml),
+            task_id: try_field!("TaskId", xml),
+            response_code: field_or_default!("ResponseCode", xml),
+        }),
+        ty => Event::Other(ty.to_string()),
+    };
+    Ok(event)
+}
+
+fn parse_approval_info(xml: &Element) -> Result<ApprovalInfo> {
+    let applyer = try_child!("Applyer", xml);
+    let sp_records = child_elements("SpRecord", xml)
+        .map(parse_sp_record)
+        .collect::<Result<_>>()?;
+    Ok(ApprovalInfo {
+        sp_no: try_field!("SpNo", xml),
+        sp_name: field_or_default!("SpName", xml),
+        sp_status: try_field_parse!("SpStatus", xml, u32),
+        template_id: field_or_default!("TemplateId", xml),
+        apply_time: try_field_parse!("ApplyTime", xml, u64),
+        applyer: try_field!("UserId", applyer),
+        sp_records,
+        statu_change_event: try_field_parse!("StatuChangeEvent", xml, u32),
+    })
+}
+
+fn parse_sp_record(xml: &Element) -> Result<SpRecord> {
+    let details = child_elements("Details", xml)
+        .map(|d| {
+            let approver = try_child!("Approver", d);
+            Ok(SpRecordDetail {
+                approver: try_field!("UserId", approver),
+                speech: field_or_default!("Speech", d),
+                sp_status: try_field_parse!("SpStatus", d, u32),
+                sp_time: try_field_parse!("SpTime", d, u64),
+            })
+        })
+        .collect::<Result<_>>()?;
+    Ok(SpRecord {
+        sp_status: try_field_parse!("SpStatus", xml, u32),
+        approver_attr: try_field_parse!("ApproverAttr", xml, u32),
+        details,
+    })
+}
+
+fn child_elements<'a>(name: &'a str, xml: &'a Element) -> impl Iterator<Item = &'a Element> {
+    xml.children
+        .iter()
+        .filter_map(|n| n.as_element())
+        .filter(move |e| e.name == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "native")]
+    use crate::server::crypto::Payload;
+
+    #[cfg(feature = "native")]
+    #[test]
+    fn test_parse_envelope_and_plain() {
+        let corp_id = "wx5823bf96d3bd56c7";
+        let plain = r#"<xml>
+            <ToUserName><![CDATA[wx5823bf96d3bd56c7]]></ToUserName>
+            <FromUserName><![CDATA[mycreate]]></FromUserName>
+            <CreateTime>1409659813</CreateTime>
+            <MsgType><![CDATA[text]]></MsgType>
+            <Content><![CDATA[hello]]></Content>
+            <MsgId>4561255354251345929</MsgId>
+            <AgentID>218</AgentID>
+        </xml>"#;
+        let crypto = Crypto::new("QDG6eK", "4Ma3YBrSBbX2aez8MJpXGBne5LSDwgGqHbhM9WPYIws").unwrap();
+        let payload = Payload {
+            data: Vec::from(plain),
+            receiver_id: Vec::from(corp_id),
+        };
+        let encrypt = crypto.encrypt(&payload);
+        let sign = crypto.sign(encrypt.clone(), 1409659813, 1372623149);
+        let outer = format!(
+            "<xml><ToUserName><![CDATA[{}]]></ToUserName><AgentID><![CDATA[218]]></AgentID><Encrypt><![CDATA[{}]]></Encrypt></xml>",
+            corp_id, encrypt
+        );
+
+        let envelope = EncryptedEnvelope::parse(&outer).unwrap();
+        assert_eq!(envelope.to_user_name, corp_id);
+        assert_eq!(envelope.agent_id, Some(218));
+
+        assert!(envelope
+            .verify(&crypto, 1409659813, 1372623149, "invalid")
+            .is_err());
+        envelope
+            .verify(&crypto, 1409659813, 1372623149, &sign)
+            .unwrap();
+        let data = envelope.decrypt(&crypto).unwrap();
+
+        let msg = PlainMessage::parse(&data).unwrap();
+        assert_eq!(msg.from_user_name, "mycreate");
+        assert_eq!(msg.agent_id, 218);
+        assert_eq!(msg.msg_id, 4561255354251345929);
+        match msg.msg_ty {
+            RecvMessageType::Text(t) => assert_eq!(t, "hello"),
+            t => panic!("unexpected message type: {:?}", t),
+        }
+    }
+
+    #[test]
+    fn test_parse_approval_event() {
+        let data = r#"<xml>
+            <ToUserName><![CDATA[wwddddccc7775555aaa]]></ToUserName>
+            <FromUserName><![CDATA[sys]]></FromUserName>
+            <CreateTime>1527838022</CreateTime>
+            <MsgType><![CDATA[event]]></MsgType>
+            <Event><![CDATA[sys_approval_change]]></Event>
+            <AgentID>1</AgentID>
+            <ApprovalInfo>
+                <SpNo>202006040001</SpNo>
+                <SpName><![CDATA[请假]]></SpName>
+                <SpStatus>1</SpStatus>
+                <TemplateId><![CDATA[3TkaH5KFbrG9heEQWLJjhgpFNDqwY2tHR5kCNPTf]]></TemplateId>
+                <ApplyTime>1527837645</ApplyTime>
+                <Applyer>
+                    <UserId><![CDATA[WuJunJie]]></UserId>
+                    <Party><![CDATA[1]]></Party>
+                </Applyer>
+                <SpRecord>
+                    <SpStatus>2</SpStatus>
+                    <ApproverAttr>1</ApproverAttr>
+                    <Details>
+                        <Approver><UserId><![CDATA[WangXiaoMing]]></UserId></Approver>
+                        <Speech><![CDATA[]]></Speech>
+                        <SpStatus>2</SpStatus>
+                        <SpTime>1527838000</SpTime>
+                    </Details>
+                </SpRecord>
+                <SpRecord>
+                    <SpStatus>1</SpStatus>
+                    <ApproverAttr>2</ApproverAttr>
+                    <Details>
+                        <Approver><UserId><![CDATA[LiuXiaoGang]]></UserId></Approver>
+                        <Speech><![CDATA[]]></Speech>
+                        <SpStatus>1</SpStatus>
+                        <SpTime>0</SpTime>
+                    </Details>
+                </SpRecord>
+                <StatuChangeEvent>2</StatuChangeEvent>
+            </ApprovalInfo>
+        </xml>"#;
+        let xml = Element::parse(data.as_bytes()).unwrap();
+        let info = match parse_event(&xml).unwrap() {
+            Event::ApprovalChange(info) => info,
+            e => panic!("unexpected event: {:?}", e),
+        };
+
+        assert_eq!(info.sp_no, "202006040001");
+        assert_eq!(info.sp_name, "请假");
+        assert_eq!(info.applyer, "WuJunJie");
+        assert_eq!(info.sp_records.len(), 2);
+        assert_eq!(info.sp_records[0].details[0].approver, "WangXiaoMing");
+        assert_eq!(info.sp_records[0].details[0].speech, "");
+        assert_eq!(info.sp_records[1].approver_attr, 2);
+        assert_eq!(info.statu_change_event, 2);
+    }
+
+    #[test]
+    fn test_parse_contact_and_card_event() {
+        let data = r#"<xml>
+            <ToUserName><![CDATA[toUser]]></ToUserName>
+            <FromUserName><![CDATA[sys]]></FromUserName>
+            <CreateTime>1403610513</CreateTime>
+            <MsgType><![CDATA[event]]></MsgType>
+            <Event><![CDATA[change_contact]]></Event>
+            <ChangeType>delete_user</ChangeType>
+            <UserID><![CDATA[zhangsan]]></UserID>
+        </xml>"#;
+        let msg = RecvMessage::parse(data).unwrap();
+        assert_eq!(msg.agent_id, 0);
+        match msg.msg_ty {
+            RecvMessageType::Event(Event::ChangeContact(c)) => {
+                assert_eq!(c.change_type, "delete_user");
+                assert_eq!(c.user_id, "zhangsan");
+            }
+            t => panic!("unexpected message type: {:?}", t),
+        }
+
+        let data = r#"<xml>
+            <ToUserName><![CDATA[toUser]]></ToUserName>
+            <FromUserName><![CDATA[FromUser]]></FromUserName>
+            <CreateTime>123456789</CreateTime>
+            <MsgType><![CDATA[event]]></MsgType>
+            <Event><![CDATA[template_card_event]]></Event>
+            <EventKey><![CDATA[approve]]></EventKey>
+            <TaskId><![CDATA[inherit-zhangsan]]></TaskId>
+            <CardType><![CDATA[button_interaction]]></CardType>
+            <ResponseCode><![CDATA[ResponseCode]]></ResponseCode>
+            <AgentID>1</AgentID>
+        </xml>"#;
+        match RecvMessage::parse(data).unwrap().msg_ty {
+            RecvMessageType::Event(Event::TemplateCardEvent(e)) => {
+                assert_eq!(e.event_key, "approve");
+                assert_eq!(e.task_id, "inherit-zhangsan");
+                assert_eq!(e.response_code, "ResponseCode");
+            }
+            t => panic!("unexpected message type: {:?}", t),
+        }
+    }
+}
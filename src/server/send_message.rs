@@ -1,7 +1,16 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[cfg(feature = "native")]
 use xmltree::{Element, XMLNode};
 
+#[cfg(feature = "native")]
 use super::crypto::{Crypto, Payload};
-use super::error::Result;
+use super::error::{MessageError, Result};
+
+// limits of the voice reply, temporary media expires after 3 days
+const VOICE_MAX_SIZE: u64 = 2 * 1024 * 1024;
+const VOICE_MAX_DURATION: Duration = Duration::from_secs(60);
+const MEDIA_TTL: Duration = Duration::from_secs(3 * 24 * 3600);
 
 #[derive(Debug, Clone)]
 pub struct SendMessage {
@@ -27,6 +36,44 @@ pub struct SendVideo {
     pub description: String,
 }
 
+/// 语音素材的元数据, 通常由缓存 media_id 的地方在上传时记录
+#[derive(Debug, Clone)]
+pub struct VoiceMeta {
+    pub created_at: u64, // 上传时间, unix 时间戳
+    pub size: u64,       // 字节数
+    pub duration: Option<Duration>,
+}
+
+impl VoiceMeta {
+    /// 检查临时素材是否过期, 以及语音是否超过 2MB 或者 60 秒
+    pub fn check(&self) -> Result<()> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        self.check_at(now)
+    }
+
+    fn check_at(&self, now: u64) -> Result<()> {
+        let age = Duration::from_secs(now.saturating_sub(self.created_at));
+        if age >= MEDIA_TTL {
+            let msg = format!("voice media uploaded {:?} ago has expired", age);
+            return Err(MessageError::InvalidMedia(msg));
+        }
+        if self.size > VOICE_MAX_SIZE {
+            let msg = format!("voice size {} exceeds 2MB", self.size);
+            return Err(MessageError::InvalidMedia(msg));
+        }
+        match self.duration {
+            Some(d) if d > VOICE_MAX_DURATION => {
+                let msg = format!("voice duration {:?} exceeds 60s", d);
+                Err(MessageError::InvalidMedia(msg))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct PictureText {
     pub pic_url: String,
@@ -67,6 +114,17 @@ impl SendMessage {
         }
     }
 
+    /// 同 `new_voice`, 但是会先根据 `meta` 检查素材, 避免回复被企业微信静默丢弃
+    pub fn new_voice_checked(
+        media_id: String,
+        meta: &VoiceMeta,
+        to_user_name: String,
+        from_user_name: String,
+    ) -> Result<SendMessage> {
+        meta.check()?;
+        Ok(Self::new_voice(media_id, to_user_name, from_user_name))
+    }
+
     pub fn new_video(
         video: SendVideo,
         to_user_name: String,
@@ -101,6 +159,7 @@ impl SendMessage {
         }
     }
 
+    #[cfg(feature = "native")]
     pub(crate) fn serialize(self, timestamp: u64, nonce: u64, crypto: &Crypto) -> Result<String> {
         let SendMessage {
             to_user_name,
@@ -189,6 +248,7 @@ impl SendMessage {
 
 ///////////////////////////// helper functions ///////////////////////////////////////////////
 
+#[cfg(feature = "native")]
 fn new_node(name: &str, data: String) -> XMLNode {
     let node = XMLNode::Text(data);
     let ret = Element {
@@ -202,6 +262,7 @@ fn new_node(name: &str, data: String) -> XMLNode {
     XMLNode::Element(ret)
 }
 
+#[cfg(feature = "native")]
 fn new_xml(name: &str, nodes: Vec<XMLNode>) -> Element {
     Element {
         prefix: None,
@@ -213,8 +274,43 @@ fn new_xml(name: &str, nodes: Vec<XMLNode>) -> Element {
     }
 }
 
+#[cfg(feature = "native")]
 fn serialize_xml(e: Element) -> String {
     let mut ret = vec![];
     e.write(&mut ret).unwrap();
     String::from_utf8(ret).unwrap()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_voice_meta_check() {
+        let now = 1_600_000_000;
+        let meta = VoiceMeta {
+            created_at: now - 3600,
+            size: 1024,
+            duration: Some(Duration::from_secs(10)),
+        };
+        assert!(meta.check_at(now).is_ok());
+
+        let expired = VoiceMeta {
+            created_at: now - 3 * 24 * 3600,
+            ..meta.clone()
+        };
+        assert!(expired.check_at(now).is_err());
+
+        let too_long = VoiceMeta {
+            duration: Some(Duration::from_secs(61)),
+            ..meta.clone()
+        };
+        assert!(too_long.check_at(now).is_err());
+
+        let too_large = VoiceMeta {
+            size: 3 * 1024 * 1024,
+            ..meta
+        };
+        assert!(too_large.check_at(now).is_err());
+    }
+}
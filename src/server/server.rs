@@ -1,34 +1,149 @@
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::collections::HashMap;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-use actix_web::{web, App as ActixApp, Error, HttpResponse, HttpServer};
+use actix_web::http::header;
+use actix_web::{web, App as ActixApp, Error, HttpRequest, HttpResponse, HttpServer};
 use futures::StreamExt;
 use log::{info, warn};
 use serde::Deserialize;
 
 use super::crypto::Crypto;
-use super::{App, RecvMessage};
+use super::{App, CallbackTiming, EncryptedEnvelope, LatencyObserver, RecvMessage, ACK_BUDGET};
 
 pub struct Builder<T: App> {
     app: T,
+    corps: Vec<CorpConfig>,
+    unbound_agents: Vec<u64>, // agents registered before any corp, rejected by `build`
+    port: Option<u16>,        // optional, default is 12349
+    body_limits: BodyLimits,
+    latency: LatencyConfig,
+}
+
+struct LatencyConfig {
+    warning: Duration,
+    observer: Option<Box<dyn LatencyObserver>>,
+}
+
+impl Default for LatencyConfig {
+    fn default() -> Self {
+        LatencyConfig {
+            warning: ACK_BUDGET * 4 / 5,
+            observer: None,
+        }
+    }
+}
+
+// protect the receive loop against oversized bodies and clients trickle-feeding the body
+#[derive(Debug, Clone, Copy)]
+struct BodyLimits {
+    max_size: usize,
+    chunk_timeout: Duration, // max idle time between two chunks
+    timeout: Duration,       // max time to read the whole body
+}
+
+impl Default for BodyLimits {
+    fn default() -> Self {
+        BodyLimits {
+            max_size: 1024 * 1024,
+            chunk_timeout: Duration::from_secs(2),
+            timeout: Duration::from_secs(4),
+        }
+    }
+}
+
+struct CorpConfig {
+    path: String,
+    corp_id: Option<String>,
     token: String,
     encoding_aes_key: String,
-    port: Option<u16>, // optional, default is 12349
+    agents: HashMap<u64, Box<dyn App>>,
 }
 
 pub struct Server<T: App> {
-    app: T,
-    crypto: Crypto,
+    app: T,                             // default handler
+    routes: HashMap<String, Vec<Corp>>, // callback path -> corps served on it
     port: u16,
+    body_limits: BodyLimits,
+    latency: LatencyConfig,
+}
+
+struct Corp {
+    corp_id: Option<String>, // None means accept any corp
+    crypto: Crypto,
+    agents: HashMap<u64, Box<dyn App>>, // agent id -> handler of this corp
 }
 
 impl<T: App> Builder<T> {
     pub fn new(app: T, token: impl ToString, encoding_aes_key: impl ToString) -> Self {
+        let corp = CorpConfig {
+            path: "/".to_string(),
+            corp_id: None,
+            token: token.to_string(),
+            encoding_aes_key: encoding_aes_key.to_string(),
+            agents: HashMap::new(),
+        };
+        Builder {
+            app,
+            corps: vec![corp],
+            unbound_agents: vec![],
+            port: None,
+            body_limits: BodyLimits::default(),
+            latency: LatencyConfig::default(),
+        }
+    }
+
+    /// 服务多个企业, 通过 `corp` 和 `corp_at` 注册各个企业的配置
+    pub fn multi_corp(app: T) -> Self {
         Builder {
             app,
+            corps: vec![],
+            unbound_agents: vec![],
+            port: None,
+            body_limits: BodyLimits::default(),
+            latency: LatencyConfig::default(),
+        }
+    }
+
+    /// 在默认回调路径 `/` 上注册一个企业, 根据消息中的 ToUserName 区分企业
+    pub fn corp(
+        self,
+        corp_id: impl ToString,
+        token: impl ToString,
+        encoding_aes_key: impl ToString,
+    ) -> Self {
+        self.corp_at("/", corp_id, token, encoding_aes_key)
+    }
+
+    /// 在指定的回调路径上注册一个企业
+    pub fn corp_at(
+        mut self,
+        path: impl ToString,
+        corp_id: impl ToString,
+        token: impl ToString,
+        encoding_aes_key: impl ToString,
+    ) -> Self {
+        let corp = CorpConfig {
+            path: path.to_string(),
+            corp_id: Some(corp_id.to_string()),
             token: token.to_string(),
             encoding_aes_key: encoding_aes_key.to_string(),
-            port: None,
+            agents: HashMap::new(),
+        };
+        self.corps.push(corp);
+        self
+    }
+
+    /// 多个应用共用一个回调地址时, 为指定的应用注册单独的处理器, 其他应用的消息由默认处理器处理
+    ///
+    /// 处理器属于最近一次注册的企业, 不同企业的相同 `agent_id` 互不影响
+    pub fn agent(mut self, agent_id: u64, app: impl App) -> Self {
+        match self.corps.last_mut() {
+            Some(corp) => {
+                corp.agents.insert(agent_id, Box::new(app));
+            }
+            None => self.unbound_agents.push(agent_id),
         }
+        self
     }
 
     pub fn port(mut self, p: u16) -> Self {
@@ -36,11 +151,74 @@ impl<T: App> Builder<T> {
         self
     }
 
+    /// 回调请求体的最大字节数, 默认 1MB
+    pub fn max_body_size(mut self, size: usize) -> Self {
+        self.body_limits.max_size = size;
+        self
+    }
+
+    /// 读取回调请求体时两次读取之间允许的最长间隔, 默认 2 秒
+    pub fn body_chunk_timeout(mut self, d: Duration) -> Self {
+        self.body_limits.chunk_timeout = d;
+        self
+    }
+
+    /// 读取整个回调请求体允许的最长时间, 默认 4 秒
+    pub fn body_timeout(mut self, d: Duration) -> Self {
+        self.body_limits.timeout = d;
+        self
+    }
+
+    /// 处理回调的总耗时超过 `d` 时打印警告并通知观察者, 默认 4 秒
+    pub fn latency_warning(mut self, d: Duration) -> Self {
+        self.latency.warning = d;
+        self
+    }
+
+    /// 接收每个回调请求各阶段的耗时
+    pub fn latency_observer(mut self, observer: impl LatencyObserver) -> Self {
+        self.latency.observer = Some(Box::new(observer));
+        self
+    }
+
     pub fn build(self) -> anyhow::Result<Server<T>> {
+        if self.corps.is_empty() {
+            anyhow::bail!("no corp registered");
+        }
+        if let Some(agent_id) = self.unbound_agents.first() {
+            anyhow::bail!("agent {} is registered before any corp", agent_id);
+        }
+
+        let mut routes: HashMap<String, Vec<Corp>> = HashMap::new();
+        for c in self.corps {
+            let crypto = Crypto::new(c.token, c.encoding_aes_key)?;
+            let corp = Corp {
+                corp_id: c.corp_id,
+                crypto,
+                agents: c.agents,
+            };
+            routes.entry(c.path).or_default().push(corp);
+        }
+        for (path, corps) in &routes {
+            if corps.len() > 1 && corps.iter().any(|c| c.corp_id.is_none()) {
+                anyhow::bail!(
+                    "path {} is shared by multiple corps, corp id is required",
+                    path
+                );
+            }
+        }
+
         let app = self.app;
-        let crypto = Crypto::new(self.token, self.encoding_aes_key)?;
         let port = self.port.unwrap_or(12349);
-        let s = Server { app, crypto, port };
+        let body_limits = self.body_limits;
+        let latency = self.latency;
+        let s = Server {
+            app,
+            routes,
+            port,
+            body_limits,
+            latency,
+        };
         Ok(s)
     }
 }
@@ -53,6 +231,26 @@ impl<T: App> Server<T> {
         let ret = futures::future::pending().await;
         Ok(ret)
     }
+
+    fn corps(&self, path: &str) -> &[Corp] {
+        self.routes.get(path).map(|c| &**c).unwrap_or(&[])
+    }
+
+    fn observe(&self, timing: &CallbackTiming) {
+        let latency = &self.latency;
+        if timing.total >= latency.warning {
+            warn!(
+                "callback took {:?}, close to the {:?} ack budget: {:?}",
+                timing.total, ACK_BUDGET, timing
+            );
+        }
+        if let Some(o) = &latency.observer {
+            o.observe(timing);
+            if timing.total >= latency.warning {
+                o.on_budget_warning(timing);
+            }
+        }
+    }
 }
 
 // TODO remove this when https://github.com/actix/actix-net/pull/266#issuecomment-808939487 is released
@@ -61,10 +259,13 @@ async fn run<T: App>(s: Server<T>) -> std::io::Result<()> {
     let server = web::Data::new(s);
     let addr = format!("0.0.0.0:{}", server.port);
     HttpServer::new(move || {
-        ActixApp::new()
-            .app_data(server.clone())
-            .route("/", web::get().to(validate::<T>))
-            .route("/", web::post().to(recv::<T>))
+        let mut app = ActixApp::new().app_data(server.clone());
+        for path in server.routes.keys() {
+            app = app
+                .route(path, web::get().to(validate::<T>))
+                .route(path, web::post().to(recv::<T>));
+        }
+        app
     })
     .bind(addr)?
     .run()
@@ -88,12 +289,26 @@ struct RecvParams {
 }
 
 async fn validate<T: App>(
+    req: HttpRequest,
     info: web::Query<ValidateParams>,
     server: web::Data<Server<T>>,
 ) -> HttpResponse {
     info!("validate request: params: {:?}", info);
 
-    let crypto = &server.crypto;
+    // the echo request carries no corp id, pick the corp whose token signs it
+    let corp = server.corps(req.path()).iter().find(|c| {
+        let sign = c
+            .crypto
+            .sign(info.echostr.clone(), info.timestamp, info.nonce);
+        sign == info.msg_signature
+    });
+    let crypto = match corp {
+        Some(c) => &c.crypto,
+        None => {
+            warn!("validate message invalid signature");
+            return HttpResponse::BadRequest().finish();
+        }
+    };
     let payload = match crypto.decrypt(&info.echostr) {
         Ok(d) => d,
         Err(e) => {
@@ -106,45 +321,118 @@ async fn validate<T: App>(
 }
 
 async fn recv<T: App>(
+    req: HttpRequest,
     info: web::Query<RecvParams>,
     mut body: web::Payload,
     server: web::Data<Server<T>>,
 ) -> Result<HttpResponse, Error> {
     info!("receive request: params: {:?}", info);
 
+    let start = Instant::now();
+    let mut timing = CallbackTiming::default();
+    let limits = server.body_limits;
+    let content_length = req
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<usize>().ok());
+    if content_length.is_some_and(|len| len > limits.max_size) {
+        warn!("request body exceeds {} bytes", limits.max_size);
+        return Ok(HttpResponse::PayloadTooLarge().finish());
+    }
+
+    let deadline = Instant::now() + limits.timeout;
     let mut bytes = web::BytesMut::new();
-    while let Some(item) = body.next().await {
-        bytes.extend_from_slice(&item?);
-    }
-
-    let crypto = &server.crypto;
-    let msg = match RecvMessage::parse(
-        &bytes,
-        &crypto,
-        info.timestamp,
-        info.nonce,
-        &info.msg_signature,
-    ) {
+    loop {
+        let wait = limits
+            .chunk_timeout
+            .min(deadline.saturating_duration_since(Instant::now()));
+        let item = match tokio::time::timeout(wait, body.next()).await {
+            Ok(Some(item)) => item?,
+            Ok(None) => break,
+            Err(_) => {
+                warn!("read request body timeout, received {} bytes", bytes.len());
+                return Ok(HttpResponse::RequestTimeout().finish());
+            }
+        };
+        if bytes.len() + item.len() > limits.max_size {
+            warn!("request body exceeds {} bytes", limits.max_size);
+            return Ok(HttpResponse::PayloadTooLarge().finish());
+        }
+        bytes.extend_from_slice(&item);
+    }
+    timing.read_body = start.elapsed();
+
+    let envelope = match EncryptedEnvelope::parse(&bytes) {
         Ok(d) => d,
         Err(e) => {
             warn!("parse message failed, reason: {}", e);
             return Ok(HttpResponse::BadRequest().finish());
         }
     };
+    let corp = match select_corp(server.corps(req.path()), &envelope.to_user_name) {
+        Some(c) => c,
+        None => {
+            warn!("no corp matches the message");
+            return Ok(HttpResponse::BadRequest().finish());
+        }
+    };
+    let mut stage = Instant::now();
+    let mut lap = || {
+        let now = Instant::now();
+        let d = now - stage;
+        stage = now;
+        d
+    };
+    let crypto = &corp.crypto;
+    let msg = envelope
+        .verify(crypto, info.timestamp, info.nonce, &info.msg_signature)
+        .and_then(|_| {
+            timing.verify = lap();
+            envelope.decrypt(crypto)
+        })
+        .and_then(|data| {
+            timing.decrypt = lap();
+            RecvMessage::parse(data)
+        });
+    let msg = match msg {
+        Ok(d) => d,
+        Err(e) => {
+            warn!("parse message failed, reason: {}", e);
+            return Ok(HttpResponse::BadRequest().finish());
+        }
+    };
+    timing.parse = lap();
 
-    match server.app.handle(msg).await {
+    let app = match envelope.agent_id.and_then(|id| corp.agents.get(&id)) {
+        Some(app) => &**app,
+        None => &server.app as &dyn App,
+    };
+    let reply = app.handle(msg).await;
+    timing.handler = lap();
+    let resp = match reply {
         Some(m) => {
             let msg = m
                 .serialize(current_timestamp(), gen_nonce(), crypto)
                 .unwrap();
-            Ok(HttpResponse::Ok().body(msg))
+            HttpResponse::Ok().body(msg)
         }
-        None => Ok(HttpResponse::Ok().finish()),
-    }
+        None => HttpResponse::Ok().finish(),
+    };
+    timing.total = start.elapsed();
+    server.observe(&timing);
+    Ok(resp)
 }
 
 ///////////////////////////// helper functions ///////////////////////////////////////////////
 
+fn select_corp<'a>(corps: &'a [Corp], corp_id: &str) -> Option<&'a Corp> {
+    if let [corp @ Corp { corp_id: None, .. }] = corps {
+        return Some(corp);
+    }
+    corps.iter().find(|c| c.corp_id.as_deref() == Some(corp_id))
+}
+
 #[inline]
 fn current_timestamp() -> u64 {
     SystemTime::now()
@@ -157,3 +445,49 @@ fn current_timestamp() -> u64 {
 fn gen_nonce() -> u64 {
     rand::random()
 }
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+
+    use super::*;
+    use crate::server::SendMessage;
+
+    const AES_KEY: &str = "4Ma3YBrSBbX2aez8MJpXGBne5LSDwgGqHbhM9WPYIws";
+
+    struct Noop;
+
+    #[async_trait]
+    impl App for Noop {
+        async fn handle(&self, _msg: RecvMessage) -> Option<SendMessage> {
+            None
+        }
+    }
+
+    #[test]
+    fn test_agents_per_corp() {
+        let server = Builder::multi_corp(Noop)
+            .corp("corp_a", "token_a", AES_KEY)
+            .agent(1, Noop)
+            .corp("corp_b", "token_b", AES_KEY)
+            .agent(1, Noop)
+            .agent(2, Noop)
+            .build()
+            .unwrap();
+
+        let corps = server.corps("/");
+        let a = select_corp(corps, "corp_a").unwrap();
+        let b = select_corp(corps, "corp_b").unwrap();
+        assert!(a.agents.contains_key(&1));
+        assert!(!a.agents.contains_key(&2));
+        assert!(b.agents.contains_key(&1));
+        assert!(b.agents.contains_key(&2));
+        assert!(select_corp(corps, "corp_c").is_none());
+
+        let ret = Builder::multi_corp(Noop)
+            .agent(1, Noop)
+            .corp("corp_a", "token_a", AES_KEY)
+            .build();
+        assert!(ret.is_err());
+    }
+}
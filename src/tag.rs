@@ -0,0 +1,173 @@
+#[cfg(feature = "native")]
+use serde::de::IgnoredAny;
+use serde::{Deserialize, Deserializer, Serialize};
+
+#[cfg(feature = "native")]
+use crate::client::Client;
+#[cfg(feature = "native")]
+use crate::Result;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct TagId(pub u64);
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Tag {
+    pub tagid: TagId,
+    pub tagname: String,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct TagRequest<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tagid: Option<TagId>,
+    tagname: &'a str,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct CreateTagResponse {
+    tagid: TagId,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct ListTagResponse {
+    taglist: Vec<Tag>,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct TagUsersRequest<'a> {
+    tagid: TagId,
+    userlist: &'a [&'a str],
+    partylist: &'a [u64],
+}
+
+/// 增删标签成员时部分成员或部门非法的结果, 全部非法时接口直接返回错误
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TagUsersResponse {
+    #[serde(default)]
+    #[serde(rename = "invalidlist")]
+    #[serde(deserialize_with = "split_bar")]
+    pub invalid_users: Vec<String>,
+    #[serde(default)]
+    #[serde(rename = "invalidparty")]
+    pub invalid_parties: Vec<u64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TagUser {
+    pub userid: String,
+    #[serde(default)]
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TagMembers {
+    pub tagname: String,
+    #[serde(default)]
+    pub userlist: Vec<TagUser>,
+    #[serde(default)]
+    pub partylist: Vec<u64>,
+}
+
+/// 标签管理
+#[cfg(feature = "native")]
+impl Client {
+    /// 创建标签, 不指定 `tagid` 时自动分配, 返回标签 id
+    pub async fn create_tag(&self, tagname: &str, tagid: Option<TagId>) -> Result<TagId> {
+        let req = TagRequest { tagid, tagname };
+        let ret: CreateTagResponse = self.post("/cgi-bin/tag/create", &req).await?;
+        Ok(ret.tagid)
+    }
+
+    pub async fn update_tag(&self, tagid: TagId, tagname: &str) -> Result<()> {
+        let req = TagRequest {
+            tagid: Some(tagid),
+            tagname,
+        };
+        let _: IgnoredAny = self.post("/cgi-bin/tag/update", &req).await?;
+        Ok(())
+    }
+
+    pub async fn delete_tag(&self, tagid: TagId) -> Result<()> {
+        let _: IgnoredAny = self
+            .get("/cgi-bin/tag/delete", &[("tagid", tagid.0)])
+            .await?;
+        Ok(())
+    }
+
+    /// 增加标签成员, `users` 和 `parties` 不能同时为空
+    pub async fn add_tag_users(
+        &self,
+        tagid: TagId,
+        users: &[&str],
+        parties: &[u64],
+    ) -> Result<TagUsersResponse> {
+        let req = TagUsersRequest {
+            tagid,
+            userlist: users,
+            partylist: parties,
+        };
+        self.post("/cgi-bin/tag/addtagusers", &req).await
+    }
+
+    /// 删除标签成员, `users` 和 `parties` 不能同时为空
+    pub async fn del_tag_users(
+        &self,
+        tagid: TagId,
+        users: &[&str],
+        parties: &[u64],
+    ) -> Result<TagUsersResponse> {
+        let req = TagUsersRequest {
+            tagid,
+            userlist: users,
+            partylist: parties,
+        };
+        self.post("/cgi-bin/tag/deltagusers", &req).await
+    }
+
+    pub async fn get_tag_members(&self, tagid: TagId) -> Result<TagMembers> {
+        self.get("/cgi-bin/tag/get", &[("tagid", tagid.0)]).await
+    }
+
+    pub async fn list_tags(&self) -> Result<Vec<Tag>> {
+        let ret: ListTagResponse = self.get("/cgi-bin/tag/list", &()).await?;
+        Ok(ret.taglist)
+    }
+}
+
+///////////////////////////// helper functions ///////////////////////////////////////////////
+
+// "usr1|usr2" -> ["usr1", "usr2"]
+fn split_bar<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Vec<String>, D::Error> {
+    let s = String::deserialize(deserializer)?;
+    let ret = s
+        .split('|')
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+        .collect();
+    Ok(ret)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tag_users_response() {
+        let data = r#"{"errcode": 0, "errmsg": "ok", "invalidlist": "usr1|usr2|usr", "invalidparty": [2, 4]}"#;
+        let resp: TagUsersResponse = serde_json::from_str(data).unwrap();
+        assert_eq!(resp.invalid_users, vec!["usr1", "usr2", "usr"]);
+        assert_eq!(resp.invalid_parties, vec![2, 4]);
+
+        let data = r#"{"errcode": 0, "errmsg": "ok"}"#;
+        let resp: TagUsersResponse = serde_json::from_str(data).unwrap();
+        assert!(resp.invalid_users.is_empty());
+        assert!(resp.invalid_parties.is_empty());
+    }
+}
@@ -0,0 +1,328 @@
+use serde::Serialize;
+
+use crate::message::MessageBuildError;
+
+/// 处理模板卡片中用户提供的字符串, 返回实际写入卡片的内容
+///
+/// `max_chars` 为企业微信对该字段的长度限制 (按字符计), `field` 为字段名, 用于报错
+pub trait Sanitizer {
+    fn sanitize(
+        &self,
+        field: &'static str,
+        value: &str,
+        max_chars: usize,
+    ) -> Result<String, MessageBuildError>;
+}
+
+/// 默认的处理策略, 两种策略都会去掉换行以外的控制字符
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SanitizePolicy {
+    Truncate, // 超长时截断, 末尾加省略号
+    Error,    // 超长时返回错误
+}
+
+impl Sanitizer for SanitizePolicy {
+    fn sanitize(
+        &self,
+        field: &'static str,
+        value: &str,
+        max_chars: usize,
+    ) -> Result<String, MessageBuildError> {
+        let cleaned: String = value
+            .chars()
+            .filter(|c| !c.is_control() || *c == '\n')
+            .collect();
+        if cleaned.chars().count() <= max_chars {
+            return Ok(cleaned);
+        }
+        match self {
+            SanitizePolicy::Truncate => {
+                let mut ret: String = cleaned.chars().take(max_chars.saturating_sub(1)).collect();
+                ret.push('…');
+                Ok(ret)
+            }
+            SanitizePolicy::Error => Err(MessageBuildError::FieldTooLong(field, max_chars)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TemplateCard {
+    card_type: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source: Option<Source>,
+    main_title: MainTitle,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sub_title_text: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    horizontal_content_list: Vec<HorizontalContent>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    jump_list: Vec<Jump>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    card_action: Option<CardAction>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    task_id: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    button_list: Vec<Button>,
+}
+
+impl TemplateCard {
+    pub fn task_id(&self) -> Option<&str> {
+        self.task_id.as_deref()
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct Source {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    icon_url: Option<String>,
+    desc: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct MainTitle {
+    title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    desc: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct HorizontalContent {
+    keyname: String,
+    value: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct Jump {
+    #[serde(rename = "type")]
+    ty: u8, // 1 跳转 url
+    title: String,
+    url: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct CardAction {
+    #[serde(rename = "type")]
+    ty: u8, // 1 跳转 url
+    url: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct Button {
+    text: String,
+    style: u8, // 1 到 4
+    key: String,
+}
+
+/// 模板卡片, 目前支持文本通知型和按钮交互型
+pub struct TemplateCardBuilder {
+    card: TemplateCard,
+    sanitizer: Box<dyn Sanitizer + Send + Sync>,
+}
+
+impl TemplateCardBuilder {
+    fn new(card_type: &'static str, title: String) -> Self {
+        let card = TemplateCard {
+            card_type,
+            source: None,
+            main_title: MainTitle { title, desc: None },
+            sub_title_text: None,
+            horizontal_content_list: vec![],
+            jump_list: vec![],
+            card_action: None,
+            task_id: None,
+            button_list: vec![],
+        };
+        TemplateCardBuilder {
+            card,
+            sanitizer: Box::new(SanitizePolicy::Truncate),
+        }
+    }
+
+    /// 文本通知型卡片, 需要通过 `card_action_url` 指定点击卡片后跳转的地址
+    pub fn text_notice(title: impl ToString) -> Self {
+        Self::new("text_notice", title.to_string())
+    }
+
+    /// 按钮交互型卡片, 需要指定 `task_id` 以及至少一个按钮
+    pub fn button_interaction(title: impl ToString) -> Self {
+        Self::new("button_interaction", title.to_string())
+    }
+
+    /// 默认为 `SanitizePolicy::Truncate`
+    pub fn sanitizer(mut self, sanitizer: impl Sanitizer + Send + Sync + 'static) -> Self {
+        self.sanitizer = Box::new(sanitizer);
+        self
+    }
+
+    pub fn source(mut self, icon_url: Option<String>, desc: impl ToString) -> Self {
+        self.card.source = Some(Source {
+            icon_url,
+            desc: desc.to_string(),
+        });
+        self
+    }
+
+    pub fn main_title_desc(mut self, desc: impl ToString) -> Self {
+        self.card.main_title.desc = Some(desc.to_string());
+        self
+    }
+
+    pub fn sub_title(mut self, text: impl ToString) -> Self {
+        self.card.sub_title_text = Some(text.to_string());
+        self
+    }
+
+    pub fn horizontal_content(mut self, keyname: impl ToString, value: impl ToString) -> Self {
+        self.card.horizontal_content_list.push(HorizontalContent {
+            keyname: keyname.to_string(),
+            value: value.to_string(),
+        });
+        self
+    }
+
+    pub fn jump(mut self, title: impl ToString, url: impl ToString) -> Self {
+        self.card.jump_list.push(Jump {
+            ty: 1,
+            title: title.to_string(),
+            url: url.to_string(),
+        });
+        self
+    }
+
+    pub fn card_action_url(mut self, url: impl ToString) -> Self {
+        self.card.card_action = Some(CardAction {
+            ty: 1,
+            url: url.to_string(),
+        });
+        self
+    }
+
+    /// 任务 id, 同一个应用内不能重复, 只能由数字, 字母和 "_-@" 组成, 最长 128 字节
+    pub fn task_id(mut self, task_id: impl ToString) -> Self {
+        self.card.task_id = Some(task_id.to_string());
+        self
+    }
+
+    /// `style` 为按钮样式, 取值 1 到 4, `key` 会在回调事件中带回
+    pub fn button(mut self, text: impl ToString, style: u8, key: impl ToString) -> Self {
+        self.card.button_list.push(Button {
+            text: text.to_string(),
+            style,
+            key: key.to_string(),
+        });
+        self
+    }
+
+    pub fn build(self) -> Result<TemplateCard, MessageBuildError> {
+        let TemplateCardBuilder {
+            mut card,
+            sanitizer,
+        } = self;
+        let s = |field, value: &mut String, max| -> Result<(), MessageBuildError> {
+            *value = sanitizer.sanitize(field, value, max)?;
+            Ok(())
+        };
+
+        if let Some(source) = &mut card.source {
+            s("source.desc", &mut source.desc, 13)?;
+        }
+        s("main_title.title", &mut card.main_title.title, 26)?;
+        if let Some(desc) = &mut card.main_title.desc {
+            s("main_title.desc", desc, 30)?;
+        }
+        if let Some(text) = &mut card.sub_title_text {
+            s("sub_title_text", text, 112)?;
+        }
+        limit_len("horizontal_content_list", &card.horizontal_content_list, 6)?;
+        for c in &mut card.horizontal_content_list {
+            s("horizontal_content_list.keyname", &mut c.keyname, 5)?;
+            s("horizontal_content_list.value", &mut c.value, 26)?;
+        }
+        limit_len("jump_list", &card.jump_list, 3)?;
+        for j in &mut card.jump_list {
+            s("jump_list.title", &mut j.title, 13)?;
+        }
+        limit_len("button_list", &card.button_list, 6)?;
+        for b in &mut card.button_list {
+            s("button_list.text", &mut b.text, 10)?;
+        }
+
+        // identifiers are never rewritten, they are echoed back in callbacks
+        if let Some(task_id) = &card.task_id {
+            let valid = task_id.len() <= 128
+                && task_id
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "_-@".contains(c));
+            if !valid {
+                return Err(MessageBuildError::InvalidField("task_id", task_id.clone()));
+            }
+        }
+        for b in &card.button_list {
+            if b.key.len() > 1024 {
+                return Err(MessageBuildError::FieldTooLong("button_list.key", 1024));
+            }
+        }
+
+        match card.card_type {
+            "text_notice" if card.card_action.is_none() => {
+                Err(MessageBuildError::MissingField("card_action"))
+            }
+            "button_interaction" if card.task_id.is_none() => {
+                Err(MessageBuildError::MissingField("task_id"))
+            }
+            "button_interaction" if card.button_list.is_empty() => {
+                Err(MessageBuildError::MissingField("button_list"))
+            }
+            _ => Ok(card),
+        }
+    }
+}
+
+fn limit_len<T>(field: &'static str, list: &[T], max: usize) -> Result<(), MessageBuildError> {
+    if list.len() > max {
+        return Err(MessageBuildError::TooManyItems(field, max));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize() {
+        let p = SanitizePolicy::Truncate;
+        assert_eq!(p.sanitize("f", "a\u{0}b\nc", 10).unwrap(), "ab\nc");
+        assert_eq!(p.sanitize("f", "一二三四五六", 4).unwrap(), "一二三…");
+
+        let p = SanitizePolicy::Error;
+        assert_eq!(p.sanitize("f", "一二三四", 4).unwrap(), "一二三四");
+        assert!(p.sanitize("f", "一二三四五六", 4).is_err());
+    }
+
+    #[test]
+    fn test_build() {
+        let card = TemplateCardBuilder::button_interaction("部署审批\u{7}")
+            .sub_title("服务 foo 需要发布到生产环境")
+            .horizontal_content("申请人", "zhangsan")
+            .task_id("deploy-42")
+            .button("同意", 1, "approve")
+            .button("驳回", 2, "reject")
+            .build()
+            .unwrap();
+        let v = serde_json::to_value(&card).unwrap();
+        assert_eq!(v["card_type"], "button_interaction");
+        assert_eq!(v["main_title"]["title"], "部署审批");
+        assert_eq!(v["button_list"][1]["key"], "reject");
+
+        let r = TemplateCardBuilder::button_interaction("t")
+            .task_id("invalid id!")
+            .button("ok", 1, "ok")
+            .build();
+        assert!(r.is_err());
+
+        let r = TemplateCardBuilder::text_notice("t").build();
+        assert!(r.is_err());
+    }
+}
@@ -0,0 +1,168 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::client::Client;
+use crate::message::{MessageBuilder, MessageResponse};
+use crate::Result;
+
+// max number of folded messages listed in a digest
+const DIGEST_MAX_LINES: usize = 20;
+const DIGEST_LINE_CHARS: usize = 60;
+
+/// 按接收人限制应用消息的发送频率
+///
+/// 每个成员在一个时间窗口内最多收到 `max_per_window` 条消息, 超出的消息不会发送,
+/// 而是在窗口结束后通过 `flush` 合并为一条摘要发送
+pub struct RecipientThrottle {
+    max_per_window: u32,
+    window: Duration,
+    windows: Mutex<HashMap<String, UserWindow>>, // userid -> window
+}
+
+#[derive(Debug)]
+struct UserWindow {
+    started_at: Instant,
+    sent: u32,
+    folded: Vec<String>,
+}
+
+/// 一个成员在窗口内被合并的消息
+#[derive(Debug, Clone)]
+pub struct Digest {
+    pub userid: String,
+    pub folded: Vec<String>,
+}
+
+impl Digest {
+    pub fn content(&self) -> String {
+        let mut lines = vec![format!("另有 {} 条通知被合并:", self.folded.len())];
+        lines.extend(self.folded.iter().take(DIGEST_MAX_LINES).map(|s| {
+            let line: String = s
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .chars()
+                .take(DIGEST_LINE_CHARS)
+                .collect();
+            format!("- {}", line)
+        }));
+        if self.folded.len() > DIGEST_MAX_LINES {
+            lines.push("...".to_string());
+        }
+        lines.join("\n")
+    }
+}
+
+impl RecipientThrottle {
+    /// 比如 `RecipientThrottle::new(10, Duration::from_secs(3600))` 每人每小时最多 10 条
+    pub fn new(max_per_window: u32, window: Duration) -> Self {
+        RecipientThrottle {
+            max_per_window,
+            window,
+            windows: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 记录一次发送, 返回 false 表示超出限制, `content` 已被合并到摘要中
+    pub fn admit(&self, userid: &str, content: &str) -> bool {
+        self.admit_at(userid, content, Instant::now())
+    }
+
+    /// 取出窗口已经结束的摘要
+    pub fn take_digests(&self) -> Vec<Digest> {
+        self.take_digests_at(Instant::now())
+    }
+
+    /// 发送文本消息, 超出限制时返回 None
+    pub async fn send_text(
+        &self,
+        client: &Client,
+        agent_id: u64,
+        userid: &str,
+        content: &str,
+    ) -> Result<Option<MessageResponse>> {
+        if !self.admit(userid, content) {
+            return Ok(None);
+        }
+        let msg = MessageBuilder::new_text(agent_id, content.to_string())
+            .with_user(userid.to_string())
+            .build()?;
+        Ok(Some(client.send_msg(&msg).await?))
+    }
+
+    /// 发送所有已结束窗口的摘要, 需要定期调用, 返回发送的摘要数
+    pub async fn flush(&self, client: &Client, agent_id: u64) -> Result<usize> {
+        let digests = self.take_digests();
+        for d in &digests {
+            let msg = MessageBuilder::new_text(agent_id, d.content())
+                .with_user(d.userid.clone())
+                .build()?;
+            client.send_msg(&msg).await?;
+        }
+        Ok(digests.len())
+    }
+
+    fn admit_at(&self, userid: &str, content: &str, now: Instant) -> bool {
+        let mut windows = self.windows.lock().unwrap();
+        let w = windows.entry(userid.to_string()).or_insert(UserWindow {
+            started_at: now,
+            sent: 0,
+            folded: vec![],
+        });
+        // an expired window with folded messages is kept until its digest is taken
+        if now.duration_since(w.started_at) >= self.window && w.folded.is_empty() {
+            w.started_at = now;
+            w.sent = 0;
+        }
+        if w.sent < self.max_per_window {
+            w.sent += 1;
+            true
+        } else {
+            w.folded.push(content.to_string());
+            false
+        }
+    }
+
+    fn take_digests_at(&self, now: Instant) -> Vec<Digest> {
+        let mut windows = self.windows.lock().unwrap();
+        let mut ret = vec![];
+        windows.retain(|userid, w| {
+            if now.duration_since(w.started_at) < self.window {
+                return true;
+            }
+            if !w.folded.is_empty() {
+                ret.push(Digest {
+                    userid: userid.clone(),
+                    folded: std::mem::take(&mut w.folded),
+                });
+            }
+            false
+        });
+        ret
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_throttle() {
+        let t = RecipientThrottle::new(2, Duration::from_secs(60));
+        let now = Instant::now();
+        assert!(t.admit_at("a", "1", now));
+        assert!(t.admit_at("a", "2", now));
+        assert!(!t.admit_at("a", "3", now));
+        assert!(t.admit_at("b", "1", now));
+        assert!(t.take_digests_at(now).is_empty());
+
+        let later = now + Duration::from_secs(61);
+        assert!(!t.admit_at("a", "4", later));
+        let digests = t.take_digests_at(later);
+        assert_eq!(digests.len(), 1);
+        assert_eq!(digests[0].folded, vec!["3", "4"]);
+        assert!(digests[0].content().starts_with("另有 2 条通知"));
+        assert!(t.admit_at("a", "5", later));
+    }
+}
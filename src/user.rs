@@ -0,0 +1,265 @@
+#[cfg(feature = "native")]
+use std::collections::HashMap;
+#[cfg(feature = "native")]
+use std::sync::Mutex;
+#[cfg(feature = "native")]
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "native")]
+use futures::stream::{self, StreamExt};
+#[cfg(feature = "native")]
+use serde::de::IgnoredAny;
+use serde::Deserialize;
+#[cfg(feature = "native")]
+use serde::Serialize;
+
+#[cfg(feature = "native")]
+use crate::client::Client;
+#[cfg(feature = "native")]
+use crate::poll::retry_rate_limited;
+#[cfg(feature = "native")]
+use crate::Result;
+
+// max number of in-flight `user/get` requests issued by `get_users`
+#[cfg(feature = "native")]
+const GET_USERS_CONCURRENCY: usize = 8;
+#[cfg(feature = "native")]
+const USER_CACHE_CAPACITY: usize = 10000;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct User {
+    pub userid: String,
+    pub name: String,
+    #[serde(default)]
+    pub alias: String,
+    #[serde(default)]
+    pub department: Vec<u64>,
+    #[serde(default)]
+    pub main_department: Option<u64>,
+    #[serde(default)]
+    pub position: String,
+    #[serde(default)]
+    pub mobile: String,
+    #[serde(default)]
+    pub gender: String, // 0表示未定义，1表示男性，2表示女性
+    #[serde(default)]
+    pub email: String,
+    #[serde(default)]
+    pub avatar: String,
+    #[serde(default)]
+    pub thumb_avatar: String,
+    #[serde(default)]
+    pub status: u8, // 1=已激活，2=已禁用，4=未激活，5=退出企业
+    #[serde(default)]
+    pub open_userid: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SimpleUser {
+    pub userid: String,
+    pub name: String,
+    #[serde(default)]
+    pub department: Vec<u64>,
+}
+
+/// `get_users` 使用的短时缓存, 默认最多缓存 10000 个成员
+#[cfg(feature = "native")]
+pub struct UserCache {
+    ttl: Duration,
+    capacity: usize,
+    users: Mutex<HashMap<String, (Instant, User)>>,
+}
+
+#[cfg(feature = "native")]
+impl UserCache {
+    pub fn new(ttl: Duration) -> Self {
+        UserCache {
+            ttl,
+            capacity: USER_CACHE_CAPACITY,
+            users: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn capacity(mut self, n: usize) -> Self {
+        self.capacity = n;
+        self
+    }
+
+    fn get(&self, userid: &str) -> Option<User> {
+        let mut users = self.users.lock().unwrap();
+        match users.get(userid) {
+            Some((t, u)) if t.elapsed() < self.ttl => Some(u.clone()),
+            Some(_) => {
+                users.remove(userid);
+                None
+            }
+            None => None,
+        }
+    }
+
+    // when full, drop the expired entries first and then the oldest one
+    fn insert(&self, user: User) {
+        let mut users = self.users.lock().unwrap();
+        if users.len() >= self.capacity && !users.contains_key(&user.userid) {
+            users.retain(|_, (t, _)| t.elapsed() < self.ttl);
+            if users.len() >= self.capacity {
+                let oldest = users
+                    .iter()
+                    .min_by_key(|(_, (t, _))| *t)
+                    .map(|(k, _)| k.clone());
+                if let Some(k) = oldest {
+                    users.remove(&k);
+                }
+            }
+        }
+        users.insert(user.userid.clone(), (Instant::now(), user));
+    }
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct BatchInviteRequest<'a> {
+    user: &'a [&'a str],
+    party: &'a [u64],
+    tag: &'a [u64],
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct SimpleListQuery {
+    department_id: u64,
+    fetch_child: u8,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct SimpleListResponse {
+    userlist: Vec<SimpleUser>,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct ActiveStatRequest<'a> {
+    date: &'a str,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct ActiveStatResponse {
+    active_cnt: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BatchInviteResponse {
+    #[serde(default)]
+    pub invaliduser: Vec<String>,
+    #[serde(default)]
+    pub invalidparty: Vec<u64>,
+    #[serde(default)]
+    pub invalidtag: Vec<u64>,
+}
+
+/// 成员管理
+#[cfg(feature = "native")]
+impl Client {
+    /// 二次验证: 企业在成员验证成功后调用, 成员即可成功加入企业
+    pub async fn authsucc(&self, userid: &str) -> Result<()> {
+        let _: IgnoredAny = self
+            .get("/cgi-bin/user/authsucc", &[("userid", userid)])
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_user(&self, userid: &str) -> Result<User> {
+        self.get("/cgi-bin/user/get", &[("userid", userid)]).await
+    }
+
+    /// 并发地读取多个成员, 返回结果与 `userids` 一一对应
+    pub async fn get_users(
+        &self,
+        userids: &[&str],
+        cache: Option<&UserCache>,
+    ) -> Vec<Result<User>> {
+        stream::iter(userids)
+            .map(|id| async move {
+                if let Some(u) = cache.and_then(|c| c.get(id)) {
+                    return Ok(u);
+                }
+                let user = retry_rate_limited(|| self.get_user(id)).await?;
+                if let Some(c) = cache {
+                    c.insert(user.clone());
+                }
+                Ok(user)
+            })
+            .buffered(GET_USERS_CONCURRENCY)
+            .collect()
+            .await
+    }
+
+    /// 获取部门成员, `fetch_child` 为 true 时包含所有子部门的成员
+    pub async fn list_department_users(
+        &self,
+        department_id: u64,
+        fetch_child: bool,
+    ) -> Result<Vec<SimpleUser>> {
+        let query = SimpleListQuery {
+            department_id,
+            fetch_child: fetch_child as u8,
+        };
+        let ret: SimpleListResponse = self.get("/cgi-bin/user/simplelist", &query).await?;
+        Ok(ret.userlist)
+    }
+
+    /// 邀请成员, 返回非法的成员, 部门和标签
+    pub async fn batch_invite(
+        &self,
+        users: &[&str],
+        parties: &[u64],
+        tags: &[u64],
+    ) -> Result<BatchInviteResponse> {
+        let req = BatchInviteRequest {
+            user: users,
+            party: parties,
+            tag: tags,
+        };
+        self.post("/cgi-bin/batch/invite", &req).await
+    }
+
+    /// 获取某一天的活跃成员数, `date` 形如 `2020-03-27`, 只能查询最近 30 天
+    pub async fn get_active_stat(&self, date: &str) -> Result<u64> {
+        let req = ActiveStatRequest { date };
+        let ret: ActiveStatResponse = self.post("/cgi-bin/user/get_active_stat", &req).await?;
+        Ok(ret.active_cnt)
+    }
+}
+
+#[cfg(all(test, feature = "native"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_cache() {
+        let data = r#"{"errcode": 0, "errmsg": "ok", "userid": "zhangsan", "name": "张三", "department": [1, 2]}"#;
+        let user: User = serde_json::from_str(data).unwrap();
+
+        let cache = UserCache::new(Duration::from_secs(60));
+        cache.insert(user.clone());
+        assert_eq!(cache.get("zhangsan").unwrap().name, "张三");
+        assert!(cache.get("lisi").is_none());
+
+        let cache = UserCache::new(Duration::from_secs(0));
+        cache.insert(user.clone());
+        assert!(cache.get("zhangsan").is_none());
+
+        let cache = UserCache::new(Duration::from_secs(60)).capacity(2);
+        for id in ["a", "b", "c"] {
+            let mut u = user.clone();
+            u.userid = id.to_string();
+            cache.insert(u);
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(cache.users.lock().unwrap().len(), 2);
+        assert!(cache.get("a").is_none());
+        assert!(cache.get("c").is_some());
+    }
+}
@@ -0,0 +1,219 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::RwLock;
+
+use serde::ser::{SerializeMap, Serializer};
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, Result};
+
+static WEBHOOK_URL: &str = "https://qyapi.weixin.qq.com/cgi-bin/webhook/send";
+
+// the key is invalid, e.g. removed after rotation
+const INVALID_KEY_ERRCODE: u64 = 93000;
+
+/// 群机器人, 只依赖异步的 http 客户端, 不需要 access token, 也不会创建线程
+///
+/// 可以持有多个 key, 当前 key 失效或者被限频时自动尝试下一个
+pub struct WebhookClient {
+    keys: RwLock<Vec<String>>,
+    active: AtomicUsize, // 上次发送成功的 key 的下标
+    http_client: reqwest::Client,
+}
+
+#[derive(Debug, Clone)]
+pub enum WebhookMessage {
+    Text(WebhookText),
+    Markdown(String),
+    News(Vec<Article>), // 1 到 8 条图文
+    File(String),       // media_id
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct WebhookText {
+    pub content: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub mentioned_list: Vec<String>, // userid 列表, "@all" 表示提醒所有人
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub mentioned_mobile_list: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Article {
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub picurl: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WebhookResponse {
+    errcode: u64,
+    errmsg: String,
+}
+
+impl WebhookMessage {
+    pub fn text(content: impl ToString) -> Self {
+        WebhookMessage::Text(WebhookText {
+            content: content.to_string(),
+            ..Default::default()
+        })
+    }
+
+    pub fn markdown(content: impl ToString) -> Self {
+        WebhookMessage::Markdown(content.to_string())
+    }
+}
+
+impl WebhookClient {
+    /// `key` 为机器人 webhook 地址中的 key 参数
+    pub fn new(key: impl ToString) -> Self {
+        Self::with_keys(vec![key.to_string()])
+    }
+
+    /// 按顺序尝试的多个 key, 不能为空
+    pub fn with_keys(keys: Vec<String>) -> Self {
+        assert!(!keys.is_empty(), "webhook keys can not be empty");
+        WebhookClient {
+            keys: RwLock::new(keys),
+            active: AtomicUsize::new(0),
+            http_client: reqwest::Client::new(),
+        }
+    }
+
+    /// 运行时替换 key, 比如轮换 key 之后, 正在进行的发送不受影响
+    pub fn set_keys(&self, keys: Vec<String>) {
+        assert!(!keys.is_empty(), "webhook keys can not be empty");
+        *self.keys.write().unwrap() = keys;
+        self.active.store(0, Ordering::Relaxed);
+    }
+
+    pub fn keys(&self) -> Vec<String> {
+        self.keys.read().unwrap().clone()
+    }
+
+    /// 从上次成功的 key 开始依次尝试, 所有 key 都失败时返回最后一个错误
+    pub async fn send(&self, msg: &WebhookMessage) -> Result<()> {
+        let keys = self.keys();
+        let start = self.active.load(Ordering::Relaxed) % keys.len();
+        let mut last_err = None;
+        for i in 0..keys.len() {
+            let idx = (start + i) % keys.len();
+            match self.send_with_key(&keys[idx], msg).await {
+                Ok(()) => {
+                    self.active.store(idx, Ordering::Relaxed);
+                    return Ok(());
+                }
+                Err(e) if should_failover(&e) => last_err = Some(e),
+                Err(e) => return Err(e),
+            }
+        }
+        Err(last_err.unwrap())
+    }
+
+    async fn send_with_key(&self, key: &str, msg: &WebhookMessage) -> Result<()> {
+        let ret: WebhookResponse = self
+            .http_client
+            .post(WEBHOOK_URL)
+            .query(&[("key", key)])
+            .json(msg)
+            .send()
+            .await?
+            .json()
+            .await?;
+        if ret.errcode != 0 {
+            return Err(Error::from_errcode(ret.errcode, ret.errmsg));
+        }
+        Ok(())
+    }
+}
+
+fn should_failover(e: &Error) -> bool {
+    match e {
+        Error::RequestFailed(code, _) => *code == INVALID_KEY_ERRCODE,
+        Error::RateLimited { .. } | Error::HttpError(_) => true,
+        _ => false,
+    }
+}
+
+impl Serialize for WebhookMessage {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        use WebhookMessage::*;
+
+        #[derive(Serialize)]
+        struct Content<'a> {
+            content: &'a str,
+        }
+
+        #[derive(Serialize)]
+        struct News<'a> {
+            articles: &'a [Article],
+        }
+
+        #[derive(Serialize)]
+        struct File<'a> {
+            media_id: &'a str,
+        }
+
+        let mut map = serializer.serialize_map(Some(2))?;
+        match self {
+            Text(t) => {
+                map.serialize_entry("msgtype", "text")?;
+                map.serialize_entry("text", t)?;
+            }
+            Markdown(t) => {
+                map.serialize_entry("msgtype", "markdown")?;
+                map.serialize_entry("markdown", &Content { content: t })?;
+            }
+            News(t) => {
+                map.serialize_entry("msgtype", "news")?;
+                map.serialize_entry("news", &News { articles: t })?;
+            }
+            File(t) => {
+                map.serialize_entry("msgtype", "file")?;
+                map.serialize_entry("file", &File { media_id: t })?;
+            }
+        }
+        map.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_should_failover() {
+        assert!(should_failover(&Error::RequestFailed(93000, "".into())));
+        assert!(should_failover(&Error::from_errcode(45009, "".into())));
+        assert!(!should_failover(&Error::RequestFailed(40008, "".into())));
+
+        let client = WebhookClient::with_keys(vec!["a".into(), "b".into()]);
+        client.set_keys(vec!["c".into()]);
+        assert_eq!(client.keys(), vec!["c".to_string()]);
+    }
+
+    #[test]
+    fn test_serialize() {
+        let msg = WebhookMessage::Text(WebhookText {
+            content: "hello".into(),
+            mentioned_list: vec!["@all".into()],
+            ..Default::default()
+        });
+        let s = serde_json::to_string(&msg).unwrap();
+        assert_eq!(
+            s,
+            r#"{"msgtype":"text","text":{"content":"hello","mentioned_list":["@all"]}}"#
+        );
+
+        let s = serde_json::to_string(&WebhookMessage::markdown("**hi**")).unwrap();
+        assert_eq!(
+            s,
+            r#"{"msgtype":"markdown","markdown":{"content":"**hi**"}}"#
+        );
+    }
+}
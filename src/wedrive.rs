@@ -0,0 +1,670 @@
+#[cfg(feature = "native")]
+use serde::de::IgnoredAny;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "native")]
+use crate::client::Client;
+#[cfg(feature = "native")]
+use crate::page::Page;
+#[cfg(feature = "native")]
+use crate::{Error, Result};
+
+/// 空间或者文件的成员权限
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AuthInfo {
+    #[serde(rename = "type")]
+    pub ty: u8, // 1-成员, 2-部门
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub userid: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub departmentid: Option<u64>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth: Option<u8>, // 1-仅下载, 4-仅预览, 7-管理员, 删除权限时不需要指定
+}
+
+impl AuthInfo {
+    pub fn user(userid: impl ToString, auth: u8) -> Self {
+        AuthInfo {
+            ty: 1,
+            userid: Some(userid.to_string()),
+            auth: Some(auth),
+            ..Default::default()
+        }
+    }
+
+    pub fn department(departmentid: u64, auth: u8) -> Self {
+        AuthInfo {
+            ty: 2,
+            departmentid: Some(departmentid),
+            auth: Some(auth),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SpaceInfo {
+    pub spaceid: String,
+    pub space_name: String,
+    #[serde(default)]
+    pub auth_list: SpaceAuthList,
+    #[serde(default)]
+    pub space_sub_type: u8,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SpaceAuthList {
+    #[serde(default)]
+    pub auth_info: Vec<AuthInfo>,
+    #[serde(default)]
+    pub quit_userid: Vec<String>, // 已退出空间的成员
+}
+
+/// 空间的安全设置, 为 None 的字段不修改
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SpaceSetting {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enable_watermark: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub add_member_only_admin: Option<bool>, // 是否只允许管理员添加成员
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enable_share_url: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub share_url_no_approve: Option<bool>, // 通过链接加入空间是否无需审批
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub share_url_no_approve_default_auth: Option<u8>,
+}
+
+/// 微盘中的文件或者文件夹
+#[derive(Debug, Clone, Deserialize)]
+pub struct FileInfo {
+    pub fileid: String,
+    pub file_name: String,
+    #[serde(default)]
+    pub spaceid: String,
+    #[serde(default)]
+    pub fatherid: String,
+    #[serde(default)]
+    pub file_size: u64,
+    #[serde(default)]
+    pub ctime: u64,
+    #[serde(default)]
+    pub mtime: u64,
+    #[serde(default)]
+    pub file_type: u8, // 1-文件夹, 2-文件, 3-文档, 4-表格, 5-收集表
+    #[serde(default)]
+    pub file_status: u8, // 1-正常, 2-删除, 3-封禁
+    #[serde(default)]
+    pub create_userid: String,
+    #[serde(default)]
+    pub update_userid: String,
+    #[serde(default)]
+    pub sha: String,
+    #[serde(default)]
+    pub md5: String,
+    #[serde(default)]
+    pub url: String, // 文档和表格的访问链接
+}
+
+impl FileInfo {
+    pub fn is_folder(&self) -> bool {
+        self.file_type == 1
+    }
+}
+
+/// 文件的下载地址, 下载时需要带上 cookie
+#[derive(Debug, Clone, Deserialize)]
+pub struct FileDownload {
+    pub download_url: String,
+    pub cookie_name: String,
+    pub cookie_value: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreatedFile {
+    pub fileid: String,
+    #[serde(default)]
+    pub url: String,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct FileListRequest<'a> {
+    userid: &'a str,
+    spaceid: &'a str,
+    fatherid: &'a str,
+    sort_type: u8,
+    start: u32,
+    limit: u32,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct FileListResponse {
+    #[serde(default)]
+    has_more: bool,
+    #[serde(default)]
+    next_start: u32,
+    #[serde(default)]
+    file_list: FileItems,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Default, Deserialize)]
+struct FileItems {
+    #[serde(default)]
+    item: Vec<FileInfo>,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct FileUploadRequest<'a> {
+    userid: &'a str,
+    spaceid: &'a str,
+    fatherid: &'a str,
+    file_name: &'a str,
+    file_base64_content: String,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct FileCreateRequest<'a> {
+    userid: &'a str,
+    spaceid: &'a str,
+    fatherid: &'a str,
+    file_type: u8,
+    file_name: &'a str,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct FileRequest<'a> {
+    userid: &'a str,
+    fileid: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    new_name: Option<&'a str>,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct FilesRequest<'a> {
+    userid: &'a str,
+    fileid: &'a [&'a str],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fatherid: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    replace: Option<bool>,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct FileIdResponse {
+    fileid: String,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct FileResponse {
+    file: FileInfo,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct FileInfoResponse {
+    file_info: FileInfo,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct MoveFileResponse {
+    #[serde(default)]
+    file_list: FileItems,
+}
+
+#[cfg(feature = "native")]
+const FILE_LIST_LIMIT: u32 = 1000;
+
+/// 文件的分享设置
+#[derive(Debug, Clone, Serialize)]
+pub struct FileSetting {
+    pub auth_scope: u8, // 1-指定人, 2-企业内成员, 3-企业外成员, 4-企业内需管理员审批, 5-企业外需管理员审批
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth: Option<u8>, // 1-仅浏览, 2-可浏览可下载, 4-可编辑
+}
+
+/// 企业微盘的容量, 单位为字节
+#[derive(Debug, Clone, Deserialize)]
+pub struct CorpCapacity {
+    pub total_space: u64,
+    pub rest_space: u64,
+}
+
+impl CorpCapacity {
+    pub fn used_space(&self) -> u64 {
+        self.total_space.saturating_sub(self.rest_space)
+    }
+}
+
+/// 成员的微盘专业版信息
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProInfo {
+    #[serde(default)]
+    pub is_pro: bool,
+    #[serde(default)]
+    pub total_vip_acct_num: u32,
+    #[serde(default)]
+    pub use_vip_acct_num: u32,
+    #[serde(default)]
+    pub pro_expire_time: u64,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct FileAclRequest<'a> {
+    userid: &'a str,
+    fileid: &'a str,
+    auth_info: &'a [AuthInfo],
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct FileSettingRequest<'a> {
+    userid: &'a str,
+    fileid: &'a str,
+    #[serde(flatten)]
+    setting: &'a FileSetting,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct FileShareResponse {
+    share_url: String,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct ProInfoRequest<'a> {
+    userid: &'a str,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct SpaceCreateRequest<'a> {
+    userid: &'a str,
+    space_name: &'a str,
+    auth_info: &'a [AuthInfo],
+    space_sub_type: u8,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct SpaceRequest<'a> {
+    userid: &'a str,
+    spaceid: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    space_name: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    auth_info: Option<&'a [AuthInfo]>,
+}
+
+#[cfg(feature = "native")]
+impl<'a> SpaceRequest<'a> {
+    fn new(userid: &'a str, spaceid: &'a str) -> Self {
+        SpaceRequest {
+            userid,
+            spaceid,
+            space_name: None,
+            auth_info: None,
+        }
+    }
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Serialize)]
+struct SpaceSettingRequest<'a> {
+    userid: &'a str,
+    spaceid: &'a str,
+    #[serde(flatten)]
+    setting: &'a SpaceSetting,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct SpaceCreateResponse {
+    spaceid: String,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct SpaceInfoResponse {
+    space_info: SpaceInfo,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+struct SpaceShareResponse {
+    space_share_url: String,
+}
+
+/// 微盘空间, 所有操作都以成员 `userid` 的身份执行
+#[cfg(feature = "native")]
+impl Client {
+    /// 新建空间, 返回空间 id, `userid` 为空间的创建者
+    pub async fn wedrive_space_create(
+        &self,
+        userid: &str,
+        space_name: &str,
+        auth_info: &[AuthInfo],
+    ) -> Result<String> {
+        let req = SpaceCreateRequest {
+            userid,
+            space_name,
+            auth_info,
+            space_sub_type: 0,
+        };
+        let ret: SpaceCreateResponse = self.post("/cgi-bin/wedrive/space_create", &req).await?;
+        Ok(ret.spaceid)
+    }
+
+    pub async fn wedrive_space_rename(
+        &self,
+        userid: &str,
+        spaceid: &str,
+        space_name: &str,
+    ) -> Result<()> {
+        let req = SpaceRequest {
+            space_name: Some(space_name),
+            ..SpaceRequest::new(userid, spaceid)
+        };
+        let _: IgnoredAny = self.post("/cgi-bin/wedrive/space_rename", &req).await?;
+        Ok(())
+    }
+
+    /// 解散空间, 只有管理员可以操作
+    pub async fn wedrive_space_dismiss(&self, userid: &str, spaceid: &str) -> Result<()> {
+        let req = SpaceRequest::new(userid, spaceid);
+        let _: IgnoredAny = self.post("/cgi-bin/wedrive/space_dismiss", &req).await?;
+        Ok(())
+    }
+
+    pub async fn wedrive_space_info(&self, userid: &str, spaceid: &str) -> Result<SpaceInfo> {
+        let req = SpaceRequest::new(userid, spaceid);
+        let ret: SpaceInfoResponse = self.post("/cgi-bin/wedrive/space_info", &req).await?;
+        Ok(ret.space_info)
+    }
+
+    /// 添加空间成员或者修改成员的权限
+    pub async fn wedrive_space_acl_add(
+        &self,
+        userid: &str,
+        spaceid: &str,
+        auth_info: &[AuthInfo],
+    ) -> Result<()> {
+        let req = SpaceRequest {
+            auth_info: Some(auth_info),
+            ..SpaceRequest::new(userid, spaceid)
+        };
+        let _: IgnoredAny = self.post("/cgi-bin/wedrive/space_acl_add", &req).await?;
+        Ok(())
+    }
+
+    /// 移除空间成员, `auth_info` 中不需要指定 `auth`
+    pub async fn wedrive_space_acl_del(
+        &self,
+        userid: &str,
+        spaceid: &str,
+        auth_info: &[AuthInfo],
+    ) -> Result<()> {
+        let req = SpaceRequest {
+            auth_info: Some(auth_info),
+            ..SpaceRequest::new(userid, spaceid)
+        };
+        let _: IgnoredAny = self.post("/cgi-bin/wedrive/space_acl_del", &req).await?;
+        Ok(())
+    }
+
+    pub async fn wedrive_space_setting(
+        &self,
+        userid: &str,
+        spaceid: &str,
+        setting: &SpaceSetting,
+    ) -> Result<()> {
+        let req = SpaceSettingRequest {
+            userid,
+            spaceid,
+            setting,
+        };
+        let _: IgnoredAny = self.post("/cgi-bin/wedrive/space_setting", &req).await?;
+        Ok(())
+    }
+
+    /// 获取邀请加入空间的链接
+    pub async fn wedrive_space_share(&self, userid: &str, spaceid: &str) -> Result<String> {
+        let req = SpaceRequest::new(userid, spaceid);
+        let ret: SpaceShareResponse = self.post("/cgi-bin/wedrive/space_share", &req).await?;
+        Ok(ret.space_share_url)
+    }
+}
+
+/// 微盘文件, 所有操作都以成员 `userid` 的身份执行
+#[cfg(feature = "native")]
+impl Client {
+    /// 获取文件夹 `fatherid` 下的文件, 为空间根目录时 `fatherid` 为 spaceid, 按修改时间降序排列
+    ///
+    /// 接口以 start 翻页, 这里将 start 作为游标, 第一页传空字符串, 可以配合 `page::paginate` 使用
+    pub async fn wedrive_file_list(
+        &self,
+        userid: &str,
+        spaceid: &str,
+        fatherid: &str,
+        cursor: &str,
+    ) -> Result<Page<FileInfo>> {
+        let start = if cursor.is_empty() {
+            0
+        } else {
+            let e = |_| Error::InvalidResponse(format!("invalid cursor: {}", cursor));
+            cursor.parse().map_err(e)?
+        };
+        let req = FileListRequest {
+            userid,
+            spaceid,
+            fatherid,
+            sort_type: 4, // 修改时间降序
+            start,
+            limit: FILE_LIST_LIMIT,
+        };
+        let ret: FileListResponse = self.post("/cgi-bin/wedrive/file_list", &req).await?;
+        Ok(Page::new(
+            ret.file_list.item,
+            ret.next_start.to_string(),
+            !ret.has_more,
+        ))
+    }
+
+    /// 上传文件到文件夹 `fatherid`, 内容以 base64 编码传输, 文件不能超过 10M, 返回文件 id
+    pub async fn wedrive_file_upload(
+        &self,
+        userid: &str,
+        spaceid: &str,
+        fatherid: &str,
+        file_name: &str,
+        data: &[u8],
+    ) -> Result<String> {
+        let req = FileUploadRequest {
+            userid,
+            spaceid,
+            fatherid,
+            file_name,
+            file_base64_content: base64::encode(data),
+        };
+        let ret: FileIdResponse = self.post("/cgi-bin/wedrive/file_upload", &req).await?;
+        Ok(ret.fileid)
+    }
+
+    /// 获取文件的下载地址
+    pub async fn wedrive_file_download(&self, userid: &str, fileid: &str) -> Result<FileDownload> {
+        let req = FileRequest {
+            userid,
+            fileid,
+            new_name: None,
+        };
+        self.post("/cgi-bin/wedrive/file_download", &req).await
+    }
+
+    /// 下载文件的内容
+    pub async fn wedrive_file_download_data(&self, userid: &str, fileid: &str) -> Result<Vec<u8>> {
+        let d = self.wedrive_file_download(userid, fileid).await?;
+        self.download_with_cookie(&d.download_url, &d.cookie_name, &d.cookie_value)
+            .await
+    }
+
+    /// 新建文件夹, 文档或者表格, `file_type` 1-文件夹, 3-文档, 4-表格
+    pub async fn wedrive_file_create(
+        &self,
+        userid: &str,
+        spaceid: &str,
+        fatherid: &str,
+        file_type: u8,
+        file_name: &str,
+    ) -> Result<CreatedFile> {
+        let req = FileCreateRequest {
+            userid,
+            spaceid,
+            fatherid,
+            file_type,
+            file_name,
+        };
+        self.post("/cgi-bin/wedrive/file_create", &req).await
+    }
+
+    pub async fn wedrive_file_rename(
+        &self,
+        userid: &str,
+        fileid: &str,
+        new_name: &str,
+    ) -> Result<FileInfo> {
+        let req = FileRequest {
+            userid,
+            fileid,
+            new_name: Some(new_name),
+        };
+        let ret: FileResponse = self.post("/cgi-bin/wedrive/file_rename", &req).await?;
+        Ok(ret.file)
+    }
+
+    /// 移动文件到文件夹 `fatherid`, `replace` 为 true 时覆盖同名文件, 否则自动重命名
+    pub async fn wedrive_file_move(
+        &self,
+        userid: &str,
+        fileid: &[&str],
+        fatherid: &str,
+        replace: bool,
+    ) -> Result<Vec<FileInfo>> {
+        let req = FilesRequest {
+            userid,
+            fileid,
+            fatherid: Some(fatherid),
+            replace: Some(replace),
+        };
+        let ret: MoveFileResponse = self.post("/cgi-bin/wedrive/file_move", &req).await?;
+        Ok(ret.file_list.item)
+    }
+
+    pub async fn wedrive_file_delete(&self, userid: &str, fileid: &[&str]) -> Result<()> {
+        let req = FilesRequest {
+            userid,
+            fileid,
+            fatherid: None,
+            replace: None,
+        };
+        let _: IgnoredAny = self.post("/cgi-bin/wedrive/file_delete", &req).await?;
+        Ok(())
+    }
+
+    pub async fn wedrive_file_info(&self, userid: &str, fileid: &str) -> Result<FileInfo> {
+        let req = FileRequest {
+            userid,
+            fileid,
+            new_name: None,
+        };
+        let ret: FileInfoResponse = self.post("/cgi-bin/wedrive/file_info", &req).await?;
+        Ok(ret.file_info)
+    }
+
+    /// 添加文件的指定成员或部门
+    pub async fn wedrive_file_acl_add(
+        &self,
+        userid: &str,
+        fileid: &str,
+        auth_info: &[AuthInfo],
+    ) -> Result<()> {
+        let req = FileAclRequest {
+            userid,
+            fileid,
+            auth_info,
+        };
+        let _: IgnoredAny = self.post("/cgi-bin/wedrive/file_acl_add", &req).await?;
+        Ok(())
+    }
+
+    /// 移除文件的指定成员或部门, `auth_info` 中不需要指定 `auth`
+    pub async fn wedrive_file_acl_del(
+        &self,
+        userid: &str,
+        fileid: &str,
+        auth_info: &[AuthInfo],
+    ) -> Result<()> {
+        let req = FileAclRequest {
+            userid,
+            fileid,
+            auth_info,
+        };
+        let _: IgnoredAny = self.post("/cgi-bin/wedrive/file_acl_del", &req).await?;
+        Ok(())
+    }
+
+    pub async fn wedrive_file_setting(
+        &self,
+        userid: &str,
+        fileid: &str,
+        setting: &FileSetting,
+    ) -> Result<()> {
+        let req = FileSettingRequest {
+            userid,
+            fileid,
+            setting,
+        };
+        let _: IgnoredAny = self.post("/cgi-bin/wedrive/file_setting", &req).await?;
+        Ok(())
+    }
+
+    /// 获取文件的分享链接
+    pub async fn wedrive_file_share(&self, userid: &str, fileid: &str) -> Result<String> {
+        let req = FileRequest {
+            userid,
+            fileid,
+            new_name: None,
+        };
+        let ret: FileShareResponse = self.post("/cgi-bin/wedrive/file_share", &req).await?;
+        Ok(ret.share_url)
+    }
+}
+
+/// 微盘容量管理
+#[cfg(feature = "native")]
+impl Client {
+    /// 获取企业微盘的总容量和剩余容量
+    pub async fn wedrive_get_corp_info(&self) -> Result<CorpCapacity> {
+        let req = serde_json::json!({});
+        self.post("/cgi-bin/wedrive/get_corp_info", &req).await
+    }
+
+    /// 获取成员的专业版信息
+    pub async fn wedrive_mng_pro_info(&self, userid: &str) -> Result<ProInfo> {
+        let req = ProInfoRequest { userid };
+        self.post("/cgi-bin/wedrive/mng_pro_info", &req).await
+    }
+}
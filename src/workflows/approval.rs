@@ -0,0 +1,336 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use log::{info, warn};
+
+use crate::client::Client;
+use crate::message::MessageBuilder;
+use crate::server::{App, Event, RecvMessage, RecvMessageType, SendMessage};
+use crate::template_card::TemplateCardBuilder;
+use crate::Result;
+
+use super::MessageApi;
+
+const APPROVE_KEY: &str = "approval_approve";
+const REJECT_KEY: &str = "approval_reject";
+
+/// 需要审批人通过卡片按钮确认的请求
+#[derive(Debug, Clone)]
+pub struct ButtonApprovalRequest {
+    pub title: String,
+    pub description: String,
+    pub approvers: Vec<String>, // 任意一人处理即可
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decision {
+    Approved,
+    Rejected,
+}
+
+impl Decision {
+    fn from_key(key: &str) -> Option<Decision> {
+        match key {
+            APPROVE_KEY => Some(Decision::Approved),
+            REJECT_KEY => Some(Decision::Rejected),
+            _ => None,
+        }
+    }
+
+    // shown on the button after the card is updated
+    fn label(&self) -> &'static str {
+        match self {
+            Decision::Approved => "已同意",
+            Decision::Rejected => "已驳回",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ButtonApprovalOutcome {
+    pub task_id: String,
+    pub request: ButtonApprovalRequest,
+    pub decision: Decision,
+    pub decided_by: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditAction {
+    Requested,
+    Decided(Decision),
+}
+
+/// 审计日志中的一条记录
+#[derive(Debug, Clone)]
+pub struct AuditEntry {
+    pub task_id: String,
+    pub action: AuditAction,
+    pub operator: String, // 发起时为空, 处理时为审批人的 userid
+    pub at: SystemTime,
+}
+
+pub trait AuditLog: Send + Sync + 'static {
+    fn record(&self, entry: AuditEntry);
+}
+
+/// 保存在内存中的审计日志, 主要用于测试
+#[derive(Debug, Default)]
+pub struct MemoryAuditLog {
+    entries: Mutex<Vec<AuditEntry>>,
+}
+
+impl MemoryAuditLog {
+    pub fn entries(&self) -> Vec<AuditEntry> {
+        self.entries.lock().unwrap().clone()
+    }
+}
+
+impl AuditLog for MemoryAuditLog {
+    fn record(&self, entry: AuditEntry) {
+        self.entries.lock().unwrap().push(entry);
+    }
+}
+
+/// `ButtonApproval` 调用的接口, 默认由 `Client` 实现
+#[async_trait]
+pub trait ApprovalApi: MessageApi {
+    async fn update_template_card_button(
+        &self,
+        agent_id: u64,
+        response_code: &str,
+        userids: &[&str],
+        replace_name: &str,
+    ) -> Result<()>;
+}
+
+#[async_trait]
+impl ApprovalApi for Client {
+    async fn update_template_card_button(
+        &self,
+        agent_id: u64,
+        response_code: &str,
+        userids: &[&str],
+        replace_name: &str,
+    ) -> Result<()> {
+        Client::update_template_card_button(self, agent_id, response_code, userids, replace_name)
+            .await
+    }
+}
+
+/// 按钮审批
+///
+/// 向审批人发送按钮交互型模板卡片, 收到 `template_card_event` 回调后把所有审批人的卡片更新为
+/// 不可点击的状态, 并在审计日志中记录发起和处理的过程. 待审批的请求只保存在内存中.
+pub struct ButtonApproval<L: AuditLog, C: ApprovalApi = Client> {
+    client: C,
+    agent_id: u64,
+    audit: L,
+    pending: Mutex<HashMap<String, ButtonApprovalRequest>>, // task_id -> request
+    seq: AtomicU64,
+}
+
+impl<L: AuditLog, C: ApprovalApi> ButtonApproval<L, C> {
+    pub fn new(client: C, agent_id: u64, audit: L) -> Self {
+        ButtonApproval {
+            client,
+            agent_id,
+            audit,
+            pending: Mutex::new(HashMap::new()),
+            seq: AtomicU64::new(0),
+        }
+    }
+
+    pub fn audit(&self) -> &L {
+        &self.audit
+    }
+
+    /// 发起审批, 返回卡片的 task_id
+    pub async fn request(&self, req: ButtonApprovalRequest) -> Result<String> {
+        let task_id = task_id(self.seq.fetch_add(1, Ordering::Relaxed));
+        let card = TemplateCardBuilder::button_interaction(&req.title)
+            .sub_title(&req.description)
+            .task_id(&task_id)
+            .button("同意", 1, APPROVE_KEY)
+            .button("驳回", 2, REJECT_KEY)
+            .build()?;
+        let mut builder = MessageBuilder::new_template_card(self.agent_id, card);
+        for approver in &req.approvers {
+            builder = builder.with_user(approver.clone());
+        }
+        self.client.send_msg(&builder.build()?).await?;
+
+        info!("approval {} requested", task_id);
+        self.audit.record(AuditEntry {
+            task_id: task_id.clone(),
+            action: AuditAction::Requested,
+            operator: String::new(),
+            at: SystemTime::now(),
+        });
+        self.pending.lock().unwrap().insert(task_id.clone(), req);
+        Ok(task_id)
+    }
+
+    /// 处理回调消息, 与待审批请求无关的消息返回 None
+    pub async fn process(&self, msg: &RecvMessage) -> Result<Option<ButtonApprovalOutcome>> {
+        let e = match &msg.msg_ty {
+            RecvMessageType::Event(Event::TemplateCardEvent(e)) => e,
+            _ => return Ok(None),
+        };
+        let decision = match Decision::from_key(&e.event_key) {
+            Some(d) => d,
+            None => return Ok(None),
+        };
+        let request = match self.pending.lock().unwrap().remove(&e.task_id) {
+            Some(r) => r,
+            None => return Ok(None),
+        };
+
+        // the decision stands even if the card can not be updated
+        let approvers: Vec<&str> = request.approvers.iter().map(|s| s.as_str()).collect();
+        if let Err(err) = self
+            .client
+            .update_template_card_button(
+                self.agent_id,
+                &e.response_code,
+                &approvers,
+                decision.label(),
+            )
+            .await
+        {
+            warn!("update approval card {} failed: {}", e.task_id, err);
+        }
+
+        info!(
+            "approval {} {:?} by {}",
+            e.task_id, decision, msg.from_user_name
+        );
+        self.audit.record(AuditEntry {
+            task_id: e.task_id.clone(),
+            action: AuditAction::Decided(decision),
+            operator: msg.from_user_name.clone(),
+            at: SystemTime::now(),
+        });
+        Ok(Some(ButtonApprovalOutcome {
+            task_id: e.task_id.clone(),
+            request,
+            decision,
+            decided_by: msg.from_user_name.clone(),
+        }))
+    }
+
+    pub fn pending(&self) -> Vec<(String, ButtonApprovalRequest)> {
+        let pending = self.pending.lock().unwrap();
+        pending
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect()
+    }
+}
+
+#[async_trait]
+impl<L: AuditLog, C: ApprovalApi> App for ButtonApproval<L, C> {
+    async fn handle(&self, msg: RecvMessage) -> Option<SendMessage> {
+        if let Err(e) = self.process(&msg).await {
+            warn!("handle approval event failed: {}", e);
+        }
+        None
+    }
+}
+
+fn task_id(seq: u64) -> String {
+    let ts = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    format!("approval-{}-{}", ts, seq)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workflows::testing::{card_callback, MockApi, AGENT_ID};
+
+    #[test]
+    fn test_decision() {
+        assert_eq!(Decision::from_key(APPROVE_KEY), Some(Decision::Approved));
+        assert_eq!(Decision::from_key(REJECT_KEY), Some(Decision::Rejected));
+        assert_eq!(Decision::from_key("inherit_approve"), None);
+
+        assert!(TemplateCardBuilder::button_interaction("t")
+            .task_id(task_id(1))
+            .button("ok", 1, APPROVE_KEY)
+            .build()
+            .is_ok());
+    }
+
+    fn request() -> ButtonApprovalRequest {
+        ButtonApprovalRequest {
+            title: "上线申请".to_string(),
+            description: "v1.2.0".to_string(),
+            approvers: vec!["zhangsan".to_string(), "lisi".to_string()],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_approve_flow() {
+        let approval = ButtonApproval::new(MockApi::default(), AGENT_ID, MemoryAuditLog::default());
+        let task_id = approval.request(request()).await.unwrap();
+        let sent = approval.client.sent.lock().unwrap().clone();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0]["touser"], "zhangsan|lisi");
+        assert_eq!(approval.pending().len(), 1);
+
+        let msg = card_callback("lisi", &task_id, APPROVE_KEY);
+        let outcome = approval.process(&msg).await.unwrap().unwrap();
+        assert_eq!(outcome.decision, Decision::Approved);
+        assert_eq!(outcome.decided_by, "lisi");
+        assert!(approval.pending().is_empty());
+
+        let updated = approval.client.updated.lock().unwrap().clone();
+        assert_eq!(updated.len(), 1);
+        assert_eq!(updated[0].0, "code");
+        assert_eq!(updated[0].1, vec!["zhangsan", "lisi"]);
+        assert_eq!(updated[0].2, Decision::Approved.label());
+
+        let entries = approval.audit().entries();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].action, AuditAction::Requested);
+        assert_eq!(entries[1].action, AuditAction::Decided(Decision::Approved));
+        assert_eq!(entries[1].operator, "lisi");
+
+        // the card can only be decided once
+        assert!(approval.process(&msg).await.unwrap().is_none());
+        assert_eq!(approval.audit().entries().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_reject_flow() {
+        let approval = ButtonApproval::new(MockApi::default(), AGENT_ID, MemoryAuditLog::default());
+        let task_id = approval.request(request()).await.unwrap();
+
+        let msg = card_callback("zhangsan", &task_id, REJECT_KEY);
+        let outcome = approval.process(&msg).await.unwrap().unwrap();
+        assert_eq!(outcome.decision, Decision::Rejected);
+
+        let updated = approval.client.updated.lock().unwrap().clone();
+        assert_eq!(updated[0].2, Decision::Rejected.label());
+        let entries = approval.audit().entries();
+        assert_eq!(entries[1].action, AuditAction::Decided(Decision::Rejected));
+        assert_eq!(entries[1].operator, "zhangsan");
+    }
+
+    #[tokio::test]
+    async fn test_unknown_task() {
+        let approval = ButtonApproval::new(MockApi::default(), AGENT_ID, MemoryAuditLog::default());
+        approval.request(request()).await.unwrap();
+
+        let msg = card_callback("zhangsan", "approval-0-42", APPROVE_KEY);
+        assert!(approval.process(&msg).await.unwrap().is_none());
+        assert!(approval.client.updated.lock().unwrap().is_empty());
+        assert_eq!(approval.audit().entries().len(), 1);
+        assert_eq!(approval.pending().len(), 1);
+    }
+}
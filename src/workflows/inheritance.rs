@@ -0,0 +1,334 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use futures::future;
+use futures::TryStreamExt;
+use log::{info, warn};
+
+use crate::client::Client;
+use crate::externalcontact::{CustomerTransferResult, UnassignedCustomer};
+use crate::message::MessageBuilder;
+use crate::page::{paginate, Page};
+use crate::poll::retry_rate_limited;
+use crate::server::{App, ContactChange, Event, RecvMessage, RecvMessageType, SendMessage};
+use crate::template_card::TemplateCardBuilder;
+use crate::Result;
+
+use super::MessageApi;
+
+const APPROVE_KEY: &str = "inherit_approve";
+const REJECT_KEY: &str = "inherit_reject";
+const TRANSFER_BATCH: usize = 100;
+
+/// 离职成员的客户由谁接替, 以及由谁审批
+#[derive(Debug, Clone)]
+pub struct Assignment {
+    pub takeover_userid: String,
+    pub approver: String,
+}
+
+#[async_trait]
+pub trait InheritancePlanner: Send + Sync + 'static {
+    /// 为离职成员安排接替人, 返回 None 表示不处理该成员的客户
+    async fn plan(&self, handover_userid: &str) -> Option<Assignment>;
+}
+
+/// `InheritanceBot` 调用的接口, 默认由 `Client` 实现
+#[async_trait]
+pub trait InheritanceApi: MessageApi {
+    async fn get_unassigned_list(
+        &self,
+        cursor: &str,
+        page_size: u32,
+    ) -> Result<Page<UnassignedCustomer>>;
+
+    async fn resigned_transfer_customer(
+        &self,
+        handover_userid: &str,
+        takeover_userid: &str,
+        external_userid: &[&str],
+    ) -> Result<Vec<CustomerTransferResult>>;
+}
+
+#[async_trait]
+impl InheritanceApi for Client {
+    async fn get_unassigned_list(
+        &self,
+        cursor: &str,
+        page_size: u32,
+    ) -> Result<Page<UnassignedCustomer>> {
+        Client::get_unassigned_list(self, cursor, page_size).await
+    }
+
+    async fn resigned_transfer_customer(
+        &self,
+        handover_userid: &str,
+        takeover_userid: &str,
+        external_userid: &[&str],
+    ) -> Result<Vec<CustomerTransferResult>> {
+        Client::resigned_transfer_customer(self, handover_userid, takeover_userid, external_userid)
+            .await
+    }
+}
+
+/// 等待审批的继承任务
+#[derive(Debug, Clone)]
+pub struct InheritanceTask {
+    pub task_id: String,
+    pub handover_userid: String,
+    pub assignment: Assignment,
+    pub external_userids: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub enum InheritanceOutcome {
+    Requested(InheritanceTask), // 已向审批人发送卡片
+    Transferred(InheritanceTask, Vec<CustomerTransferResult>),
+    Rejected(InheritanceTask),
+}
+
+/// 离职继承机器人
+///
+/// 收到 `delete_user` 通讯录事件后, 查询该成员待分配的客户, 向审批人发送按钮交互型模板卡片,
+/// 审批人同意后将客户分配给接替成员. 需要同时接收通讯录回调和应用回调.
+/// 待审批的任务只保存在内存中, 服务重启后需要重新发起. 分配失败时任务保留, 审批人可以再次点击同意重试.
+pub struct InheritanceBot<P: InheritancePlanner, C: InheritanceApi = Client> {
+    client: C,
+    agent_id: u64,
+    planner: P,
+    pending: Mutex<HashMap<String, InheritanceTask>>, // task_id -> task
+    seq: AtomicU64,
+}
+
+impl<P: InheritancePlanner, C: InheritanceApi> InheritanceBot<P, C> {
+    /// `agent_id` 为发送审批卡片的应用
+    pub fn new(client: C, agent_id: u64, planner: P) -> Self {
+        InheritanceBot {
+            client,
+            agent_id,
+            planner,
+            pending: Mutex::new(HashMap::new()),
+            seq: AtomicU64::new(0),
+        }
+    }
+
+    /// 处理回调消息, 与离职继承无关的消息返回 None
+    pub async fn process(&self, msg: &RecvMessage) -> Result<Option<InheritanceOutcome>> {
+        match &msg.msg_ty {
+            RecvMessageType::Event(Event::ChangeContact(ContactChange {
+                change_type,
+                user_id,
+            })) if change_type == "delete_user" => self.request(user_id).await,
+            RecvMessageType::Event(Event::TemplateCardEvent(e)) => {
+                let task = match self.pending.lock().unwrap().remove(&e.task_id) {
+                    Some(task) => task,
+                    None => return Ok(None),
+                };
+                match &*e.event_key {
+                    APPROVE_KEY => {
+                        let mut rest = task.clone();
+                        match self.transfer(&mut rest).await {
+                            Ok(results) => Ok(Some(InheritanceOutcome::Transferred(task, results))),
+                            Err(err) => {
+                                // keep the customers not transferred yet so that it can be retried
+                                self.pending.lock().unwrap().insert(e.task_id.clone(), rest);
+                                Err(err)
+                            }
+                        }
+                    }
+                    _ => Ok(Some(InheritanceOutcome::Rejected(task))),
+                }
+            }
+            _ => Ok(None),
+        }
+    }
+
+    pub fn pending(&self) -> Vec<InheritanceTask> {
+        self.pending.lock().unwrap().values().cloned().collect()
+    }
+
+    async fn request(&self, handover_userid: &str) -> Result<Option<InheritanceOutcome>> {
+        let external_userids = self.unassigned_customers(handover_userid).await?;
+        if external_userids.is_empty() {
+            return Ok(None);
+        }
+        let assignment = match self.planner.plan(handover_userid).await {
+            Some(a) => a,
+            None => return Ok(None),
+        };
+
+        let task = InheritanceTask {
+            task_id: task_id(handover_userid, self.seq.fetch_add(1, Ordering::Relaxed)),
+            handover_userid: handover_userid.to_string(),
+            assignment,
+            external_userids,
+        };
+        let card = TemplateCardBuilder::button_interaction("离职成员客户继承")
+            .sub_title(format!(
+                "{} 的 {} 位客户将分配给 {}",
+                task.handover_userid,
+                task.external_userids.len(),
+                task.assignment.takeover_userid
+            ))
+            .task_id(&task.task_id)
+            .button("同意", 1, APPROVE_KEY)
+            .button("驳回", 2, REJECT_KEY)
+            .build()?;
+        let msg = MessageBuilder::new_template_card(self.agent_id, card)
+            .with_user(task.assignment.approver.clone())
+            .build()?;
+        self.client.send_msg(&msg).await?;
+
+        info!("inheritance task {} requested", task.task_id);
+        self.pending
+            .lock()
+            .unwrap()
+            .insert(task.task_id.clone(), task.clone());
+        Ok(Some(InheritanceOutcome::Requested(task)))
+    }
+
+    async fn unassigned_customers(&self, handover_userid: &str) -> Result<Vec<String>> {
+        let client = &self.client;
+        paginate(|cursor| async move { client.get_unassigned_list(&cursor, 1000).await })
+            .try_filter(|c| future::ready(c.handover_userid == handover_userid))
+            .map_ok(|c| c.external_userid)
+            .try_collect()
+            .await
+    }
+
+    // transferred customers are removed from `task`, so a failed task only keeps the rest
+    async fn transfer(&self, task: &mut InheritanceTask) -> Result<Vec<CustomerTransferResult>> {
+        let mut ret = vec![];
+        while !task.external_userids.is_empty() {
+            let n = task.external_userids.len().min(TRANSFER_BATCH);
+            let ids: Vec<&str> = task.external_userids[..n]
+                .iter()
+                .map(|s| s.as_str())
+                .collect();
+            let results = retry_rate_limited(|| {
+                self.client.resigned_transfer_customer(
+                    &task.handover_userid,
+                    &task.assignment.takeover_userid,
+                    &ids,
+                )
+            })
+            .await?;
+            ret.extend(results);
+            task.external_userids.drain(..n);
+        }
+        info!("inheritance task {} transferred", task.task_id);
+        Ok(ret)
+    }
+}
+
+#[async_trait]
+impl<P: InheritancePlanner, C: InheritanceApi> App for InheritanceBot<P, C> {
+    async fn handle(&self, msg: RecvMessage) -> Option<SendMessage> {
+        if let Err(e) = self.process(&msg).await {
+            warn!("handle inheritance event failed: {}", e);
+        }
+        None
+    }
+}
+
+// userid may contain '.', which is not allowed in task_id
+fn task_id(handover_userid: &str, seq: u64) -> String {
+    let ts = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let name: String = handover_userid
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '_' | '-' | '@' => c,
+            _ => '_',
+        })
+        .take(96)
+        .collect();
+    format!("inherit-{}-{}-{}", name, ts, seq)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workflows::testing::{card_callback, event, MockApi, AGENT_ID};
+
+    #[test]
+    fn test_task_id() {
+        let id = task_id("zhang.san", 1);
+        assert!(id.starts_with("inherit-zhang_san-"));
+        assert_ne!(id, task_id("zhang.san", 2));
+        assert!(TemplateCardBuilder::button_interaction("t")
+            .task_id(&id)
+            .button("ok", 1, "ok")
+            .build()
+            .is_ok());
+    }
+
+    struct Planner;
+
+    #[async_trait]
+    impl InheritancePlanner for Planner {
+        async fn plan(&self, _handover_userid: &str) -> Option<Assignment> {
+            Some(Assignment {
+                takeover_userid: "lisi".to_string(),
+                approver: "boss".to_string(),
+            })
+        }
+    }
+
+    // request a task for zhangsan and return the approve event of its card
+    async fn request_task<C: InheritanceApi>(bot: &InheritanceBot<Planner, C>) -> RecvMessage {
+        let delete = event(
+            "sys",
+            Event::ChangeContact(ContactChange {
+                change_type: "delete_user".to_string(),
+                user_id: "zhangsan".to_string(),
+            }),
+        );
+        let task = match bot.process(&delete).await.unwrap() {
+            Some(InheritanceOutcome::Requested(task)) => task,
+            o => panic!("unexpected outcome: {:?}", o),
+        };
+        card_callback("boss", &task.task_id, APPROVE_KEY)
+    }
+
+    #[tokio::test]
+    async fn test_transfer_failure_keeps_task() {
+        let bot = InheritanceBot::new(MockApi::with_customers(1, Some(0)), AGENT_ID, Planner);
+        let approve = request_task(&bot).await;
+        assert!(bot.process(&approve).await.is_err());
+        assert_eq!(bot.pending().len(), 1);
+
+        match bot.process(&approve).await.unwrap() {
+            Some(InheritanceOutcome::Transferred(_, results)) => assert_eq!(results.len(), 1),
+            o => panic!("unexpected outcome: {:?}", o),
+        }
+        assert!(bot.pending().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_partial_transfer_failure() {
+        let bot = InheritanceBot::new(MockApi::with_customers(150, Some(1)), AGENT_ID, Planner);
+        let approve = request_task(&bot).await;
+
+        // the first batch succeeds, only the second one is kept
+        assert!(bot.process(&approve).await.is_err());
+        assert_eq!(bot.client.transferred.lock().unwrap().len(), TRANSFER_BATCH);
+        assert_eq!(bot.pending()[0].external_userids.len(), 50);
+
+        match bot.process(&approve).await.unwrap() {
+            Some(InheritanceOutcome::Transferred(_, results)) => assert_eq!(results.len(), 50),
+            o => panic!("unexpected outcome: {:?}", o),
+        }
+        // nothing is transferred twice
+        let mut transferred = bot.client.transferred.lock().unwrap().clone();
+        assert_eq!(transferred.len(), 150);
+        transferred.sort();
+        transferred.dedup();
+        assert_eq!(transferred.len(), 150);
+    }
+}
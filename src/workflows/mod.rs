@@ -0,0 +1,29 @@
+//! 由多个接口组合而成的常用业务流程
+
+use async_trait::async_trait;
+
+use crate::client::Client;
+use crate::message::Message;
+use crate::Result;
+
+mod approval;
+mod inheritance;
+#[cfg(test)]
+mod testing;
+
+pub use approval::*;
+pub use inheritance::*;
+
+/// 业务流程发送应用消息的接口, 默认由 `Client` 实现
+#[async_trait]
+pub trait MessageApi: Send + Sync + 'static {
+    async fn send_msg(&self, msg: &Message) -> Result<()>;
+}
+
+#[async_trait]
+impl MessageApi for Client {
+    async fn send_msg(&self, msg: &Message) -> Result<()> {
+        Client::send_msg(self, msg).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,141 @@
+// scaffolding shared by the workflow tests
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+
+use crate::externalcontact::{CustomerTransferResult, UnassignedCustomer};
+use crate::message::Message;
+use crate::page::Page;
+use crate::server::crypto::{Crypto, Payload};
+use crate::server::{EncryptedEnvelope, Event, PlainMessage, RecvMessage, RecvMessageType};
+use crate::{Error, Result};
+
+use super::{ApprovalApi, InheritanceApi, MessageApi};
+
+pub(super) const AGENT_ID: u64 = 1000002;
+const CORP_ID: &str = "ww1234";
+
+// records the calls, `customers` unassigned customers of zhangsan, the `failing_call`th transfer fails
+#[derive(Default)]
+pub(super) struct MockApi {
+    pub sent: Mutex<Vec<serde_json::Value>>,
+    pub updated: Mutex<Vec<(String, Vec<String>, String)>>, // response_code, userids, replace_name
+    pub transferred: Mutex<Vec<String>>,
+    customers: usize,
+    failing_call: Option<u32>,
+    calls: AtomicU32,
+}
+
+impl MockApi {
+    pub fn with_customers(customers: usize, failing_call: Option<u32>) -> Self {
+        MockApi {
+            customers,
+            failing_call,
+            ..Default::default()
+        }
+    }
+}
+
+#[async_trait]
+impl MessageApi for MockApi {
+    async fn send_msg(&self, msg: &Message) -> Result<()> {
+        self.sent.lock().unwrap().push(serde_json::to_value(msg)?);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ApprovalApi for MockApi {
+    async fn update_template_card_button(
+        &self,
+        _agent_id: u64,
+        response_code: &str,
+        userids: &[&str],
+        replace_name: &str,
+    ) -> Result<()> {
+        let userids = userids.iter().map(|s| s.to_string()).collect();
+        let update = (response_code.to_string(), userids, replace_name.to_string());
+        self.updated.lock().unwrap().push(update);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl InheritanceApi for MockApi {
+    async fn get_unassigned_list(
+        &self,
+        _cursor: &str,
+        _page_size: u32,
+    ) -> Result<Page<UnassignedCustomer>> {
+        let customers = (0..self.customers)
+            .map(|i| UnassignedCustomer {
+                handover_userid: "zhangsan".to_string(),
+                external_userid: format!("wm{}", i),
+                dimission_time: 0,
+            })
+            .collect();
+        Ok(Page::new(customers, String::new(), true))
+    }
+
+    async fn resigned_transfer_customer(
+        &self,
+        _handover_userid: &str,
+        _takeover_userid: &str,
+        external_userid: &[&str],
+    ) -> Result<Vec<CustomerTransferResult>> {
+        if Some(self.calls.fetch_add(1, Ordering::SeqCst)) == self.failing_call {
+            return Err(Error::RequestFailed(
+                40098,
+                "member not resigned".to_string(),
+            ));
+        }
+        let mut transferred = self.transferred.lock().unwrap();
+        transferred.extend(external_userid.iter().map(|id| id.to_string()));
+        let ret = external_userid
+            .iter()
+            .map(|id| CustomerTransferResult {
+                external_userid: id.to_string(),
+                errcode: 0,
+            })
+            .collect();
+        Ok(ret)
+    }
+}
+
+pub(super) fn event(from: &str, event: Event) -> RecvMessage {
+    RecvMessage {
+        to_user_name: CORP_ID.to_string(),
+        from_user_name: from.to_string(),
+        agent_id: AGENT_ID,
+        create_time: 0,
+        msg_id: 0,
+        msg_ty: RecvMessageType::Event(event),
+    }
+}
+
+// the template_card_event callback as the server receives it: encrypted, signed and then parsed
+pub(super) fn card_callback(from: &str, task_id: &str, event_key: &str) -> RecvMessage {
+    let plain = format!(
+        "<xml><ToUserName><![CDATA[{}]]></ToUserName><FromUserName><![CDATA[{}]]></FromUserName>\
+         <CreateTime>1645000000</CreateTime><MsgType><![CDATA[event]]></MsgType>\
+         <Event><![CDATA[template_card_event]]></Event><EventKey><![CDATA[{}]]></EventKey>\
+         <TaskId><![CDATA[{}]]></TaskId><CardType><![CDATA[button_interaction]]></CardType>\
+         <ResponseCode><![CDATA[code]]></ResponseCode><AgentID>{}</AgentID></xml>",
+        CORP_ID, from, event_key, task_id, AGENT_ID
+    );
+    let crypto = Crypto::new("QDG6eK", "4Ma3YBrSBbX2aez8MJpXGBne5LSDwgGqHbhM9WPYIws").unwrap();
+    let encrypt = crypto.encrypt(&Payload {
+        data: plain.into_bytes(),
+        receiver_id: Vec::from(CORP_ID),
+    });
+    let sign = crypto.sign(encrypt.clone(), 1645000000, 42);
+    let outer = format!(
+        "<xml><ToUserName><![CDATA[{}]]></ToUserName><AgentID><![CDATA[{}]]></AgentID><Encrypt><![CDATA[{}]]></Encrypt></xml>",
+        CORP_ID, AGENT_ID, encrypt
+    );
+
+    let envelope = EncryptedEnvelope::parse(&outer).unwrap();
+    envelope.verify(&crypto, 1645000000, 42, &sign).unwrap();
+    PlainMessage::parse(envelope.decrypt(&crypto).unwrap()).unwrap()
+}